@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use srex::srecord::{DataChunk, OverlapPolicy, Pipeline, SRecordFile};
+
+fn bench_pipeline_merge(c: &mut Criterion) {
+    let num_files: u64 = 100;
+    let num_data_chunks: u64 = 10000;
+    // Chunks of all files are interleaved, with a gap between each chunk
+    let srecord_files = || {
+        (0..num_files)
+            .map(|file_idx| {
+                let mut srecord_file = SRecordFile::new();
+                srecord_file.data_chunks = (0..num_data_chunks)
+                    .map(|chunk_idx| DataChunk {
+                        address: (chunk_idx * num_files + file_idx) * 32,
+                        data: vec![file_idx as u8; 16],
+                    })
+                    .collect();
+                srecord_file
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut merge_group = c.benchmark_group("Merge");
+    merge_group.sample_size(10);
+    merge_group.bench_function("100 files, 10000 chunks/file", |b| {
+        b.iter_batched(
+            srecord_files,
+            |srecord_files| {
+                let mut srecord_files = srecord_files.into_iter();
+                let mut pipeline = Pipeline::load(srecord_files.next().unwrap());
+                for srecord_file in srecord_files {
+                    pipeline = pipeline.merge(srecord_file, OverlapPolicy::Error);
+                }
+                pipeline.run().unwrap()
+            },
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = bench_pipeline_merge,
+}
+criterion_main!(benches);
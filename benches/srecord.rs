@@ -3,7 +3,7 @@ use std::str::FromStr;
 use criterion::{criterion_group, criterion_main, Criterion};
 
 use srex::srecord::utils::calculate_checksum;
-use srex::srecord::SRecordFile;
+use srex::srecord::{DataChunk, GapFill, ParseOptions, SRecordFile};
 
 fn bench_calculate_checksum(c: &mut Criterion) {
     let mut group = c.benchmark_group("calculate_checksum");
@@ -83,9 +83,111 @@ fn bench_from_str_data_chunks(c: &mut Criterion) {
     );
 }
 
+fn bench_from_str_fragmented(c: &mut Criterion) {
+    let num_rows: u64 = 1000000;
+    let record = |address: u64| {
+        let checksum = calculate_checksum(&0x06, &address, &[0xAA]);
+        format!("S306{address:08X}AA{checksum:02X}\n")
+    };
+
+    let mut fragmented_group = c.benchmark_group("Fragmented data");
+    fragmented_group.sample_size(10);
+
+    // Every record is a separate data chunk
+    let srecord_str: String = (0..num_rows).map(|i| record(i * 2)).collect();
+    fragmented_group.bench_with_input("1M 1 byte, ascending", srecord_str.as_str(), |b, s| {
+        b.iter(|| SRecordFile::from_str(s).unwrap());
+    });
+
+    // Every record is out of order
+    let srecord_str: String = (0..num_rows).rev().map(|i| record(i * 2)).collect();
+    fragmented_group.bench_with_input("1M 1 byte, descending", srecord_str.as_str(), |b, s| {
+        b.iter(|| SRecordFile::from_str(s).unwrap());
+    });
+
+    let mut options = ParseOptions::default();
+    options.gap_fill = Some(GapFill {
+        max_gap: 16,
+        value: 0xFF,
+    });
+    fragmented_group.bench_with_input(
+        "1M 1 byte, descending, gap fill",
+        srecord_str.as_str(),
+        |b, s| {
+            b.iter(|| SRecordFile::from_str_with_options(s, &options).unwrap());
+        },
+    );
+}
+
+fn bench_random_access(c: &mut Criterion) {
+    let num_data_chunks: u64 = 10000;
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.data_chunks = (0..num_data_chunks)
+        .map(|chunk_idx| DataChunk {
+            address: chunk_idx * 0x200,
+            data: vec![chunk_idx as u8; 0x100],
+        })
+        .collect();
+
+    let num_reads = 1000000;
+    let mut state: u64 = 1;
+    let mut next_random = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state
+    };
+    // Random reads that mostly stay inside the same data chunk, like a lookup table being read
+    let local_addresses: Vec<u64> = (0..num_reads)
+        .map(|i| {
+            let chunk_idx = (i / 1000) as u64 % num_data_chunks;
+            chunk_idx * 0x200 + (next_random() >> 56)
+        })
+        .collect();
+    // Random reads spread uniformly over all data chunks, so that almost every read moves to
+    // another data chunk
+    let uniform_addresses: Vec<u64> = (0..num_reads)
+        .map(|_| {
+            let random = next_random();
+            let chunk_idx = (random >> 32) % num_data_chunks;
+            chunk_idx * 0x200 + (random >> 56)
+        })
+        .collect();
+
+    let mut random_access_group = c.benchmark_group("Random access");
+    for (name, addresses) in [("local", &local_addresses), ("uniform", &uniform_addresses)] {
+        random_access_group.bench_with_input(
+            format!("1M get, {name}"),
+            addresses,
+            |b, addresses| {
+                b.iter(|| {
+                    addresses
+                        .iter()
+                        .map(|&address| *srecord_file.get(address).unwrap() as u64)
+                        .sum::<u64>()
+                });
+            },
+        );
+        random_access_group.bench_with_input(
+            format!("1M DataReader::get, {name}"),
+            addresses,
+            |b, addresses| {
+                b.iter(|| {
+                    let mut reader = srecord_file.reader();
+                    addresses
+                        .iter()
+                        .map(|&address| reader.get(address).unwrap() as u64)
+                        .sum::<u64>()
+                });
+            },
+        );
+    }
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default();
     targets = bench_calculate_checksum, bench_from_str_sequential, bench_from_str_data_chunks,
+        bench_from_str_fragmented, bench_random_access,
 }
 criterion_main!(benches);
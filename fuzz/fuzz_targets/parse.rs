@@ -0,0 +1,20 @@
+//! Parses arbitrary bytes, and checks that every successfully parsed file serializes into a file
+//! that parses into the same data.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use srex::srecord::SRecordFile;
+
+fuzz_target!(|srecord_bytes: &[u8]| {
+    let Ok(srecord_file) = SRecordFile::from_bytes(srecord_bytes) else {
+        return;
+    };
+    // Files with data above 32 bits cannot be serialized
+    let Ok(srecord_str) = srecord_file.serialize_original() else {
+        return;
+    };
+    let parsed = srecord_str.parse::<SRecordFile>().unwrap();
+    assert_eq!(parsed.data_chunks, srecord_file.data_chunks);
+    assert_eq!(parsed.start_address, srecord_file.start_address);
+});
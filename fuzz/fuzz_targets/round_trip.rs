@@ -0,0 +1,23 @@
+//! Serializes arbitrary valid files and records, and checks that they parse back unchanged.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use srex::srecord::{OwnedRecord, SRecordFile};
+
+fuzz_target!(|input: (SRecordFile, Vec<OwnedRecord>)| {
+    let (srecord_file, records) = input;
+    let srecord_str = srecord_file.serialize_original().unwrap();
+    let parsed = SRecordFile::from_str(&srecord_str).unwrap();
+    assert_eq!(parsed.data_chunks, srecord_file.data_chunks);
+    assert_eq!(parsed.start_address, srecord_file.start_address);
+    if srecord_file.header_data.is_some() {
+        assert_eq!(parsed.header_data, srecord_file.header_data);
+    }
+
+    for record in records {
+        assert_eq!(OwnedRecord::from_str(&record.serialize()).unwrap(), record);
+    }
+});
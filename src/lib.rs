@@ -31,5 +31,33 @@
 //! }
 //! assert_eq!(srecord_file[0x38..0x3C], [0x00, 0x01, 0x02, 0x03]);
 //! ```
+//!
+//! ## Cargo features
+//!
+//! No features are enabled by default, so parsing and writing SRecord files does not pull in any
+//! dependencies. Optional functionality is enabled with:
+//!
+//! - `arbitrary`: Implements `Arbitrary` of the `arbitrary` crate for
+//!   [`SRecordFile`](srecord::SRecordFile), [`DataChunk`](srecord::DataChunk) and
+//!   [`OwnedRecord`](srecord::OwnedRecord), generating valid files and records for fuzzing.
+//! - `hex`: Decodes and encodes hexadecimal digits with the `hex` crate instead of the built-in
+//!   lookup table.
+//! - `ihex`: Conversion between [`SRecordFile`](srecord::SRecordFile) and Intel HEX records of the
+//!   `ihex` crate.
+//! - `mmap`: `SRecordFile::from_mmap`, parsing memory-mapped files with the `memmap2` crate.
+//! - `object`: Conversion from ELF and other object files read by the `object` crate.
+//! - `rayon`: Parses large files on multiple threads with the `rayon` crate.
+//! - `unstable`: APIs that are still evolving, see below.
+//!
+//! ## Stability
+//!
+//! [`SRecordFile`](srecord::SRecordFile), [`Record`](srecord::Record) and the other items that are
+//! available without the `unstable` feature follow semantic versioning. Their signatures are pinned
+//! by `tests/stable_api.rs`.
+//!
+//! The document model (`SRecordDocument`), pipelines (`Pipeline`, `RemapTable`,
+//! `StartAddressPolicy` and their errors) and the Extended Tektronix hex conversion
+//! (`SRecordFile::from_tektronix_str`, `SRecordFile::to_tektronix_string`) are only available with
+//! the `unstable` feature, and may change in any release.
 
 pub mod srecord;
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::srecord::RecordType;
+
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// Address in an SRecord file, stored as a `u64`.
+///
+/// All addresses in this crate are `u64`. [`Address`] adds checked constructors for the address
+/// width of each record type, so that addresses do not have to be cast, and silently truncated, by
+/// hand. An [`Address`] can be used to index into an [`SRecordFile`] like a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::{Address, RecordType, SRecordFile};
+///
+/// assert_eq!(Address::for_record_type(0xFFFF, &RecordType::S1), Some(Address::new(0xFFFF)));
+/// assert_eq!(Address::for_record_type(0x10000, &RecordType::S1), None);
+/// assert!(Address::from(0x10000u32).fits(&RecordType::S2));
+/// assert!(u16::try_from(Address::new(0x10000)).is_err());
+///
+/// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+/// assert_eq!(srecord_file[Address::from(0x1001u16)], 0x01);
+/// srecord_file.set_data(Address::from(0x1004u32), &[0xAA]);
+/// assert_eq!(srecord_file.read_u16_le(Address::new(0x1003)), Some(0xAA03));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(u64);
+
+impl Address {
+    /// Creates a new [`Address`]. Any `u64` is a valid address, use
+    /// [`for_record_type`](Address::for_record_type) to check that it fits in a record.
+    pub const fn new(address: u64) -> Self {
+        Address(address)
+    }
+
+    /// Creates a new [`Address`], or returns `None` if `address` does not fit in the address field
+    /// of `record_type`.
+    pub fn for_record_type(address: u64, record_type: &RecordType) -> Option<Self> {
+        let address = Address(address);
+        match address.fits(record_type) {
+            true => Some(address),
+            false => None,
+        }
+    }
+
+    /// Returns the address as a `u64`.
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Returns whether the address fits in the address field of `record_type`.
+    pub fn fits(self, record_type: &RecordType) -> bool {
+        self.0 >> (8 * record_type.num_address_bytes()) == 0
+    }
+}
+
+impl From<u16> for Address {
+    fn from(address: u16) -> Self {
+        Address(address as u64)
+    }
+}
+
+impl From<u32> for Address {
+    fn from(address: u32) -> Self {
+        Address(address as u64)
+    }
+}
+
+impl From<u64> for Address {
+    fn from(address: u64) -> Self {
+        Address(address)
+    }
+}
+
+impl From<Address> for u64 {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl TryFrom<Address> for u16 {
+    type Error = std::num::TryFromIntError;
+
+    /// Converts to a 16-bit address, as used by S1 records, failing instead of truncating.
+    fn try_from(address: Address) -> Result<Self, Self::Error> {
+        u16::try_from(address.0)
+    }
+}
+
+impl TryFrom<Address> for u32 {
+    type Error = std::num::TryFromIntError;
+
+    /// Converts to a 32-bit address, as used by S3 records, failing instead of truncating.
+    fn try_from(address: Address) -> Result<Self, Self::Error> {
+        u32::try_from(address.0)
+    }
+}
+
+impl fmt::Display for Address {
+    /// Formats the address as hexadecimal with a leading `0x`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#X}", self.0)
+    }
+}
+
+/// Conversion into an [`Address`], accepted by the methods of [`SRecordFile`] that take an address.
+///
+/// Implemented for `u64` and [`Address`] only, so that an integer literal passed as an address is
+/// still inferred to be a `u64`. Other integer types are converted with [`Address::from`], which
+/// cannot truncate.
+pub trait IntoAddress: private::Sealed {
+    /// Converts `self` into an [`Address`].
+    fn into_address(self) -> Address;
+}
+
+impl IntoAddress for u64 {
+    fn into_address(self) -> Address {
+        Address(self)
+    }
+}
+
+impl IntoAddress for Address {
+    fn into_address(self) -> Address {
+        self
+    }
+}
+
+/// Converts both ends of `range` into `u64` addresses.
+pub(crate) fn address_range<A: IntoAddress>(range: Range<A>) -> Range<u64> {
+    range.start.into_address().value()..range.end.into_address().value()
+}
+
+mod private {
+    use crate::srecord::Address;
+
+    pub trait Sealed {}
+    impl Sealed for u64 {}
+    impl Sealed for Address {}
+}
@@ -0,0 +1,102 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::srecord::{
+    CountRecord, DataChunk, OwnedDataRecord, OwnedHeaderRecord, OwnedRecord, SRecordFile,
+    StartAddressRecord,
+};
+
+/// Maximum number of data bytes in a header record, limited by the byte count of the record.
+const MAX_HEADER_LEN: usize = 252;
+
+impl<'a> Arbitrary<'a> for DataChunk {
+    /// Generates a non-empty [`DataChunk`] that fits in the 32-bit address range of S3 records.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let address = u32::arbitrary(u)? as u64;
+        let mut data = Vec::<u8>::arbitrary(u)?;
+        data.truncate(((1 << 32) - address).min(usize::MAX as u64) as usize);
+        if data.is_empty() {
+            data.push(u8::arbitrary(u)?);
+        }
+        Ok(DataChunk { address, data })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SRecordFile {
+    /// Generates an [`SRecordFile`] that can be serialized with the default
+    /// [`SerializationOptions`](crate::srecord::SerializationOptions): the data chunks are sorted,
+    /// merged and inside the 32-bit address range, the header fits in a header record and the start
+    /// address fits in 32 bits. No [`record_layout`](SRecordFile::record_layout) is generated.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let header_data = match bool::arbitrary(u)? {
+            true => Some(header_data(u)?),
+            false => None,
+        };
+        let mut srecord_file = SRecordFile {
+            header_data,
+            ..SRecordFile::new()
+        };
+        for data_chunk in u.arbitrary_iter::<DataChunk>()? {
+            let data_chunk = data_chunk?;
+            srecord_file.set_data(data_chunk.address, &data_chunk.data);
+        }
+        srecord_file.start_address = Option::<u32>::arbitrary(u)?.map(u64::from);
+        Ok(srecord_file)
+    }
+}
+
+impl<'a> Arbitrary<'a> for OwnedRecord {
+    /// Generates a valid record of any type, with the address, data length and record count inside
+    /// the limits of the record type.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=8)? {
+            0 => OwnedRecord::S0Record(OwnedHeaderRecord {
+                data: header_data(u)?,
+            }),
+            1 => OwnedRecord::S1Record(data_record(u, 2)?),
+            2 => OwnedRecord::S2Record(data_record(u, 3)?),
+            3 => OwnedRecord::S3Record(data_record(u, 4)?),
+            4 => OwnedRecord::S5Record(count_record(u, 2)?),
+            5 => OwnedRecord::S6Record(count_record(u, 3)?),
+            6 => OwnedRecord::S7Record(start_address_record(u, 4)?),
+            7 => OwnedRecord::S8Record(start_address_record(u, 3)?),
+            _ => OwnedRecord::S9Record(start_address_record(u, 2)?),
+        })
+    }
+}
+
+/// Returns the largest value of an address or record count of `num_bytes` bytes.
+fn max_value(num_bytes: usize) -> u64 {
+    (1 << (8 * num_bytes)) - 1
+}
+
+/// Generates the data of a header record.
+fn header_data(u: &mut Unstructured) -> Result<Vec<u8>> {
+    let mut data = Vec::<u8>::arbitrary(u)?;
+    data.truncate(MAX_HEADER_LEN);
+    Ok(data)
+}
+
+/// Generates a data record with an address of `num_address_bytes` bytes, whose data fits in both
+/// the record and the address range.
+fn data_record(u: &mut Unstructured, num_address_bytes: usize) -> Result<OwnedDataRecord> {
+    let address = u.int_in_range(0..=max_value(num_address_bytes))?;
+    let mut data = Vec::<u8>::arbitrary(u)?;
+    data.truncate(
+        (254 - num_address_bytes).min((max_value(num_address_bytes) - address + 1) as usize),
+    );
+    Ok(OwnedDataRecord { address, data })
+}
+
+/// Generates a count record with a record count of `num_bytes` bytes.
+fn count_record(u: &mut Unstructured, num_bytes: usize) -> Result<CountRecord> {
+    Ok(CountRecord {
+        record_count: u.int_in_range(0..=max_value(num_bytes))? as usize,
+    })
+}
+
+/// Generates a start address record with an address of `num_bytes` bytes.
+fn start_address_record(u: &mut Unstructured, num_bytes: usize) -> Result<StartAddressRecord> {
+    Ok(StartAddressRecord {
+        start_address: u.int_in_range(0..=max_value(num_bytes))?,
+    })
+}
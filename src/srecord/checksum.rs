@@ -0,0 +1,123 @@
+use std::iter;
+use std::ops::Range;
+
+use crate::srecord::utils::crc32_update;
+use crate::srecord::SRecordFile;
+
+/// Calculates the CRC-32 (IEEE 802.3, as used by zlib and Ethernet) of the data in `range`, with
+/// `fill` at every address without data.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::checksum;
+/// use srex::srecord::SRecordFile;
+///
+/// let mut srecord_file = SRecordFile::new();
+/// srecord_file.set_data(0x1000, b"1234");
+/// srecord_file.set_data(0x1005, b"6789");
+/// assert_eq!(checksum::crc32(&srecord_file, 0x1000..0x1009, b'5'), 0xCBF43926);
+/// ```
+pub fn crc32(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u32 {
+    !bytes_in_range(srecord_file, range, fill).fold(0xFFFFFFFF, crc32_update)
+}
+
+/// Calculates the CRC-16/CCITT-FALSE (polynomial 0x1021, initial value 0xFFFF, not reflected) of
+/// the data in `range`, with `fill` at every address without data.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::checksum;
+/// use srex::srecord::SRecordFile;
+///
+/// let srecord_file = SRecordFile::from_binary(0, b"123456789");
+/// assert_eq!(checksum::crc16_ccitt(&srecord_file, 0..9, 0xFF), 0x29B1);
+/// ```
+pub fn crc16_ccitt(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u16 {
+    bytes_in_range(srecord_file, range, fill).fold(0xFFFF, |mut crc, byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = match crc & 0x8000 != 0 {
+                true => (crc << 1) ^ 0x1021,
+                false => crc << 1,
+            };
+        }
+        crc
+    })
+}
+
+/// Calculates the CRC-8 (polynomial 0x07, initial value 0x00, not reflected, as used by SMBus) of
+/// the data in `range`, with `fill` at every address without data.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::checksum;
+/// use srex::srecord::SRecordFile;
+///
+/// let srecord_file = SRecordFile::from_binary(0, b"123456789");
+/// assert_eq!(checksum::crc8(&srecord_file, 0..9, 0xFF), 0xF4);
+/// ```
+pub fn crc8(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u8 {
+    bytes_in_range(srecord_file, range, fill).fold(0x00, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = match crc & 0x80 != 0 {
+                true => (crc << 1) ^ 0x07,
+                false => crc << 1,
+            };
+        }
+        crc
+    })
+}
+
+/// Calculates the sum of the bytes in `range`, with `fill` at every address without data,
+/// wrapping around at 8 bits.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::checksum;
+/// use srex::srecord::SRecordFile;
+///
+/// let srecord_file = SRecordFile::from_binary(0x1000, &[0x80, 0x81]);
+/// assert_eq!(checksum::sum8(&srecord_file, 0x1000..0x1003, 0xFF), 0x00);
+/// assert_eq!(checksum::sum16(&srecord_file, 0x1000..0x1003, 0xFF), 0x0200);
+/// ```
+pub fn sum8(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u8 {
+    bytes_in_range(srecord_file, range, fill).fold(0, u8::wrapping_add)
+}
+
+/// Calculates the sum of the bytes in `range`, with `fill` at every address without data,
+/// wrapping around at 16 bits.
+pub fn sum16(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u16 {
+    bytes_in_range(srecord_file, range, fill).fold(0, |sum, byte| sum.wrapping_add(byte as u16))
+}
+
+/// Calculates the sum of the bytes in `range`, with `fill` at every address without data,
+/// wrapping around at 32 bits.
+pub fn sum32(srecord_file: &SRecordFile, range: Range<u64>, fill: u8) -> u32 {
+    bytes_in_range(srecord_file, range, fill).fold(0, |sum, byte| sum.wrapping_add(byte as u32))
+}
+
+/// Iterates over every byte in `range`, with `fill` at every address without data.
+fn bytes_in_range(
+    srecord_file: &SRecordFile,
+    range: Range<u64>,
+    fill: u8,
+) -> impl Iterator<Item = u8> + '_ {
+    let mut address = range.start;
+    let end_address = range.end.max(range.start);
+    srecord_file
+        .iter_data_in_range(range)
+        .map(Some)
+        .chain(iter::once(None))
+        .flat_map(move |data| {
+            // The final gap after the last data is filled up to the end of the range
+            let (data_address, data) = data.unwrap_or((end_address, &[]));
+            let gap = data_address - address;
+            address = data_address + data.len() as u64;
+            iter::repeat_n(fill, gap as usize).chain(data.iter().copied())
+        })
+}
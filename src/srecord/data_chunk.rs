@@ -1,14 +1,17 @@
-use std::cmp::{min, Ordering};
-use std::ops::Range;
+use std::cmp::{max, min, Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
+use std::vec;
 
+use crate::srecord::endianness::{check_int_size, int_from_bytes};
 use crate::srecord::slice_index::SliceIndex;
-use crate::srecord::DataRecord;
+use crate::srecord::{DataRecord, Endianness, OverlapPolicy};
 
 /// A contiguous chunk of data at a specific address.
 ///
 /// [`DataChunk`]s are intended to be the largest contiguous ranges of data, allowing flexible
 /// slicing of contiguous data.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DataChunk {
     /// Start address of the [`DataChunk`]. The first byte of the data is located at this address.
     pub address: u64,
@@ -17,6 +20,26 @@ pub struct DataChunk {
 }
 
 impl DataChunk {
+    /// Creates an empty [`DataChunk`] at `address`, with room for at least `capacity` bytes of
+    /// data before reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk::with_capacity(0x1000, 256);
+    /// assert_eq!(data_chunk.address, 0x1000);
+    /// assert!(data_chunk.is_empty());
+    /// assert!(data_chunk.data.capacity() >= 256);
+    /// ```
+    pub fn with_capacity(address: u64, capacity: usize) -> Self {
+        DataChunk {
+            address,
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
     /// Returns inclusive start address of [`DataChunk`]. Same as `address`.
     pub fn start_address(&self) -> u64 {
         self.address
@@ -28,6 +51,48 @@ impl DataChunk {
         self.address + self.data.len() as u64
     }
 
+    /// Returns the number of bytes of data in the [`DataChunk`].
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the [`DataChunk`] contains no data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the data of the [`DataChunk`] as a slice. The first byte is located at `address`.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the data of the [`DataChunk`] as a mutable slice. The first byte is located at
+    /// `address`.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Iterates over every byte of data together with its address, in ascending address order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0xAA, 0xBB],
+    /// };
+    /// let bytes: Vec<_> = data_chunk.iter_bytes().collect();
+    /// assert_eq!(bytes, [(0x1000, 0xAA), (0x1001, 0xBB)]);
+    /// ```
+    pub fn iter_bytes(&self) -> impl ExactSizeIterator<Item = (u64, u8)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, &byte)| (self.address + index as u64, byte))
+    }
+
     /// Returns a reference to a byte or byte data subslice depending on the type of index.
     ///
     /// - If given an address, returns a reference to the byte at that address or `None` if out of
@@ -85,6 +150,175 @@ impl DataChunk {
         index.get_mut(self)
     }
 
+    /// Returns the unsigned integer of `size` bytes at `address` with byte order `endianness`, or
+    /// `None` if any of the bytes has no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, Endianness};
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// assert_eq!(data_chunk.read_int(0x1001, 3, Endianness::Little), Some(0x030201));
+    /// assert_eq!(data_chunk.read_u16_be(0x1002), Some(0x0203));
+    /// assert_eq!(data_chunk.read_u32_be(0x1001), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`read_int`](DataChunk::read_int) will [`panic!`] if `size` is not between 1 and 8.
+    pub fn read_int(&self, address: u64, size: usize, endianness: Endianness) -> Option<u64> {
+        check_int_size(size);
+        let end_address = address.checked_add(size as u64)?;
+        Some(int_from_bytes(self.get(address..end_address)?, endianness))
+    }
+
+    /// Returns the little-endian `u16` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u16_le(&self, address: u64) -> Option<u16> {
+        self.read_array(address).map(u16::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u16` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u16_be(&self, address: u64) -> Option<u16> {
+        self.read_array(address).map(u16::from_be_bytes)
+    }
+
+    /// Returns the little-endian `u32` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u32_le(&self, address: u64) -> Option<u32> {
+        self.read_array(address).map(u32::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u32` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u32_be(&self, address: u64) -> Option<u32> {
+        self.read_array(address).map(u32::from_be_bytes)
+    }
+
+    /// Returns the little-endian `u64` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u64_le(&self, address: u64) -> Option<u64> {
+        self.read_array(address).map(u64::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u64` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u64_be(&self, address: u64) -> Option<u64> {
+        self.read_array(address).map(u64::from_be_bytes)
+    }
+
+    /// Returns the `N` bytes at `address` as an array, or `None` if any of the bytes has no data.
+    fn read_array<const N: usize>(&self, address: u64) -> Option<[u8; N]> {
+        let end_address = address.checked_add(N as u64)?;
+        self.get(address..end_address)?.try_into().ok()
+    }
+
+    /// Splits the [`DataChunk`] into the data below `address` and the data at or above `address`.
+    /// One of the chunks is empty if `address` is the start or end address of the chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// let (below, above) = data_chunk.split_at(0x1001);
+    /// assert_eq!(below.address, 0x1000);
+    /// assert_eq!(below.data, [0x00]);
+    /// assert_eq!(above.address, 0x1001);
+    /// assert_eq!(above.data, [0x01, 0x02, 0x03]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`split_at`](DataChunk::split_at) will [`panic!`] if `address` is not between the start and
+    /// end address of the chunk.
+    pub fn split_at(mut self, address: u64) -> (DataChunk, DataChunk) {
+        if address < self.address || address > self.end_address() {
+            panic!(
+                "Address {address:#08X} is outside DataChunk {:#08X}:{:#08X}",
+                self.address,
+                self.end_address()
+            );
+        }
+        let above = DataChunk {
+            address,
+            data: self.data.split_off((address - self.address) as usize),
+        };
+        (self, above)
+    }
+
+    /// Removes all data at or above `new_end_address`, keeping the data below it. Nothing is
+    /// removed if `new_end_address` is at or above the end address of the chunk, and all data is
+    /// removed if it is at or below the start address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let mut data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// data_chunk.truncate(0x1002);
+    /// assert_eq!(data_chunk.data, [0x00, 0x01]);
+    /// data_chunk.truncate(0x2000);
+    /// assert_eq!(data_chunk.data, [0x00, 0x01]);
+    /// ```
+    pub fn truncate(&mut self, new_end_address: u64) {
+        self.data
+            .truncate(new_end_address.saturating_sub(self.address) as usize);
+    }
+
+    /// Removes the data in `range` from the start or end of the [`DataChunk`], and returns it as
+    /// an iterator. The remaining data keeps its addresses, so the start address of the chunk moves
+    /// to `range.end` if data is removed from the start, unless all data is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let mut data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// assert_eq!(data_chunk.drain(0x1000..0x1002).collect::<Vec<_>>(), [0x00, 0x01]);
+    /// assert_eq!(data_chunk.address, 0x1002);
+    /// assert_eq!(data_chunk.data, [0x02, 0x03]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`drain`](DataChunk::drain) will [`panic!`] if `range` is not inside the chunk, or if it
+    /// is non-empty and contains neither the first nor the last byte of the chunk, since removing
+    /// data from the middle of the chunk would move the data after it. Use
+    /// [`split_at`](DataChunk::split_at) to remove data from the middle.
+    pub fn drain(&mut self, range: Range<u64>) -> vec::Drain<'_, u8> {
+        let end_address = self.end_address();
+        if range.start < self.address || range.end > end_address || range.start > range.end {
+            panic!(
+                "Address range {:#08X}:{:#08X} is outside DataChunk {:#08X}:{end_address:#08X}",
+                range.start, range.end, self.address
+            );
+        }
+        if range.start > self.address && range.end < end_address && range.start < range.end {
+            panic!(
+                "Address range {:#08X}:{:#08X} is in the middle of DataChunk {:#08X}:{end_address:#08X}",
+                range.start, range.end, self.address
+            );
+        }
+        let start_index = (range.start - self.address) as usize;
+        let end_index = (range.end - self.address) as usize;
+        if start_index == 0 && range.end < end_address {
+            self.address = range.end;
+        }
+        self.data.drain(start_index..end_index)
+    }
+
     /// Iterate over [`DataChunk`] with [`DataRecord`]s.
     ///
     /// Each record contains `record_size` bytes of data. The data is aligned to the start of the
@@ -104,16 +338,62 @@ impl DataChunk {
     /// assert_eq!(iterator.next().unwrap(), DataRecord{ address: 0x1002, data: &[0x02, 0x03] });
     /// assert!(iterator.next().is_none());
     /// ```
-    // TODO: Alignment
-    pub fn iter_records(&self, record_size: usize) -> DataChunkIterator {
+    pub fn iter_records(&self, record_size: usize) -> DataChunkIterator<'_> {
+        self.iter_records_aligned(record_size, 1)
+    }
+
+    /// Iterate over [`DataChunk`] with [`DataRecord`]s aligned to absolute address boundaries.
+    ///
+    /// Each record contains at most `record_size` bytes of data. A record starting at an address
+    /// that is not a multiple of `alignment` is cut short at the next multiple of `alignment`, so
+    /// that the following records start on aligned addresses. An `alignment` of 0 or 1 disables
+    /// alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, DataRecord};
+    ///
+    /// let data_chunk = DataChunk{
+    ///     address: 0x1002,
+    ///     data: vec![0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+    /// };
+    /// let mut iterator = data_chunk.iter_records_aligned(4, 4);
+    /// assert_eq!(iterator.next().unwrap(), DataRecord{ address: 0x1002, data: &[0x02, 0x03] });
+    /// assert_eq!(
+    ///     iterator.next().unwrap(),
+    ///     DataRecord{ address: 0x1004, data: &[0x04, 0x05, 0x06, 0x07] },
+    /// );
+    /// assert!(iterator.next().is_none());
+    /// ```
+    pub fn iter_records_aligned(
+        &self,
+        record_size: usize,
+        alignment: u64,
+    ) -> DataChunkIterator<'_> {
         DataChunkIterator {
             data_chunk: self,
             record_size,
+            alignment: max(alignment, 1),
             address: self.address,
         }
     }
 }
 
+impl AsRef<[u8]> for DataChunk {
+    /// Returns the data of the [`DataChunk`], see [`as_slice`](DataChunk::as_slice).
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for DataChunk {
+    /// Returns the data of the [`DataChunk`], see [`as_mut_slice`](DataChunk::as_mut_slice).
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
 impl SliceIndex<DataChunk> for u64 {
     type Output = u8;
 
@@ -228,6 +508,203 @@ impl SliceIndex<DataChunk> for Range<u64> {
     }
 }
 
+impl SliceIndex<DataChunk> for RangeInclusive<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to a data slice in a [`DataChunk`], at the inclusive address range that
+    /// `self` points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// assert_eq!(data_chunk.get(0x1001..=0x1003).unwrap(), [0x01, 0x02, 0x03]);
+    /// assert!(data_chunk.get(0x1000..=0x1004).is_none());
+    /// ```
+    fn get(self, data_chunk: &DataChunk) -> Option<&[u8]> {
+        exclusive_range(self)?.get(data_chunk)
+    }
+
+    /// Returns a mutable reference to a data slice in a [`DataChunk`], at the inclusive address
+    /// range that `self` points to, or `None` if out of bounds.
+    fn get_mut(self, data_chunk: &mut DataChunk) -> Option<&mut [u8]> {
+        exclusive_range(self)?.get_mut(data_chunk)
+    }
+}
+
+impl SliceIndex<DataChunk> for RangeFrom<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to the data in a [`DataChunk`] from the address that `self` points to
+    /// until the end of the chunk, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// assert_eq!(data_chunk.get(0x1002..).unwrap(), [0x02, 0x03]);
+    /// assert!(data_chunk.get(0x0FFF..).is_none());
+    /// ```
+    fn get(self, data_chunk: &DataChunk) -> Option<&[u8]> {
+        (self.start..data_chunk.end_address()).get(data_chunk)
+    }
+
+    /// Returns a mutable reference to the data in a [`DataChunk`] from the address that `self`
+    /// points to until the end of the chunk, or `None` if out of bounds.
+    fn get_mut(self, data_chunk: &mut DataChunk) -> Option<&mut [u8]> {
+        (self.start..data_chunk.end_address()).get_mut(data_chunk)
+    }
+}
+
+impl SliceIndex<DataChunk> for RangeTo<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to the data in a [`DataChunk`] from the start of the chunk until the
+    /// exclusive end address that `self` points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::DataChunk;
+    ///
+    /// let data_chunk = DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// };
+    /// assert_eq!(data_chunk.get(..0x1002).unwrap(), [0x00, 0x01]);
+    /// assert!(data_chunk.get(..0x1005).is_none());
+    /// ```
+    fn get(self, data_chunk: &DataChunk) -> Option<&[u8]> {
+        (data_chunk.address..self.end).get(data_chunk)
+    }
+
+    /// Returns a mutable reference to the data in a [`DataChunk`] from the start of the chunk
+    /// until the exclusive end address that `self` points to, or `None` if out of bounds.
+    fn get_mut(self, data_chunk: &mut DataChunk) -> Option<&mut [u8]> {
+        (data_chunk.address..self.end).get_mut(data_chunk)
+    }
+}
+
+impl SliceIndex<DataChunk> for RangeFull {
+    type Output = [u8];
+
+    /// Returns a reference to all data in a [`DataChunk`].
+    fn get(self, data_chunk: &DataChunk) -> Option<&[u8]> {
+        Some(&data_chunk.data)
+    }
+
+    /// Returns a mutable reference to all data in a [`DataChunk`].
+    fn get_mut(self, data_chunk: &mut DataChunk) -> Option<&mut [u8]> {
+        Some(&mut data_chunk.data)
+    }
+}
+
+/// Converts the inclusive address range `range` into an exclusive range, or returns `None` if the
+/// end address does not fit in a `u64`, in which case no data can be inside the range.
+pub(crate) fn exclusive_range(range: RangeInclusive<u64>) -> Option<Range<u64>> {
+    let (start, end) = range.into_inner();
+    Some(start..end.checked_add(1)?)
+}
+
+/// Merges lists of sorted, non-overlapping [`DataChunk`]s into a single list of sorted and merged
+/// [`DataChunk`]s, in one pass over all chunks.
+///
+/// Each later list is treated as incoming data written over the earlier lists, and data at the
+/// same address in several lists is resolved with `policy`. With [`OverlapPolicy::Error`], the
+/// first overlapping address range is returned as error.
+pub(crate) fn merge_chunk_lists(
+    chunk_lists: Vec<Vec<DataChunk>>,
+    policy: OverlapPolicy,
+) -> Result<Vec<DataChunk>, Range<u64>> {
+    let mut chunk_iterators: Vec<vec::IntoIter<DataChunk>> =
+        chunk_lists.into_iter().map(Vec::into_iter).collect();
+    // Next chunk of each list, ordered by start address and then list index
+    let mut next_chunks = BinaryHeap::new();
+    for (list_index, chunk_iterator) in chunk_iterators.iter_mut().enumerate() {
+        if let Some(data_chunk) = chunk_iterator.next() {
+            next_chunks.push(Reverse((data_chunk.address, list_index, data_chunk.data)));
+        }
+    }
+
+    let mut merged_chunks: Vec<DataChunk> = Vec::new();
+    // Index of the list each address range of the last merged chunk was taken from. Ranges
+    // ending before the chunk currently being merged can no longer be overlapped, and are dropped.
+    let mut owners: VecDeque<(Range<u64>, usize)> = VecDeque::new();
+    while let Some(Reverse((address, list_index, data))) = next_chunks.pop() {
+        if let Some(data_chunk) = chunk_iterators[list_index].next() {
+            next_chunks.push(Reverse((data_chunk.address, list_index, data_chunk.data)));
+        }
+        let end_address = address + data.len() as u64;
+        while owners
+            .front()
+            .is_some_and(|(range, _)| range.end <= address)
+        {
+            owners.pop_front();
+        }
+
+        let last_chunk = match merged_chunks.last_mut() {
+            Some(last_chunk) if address <= last_chunk.end_address() => last_chunk,
+            _ => {
+                merged_chunks.push(DataChunk { address, data });
+                owners.clear();
+                owners.push_back((address..end_address, list_index));
+                continue;
+            }
+        };
+        let last_end_address = last_chunk.end_address();
+        if address < last_end_address {
+            let overlap_end_address = min(end_address, last_end_address);
+            if policy == OverlapPolicy::Error {
+                return Err(address..overlap_end_address);
+            }
+            let mut updated_owners = VecDeque::with_capacity(owners.len() + 2);
+            for (range, owner) in owners.drain(..) {
+                let start = max(range.start, address);
+                let end = min(range.end, overlap_end_address);
+                let incoming_wins = match policy {
+                    OverlapPolicy::KeepExisting => list_index < owner,
+                    _ => list_index > owner,
+                };
+                if start >= end || !incoming_wins {
+                    updated_owners.push_back((range, owner));
+                    continue;
+                }
+                let chunk_offset = (start - last_chunk.address) as usize;
+                let data_offset = (start - address) as usize;
+                let len = (end - start) as usize;
+                last_chunk.data[chunk_offset..chunk_offset + len]
+                    .copy_from_slice(&data[data_offset..data_offset + len]);
+                for (range, owner) in [
+                    (range.start..start, owner),
+                    (start..end, list_index),
+                    (end..range.end, owner),
+                ] {
+                    if range.start < range.end {
+                        updated_owners.push_back((range, owner));
+                    }
+                }
+            }
+            owners = updated_owners;
+        }
+        if end_address > last_end_address {
+            let data_offset = (last_end_address - address) as usize;
+            last_chunk.data.extend_from_slice(&data[data_offset..]);
+            owners.push_back((last_end_address..end_address, list_index));
+        }
+    }
+    Ok(merged_chunks)
+}
+
 /// Iterator that returns [`DataRecords`](`DataRecord`) in sequence for the data chunk, with a
 /// specified length.
 pub struct DataChunkIterator<'a> {
@@ -235,6 +712,9 @@ pub struct DataChunkIterator<'a> {
     data_chunk: &'a DataChunk,
     /// Data size (bytes of actual data) in each record.
     record_size: usize,
+    /// Address alignment of records. Records starting at unaligned addresses end at the next
+    /// aligned address.
+    alignment: u64,
     /// Address that the current [`DataRecord`] should point to.
     address: u64,
 }
@@ -247,10 +727,14 @@ impl<'a> Iterator for DataChunkIterator<'a> {
         let data_chunk_end_address = self.data_chunk.end_address();
         match start_address.cmp(&data_chunk_end_address) {
             Ordering::Less => {
-                let end_address = min(
-                    start_address + self.record_size as u64,
+                let mut end_address = min(
+                    start_address.saturating_add(self.record_size as u64),
                     self.data_chunk.end_address(),
                 );
+                if !start_address.is_multiple_of(self.alignment) {
+                    let aligned_address = start_address.next_multiple_of(self.alignment);
+                    end_address = min(end_address, aligned_address);
+                }
                 match self.data_chunk.get(start_address..end_address) {
                     Some(data) => {
                         self.address = end_address;
@@ -266,3 +750,65 @@ impl<'a> Iterator for DataChunkIterator<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(address: u64, data: &[u8]) -> DataChunk {
+        DataChunk {
+            address,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_merge_chunk_lists() {
+        let chunk_lists = || {
+            vec![
+                vec![chunk(0x00, &[0x00; 4]), chunk(0x10, &[0x00; 4])],
+                vec![chunk(0x02, &[0x01; 4])],
+                vec![chunk(0x04, &[0x02; 4]), chunk(0x14, &[0x02; 2])],
+            ]
+        };
+        assert_eq!(
+            merge_chunk_lists(chunk_lists(), OverlapPolicy::Overwrite),
+            Ok(vec![
+                chunk(0x00, &[0, 0, 1, 1, 2, 2, 2, 2]),
+                chunk(0x10, &[0, 0, 0, 0, 2, 2]),
+            ])
+        );
+        assert_eq!(
+            merge_chunk_lists(chunk_lists(), OverlapPolicy::KeepExisting),
+            Ok(vec![
+                chunk(0x00, &[0, 0, 0, 0, 1, 1, 2, 2]),
+                chunk(0x10, &[0, 0, 0, 0, 2, 2]),
+            ])
+        );
+        assert_eq!(
+            merge_chunk_lists(chunk_lists(), OverlapPolicy::Error),
+            Err(0x02..0x04)
+        );
+    }
+
+    #[test]
+    fn test_merge_chunk_lists_later_list_first() {
+        // A later list starting at a lower address still takes precedence with Overwrite
+        let chunk_lists = || {
+            vec![
+                vec![chunk(0x02, &[0x00; 2])],
+                vec![chunk(0x00, &[0x01; 8])],
+                vec![],
+            ]
+        };
+        assert_eq!(
+            merge_chunk_lists(chunk_lists(), OverlapPolicy::Overwrite),
+            Ok(vec![chunk(0x00, &[1; 8])])
+        );
+        assert_eq!(
+            merge_chunk_lists(chunk_lists(), OverlapPolicy::KeepExisting),
+            Ok(vec![chunk(0x00, &[1, 1, 0, 0, 1, 1, 1, 1])])
+        );
+        assert_eq!(merge_chunk_lists(vec![], OverlapPolicy::Error), Ok(vec![]));
+    }
+}
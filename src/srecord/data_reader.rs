@@ -0,0 +1,79 @@
+use std::ops::Range;
+
+use crate::srecord::{DataChunk, SRecordFile};
+
+/// Reader for many reads of single bytes or address ranges in an [`SRecordFile`], created with
+/// [`SRecordFile::reader`].
+///
+/// Reading with [`SRecordFile::get`] searches all data chunks for every read. A [`DataReader`]
+/// remembers the data chunk of the last read, and checks it and the data chunk after it before
+/// searching, which makes reads close to each other, e.g. sequential reads or random reads inside
+/// the same region, considerably faster in files with many data chunks.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::SRecordFile;
+///
+/// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+/// let mut reader = srecord_file.reader();
+/// assert_eq!(reader.get(0x1001), Some(0x01));
+/// assert_eq!(reader.get(0x2000), Some(0xAA));
+/// assert_eq!(reader.get(0x1004), None);
+/// assert_eq!(reader.get_range(0x1002..0x1004), Some([0x02, 0x03].as_slice()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DataReader<'a> {
+    /// Data chunks of the file that is read.
+    data_chunks: &'a [DataChunk],
+    /// Index in `data_chunks` of the data chunk of the last successful read.
+    last_index: usize,
+}
+
+impl<'a> DataReader<'a> {
+    /// Creates a new [`DataReader`] reading from `srecord_file`.
+    pub(crate) fn new(srecord_file: &'a SRecordFile) -> Self {
+        DataReader {
+            data_chunks: &srecord_file.data_chunks,
+            last_index: 0,
+        }
+    }
+
+    /// Returns the byte at `address`, or `None` if there is no data at `address`.
+    pub fn get(&mut self, address: u64) -> Option<u8> {
+        let data_chunk = self.find_data_chunk(address)?;
+        Some(data_chunk.data[(address - data_chunk.address) as usize])
+    }
+
+    /// Returns the data in `range`, or `None` if any address in `range` has no data.
+    pub fn get_range(&mut self, range: Range<u64>) -> Option<&'a [u8]> {
+        self.find_data_chunk(range.start)?.get(range)
+    }
+
+    /// Returns the data chunk containing `address`, checking the data chunk of the last read and
+    /// the one after it before searching all data chunks.
+    fn find_data_chunk(&mut self, address: u64) -> Option<&'a DataChunk> {
+        let contains = |data_chunk: &DataChunk| {
+            data_chunk.address <= address && address < data_chunk.end_address()
+        };
+        for index in [self.last_index, self.last_index + 1] {
+            match self.data_chunks.get(index) {
+                Some(data_chunk) if contains(data_chunk) => {
+                    self.last_index = index;
+                    return Some(data_chunk);
+                }
+                _ => {}
+            }
+        }
+        let index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() <= address);
+        let data_chunk = self
+            .data_chunks
+            .get(index)
+            .filter(|data_chunk| contains(data_chunk))?;
+        self.last_index = index;
+        Some(data_chunk)
+    }
+}
@@ -0,0 +1,158 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::srecord::error::{SRecordParseError, SerializationError};
+use crate::srecord::{
+    OwnedRecord, ParseOptions, ParseStats, Record, SRecordFile, SRecordParser, SerializationOptions,
+};
+
+/// Record-level representation of an SRecord file, containing every record in the order it appears
+/// in the file.
+///
+/// Unlike [`SRecordFile`], which merges all data into contiguous chunks, an [`SRecordDocument`]
+/// can be used to inspect or rewrite individual records, e.g. reordering them or changing their
+/// data. Checksums are calculated when the records are serialized, except for records that are
+/// unchanged since they were parsed, which are written exactly as they appeared in the parsed text.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::{OwnedRecord, ParseOptions, SRecordDocument};
+///
+/// let mut document = SRecordDocument::from_str("S107100000010203E2\nS10510040405DD").unwrap();
+/// document.records.swap(0, 1);
+/// if let OwnedRecord::S1Record(data_record) = &mut document.records[0] {
+///     data_record.data[1] = 0xFF;
+/// }
+/// assert_eq!(document.serialize(), "S105100404FFE3\nS107100000010203E2");
+///
+/// let (srecord_file, _) = document.to_srecord_file(&ParseOptions::default()).unwrap();
+/// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0x04, 0xFF]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SRecordDocument {
+    /// Records in the order they appear in the file.
+    pub records: Vec<OwnedRecord>,
+    /// Text the document was parsed from, or empty if it was not parsed.
+    source: String,
+    /// Byte range in `source` of the line that each record was parsed from, without line ending,
+    /// by record index.
+    source_lines: Vec<Range<usize>>,
+}
+
+impl PartialEq for SRecordDocument {
+    /// Documents are equal if they contain the same records, regardless of how they were parsed.
+    fn eq(&self, other: &Self) -> bool {
+        self.records == other.records
+    }
+}
+
+impl Eq for SRecordDocument {}
+
+impl SRecordDocument {
+    /// Generates the records of `srecord_file` as configured by `options`, see
+    /// [`SRecordFile::iter_records_with_options`].
+    pub fn from_srecord_file(
+        srecord_file: &SRecordFile,
+        options: &SerializationOptions,
+    ) -> Result<Self, SerializationError> {
+        Ok(SRecordDocument {
+            records: srecord_file
+                .iter_records_with_options(options)?
+                .map(OwnedRecord::from)
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Collects the records into an [`SRecordFile`], validating them as configured by `options`
+    /// in the same way as [`SRecordFile::from_str_with_options`].
+    pub fn to_srecord_file(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<(SRecordFile, ParseStats), SRecordParseError> {
+        let mut parser = SRecordParser::with_options(options.clone());
+        for record in self.records.iter() {
+            parser.push_record(record.as_record())?;
+        }
+        let (mut srecord_file, stats) = parser.finish_with_stats()?;
+        srecord_file.attach_source(self.serialize());
+        Ok((srecord_file, stats))
+    }
+
+    /// Serializes all records, with one record per line.
+    ///
+    /// A record that is equal to the record parsed from the same line of the original text is
+    /// written as the original line, including its checksum. The line ending of the original text
+    /// is used, and the output only ends without a line ending if the original text did.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{OwnedRecord, SRecordDocument};
+    ///
+    /// let mut document = SRecordDocument::from_str("S107100000010203e2\r\nS9031000EC").unwrap();
+    /// assert_eq!(document.serialize(), "S107100000010203e2\r\nS9031000EC");
+    ///
+    /// if let OwnedRecord::S1Record(data_record) = &mut document.records[0] {
+    ///     data_record.data[0] = 0xFF;
+    /// }
+    /// assert_eq!(document.serialize(), "S1071000FF010203E3\r\nS9031000EC");
+    /// ```
+    pub fn serialize(&self) -> String {
+        let line_ending = match self.source.contains("\r\n") {
+            true => "\r\n",
+            false => "\n",
+        };
+        let mut data_buffer = [0u8; 256];
+        let mut srecord_str = String::with_capacity(self.source.len());
+        for (index, record) in self.records.iter().enumerate() {
+            let source_line = self
+                .source_lines
+                .get(index)
+                .map(|range| &self.source[range.clone()]);
+            match source_line {
+                // Parsing the line again is cheaper than serializing, and verifies that the record
+                // is unchanged
+                Some(line)
+                    if Record::from_str(line, &mut data_buffer)
+                        .is_ok_and(|source_record| source_record == record.as_record()) =>
+                {
+                    srecord_str.push_str(line)
+                }
+                _ => srecord_str.push_str(&record.serialize()),
+            }
+            srecord_str.push_str(line_ending);
+        }
+        if !self.source.is_empty() && !self.source.ends_with('\n') {
+            srecord_str.truncate(srecord_str.len().saturating_sub(line_ending.len()));
+        }
+        srecord_str
+    }
+}
+
+impl FromStr for SRecordDocument {
+    type Err = SRecordParseError;
+
+    /// Parses every line of an SRecord file into an [`OwnedRecord`]. Only the individual records
+    /// are validated, not their order or contents across records.
+    fn from_str(srecord_str: &str) -> Result<Self, Self::Err> {
+        let mut records = Vec::new();
+        let mut source_lines = Vec::new();
+        let mut line_start = 0;
+        for line in srecord_str.split_inclusive('\n') {
+            let line_content = line.strip_suffix('\n').unwrap_or(line);
+            let line_content = line_content.strip_suffix('\r').unwrap_or(line_content);
+            records.push(OwnedRecord::from_str(line_content)?);
+            source_lines.push(line_start..line_start + line_content.len());
+            line_start += line.len();
+        }
+        Ok(SRecordDocument {
+            records,
+            source: srecord_str.to_string(),
+            source_lines,
+        })
+    }
+}
@@ -0,0 +1,33 @@
+/// Byte order of multi-byte integers stored in the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte at the lowest address.
+    Little,
+    /// Most significant byte at the lowest address.
+    Big,
+}
+
+/// Returns the unsigned integer stored in `bytes` with byte order `endianness`. `bytes` must not
+/// be longer than 8 bytes.
+pub(crate) fn int_from_bytes(bytes: &[u8], endianness: Endianness) -> u64 {
+    let fold = |value: u64, &byte: &u8| (value << 8) | byte as u64;
+    match endianness {
+        Endianness::Little => bytes.iter().rev().fold(0, fold),
+        Endianness::Big => bytes.iter().fold(0, fold),
+    }
+}
+
+/// Returns the `size` bytes of `value` in byte order `endianness`.
+pub(crate) fn int_to_bytes(value: u64, size: usize, endianness: Endianness) -> Vec<u8> {
+    match endianness {
+        Endianness::Little => value.to_le_bytes()[..size].to_vec(),
+        Endianness::Big => value.to_be_bytes()[8 - size..].to_vec(),
+    }
+}
+
+/// Panics if `size` is not a valid size in bytes of an integer read or written as a `u64`.
+pub(crate) fn check_int_size(size: usize) {
+    if !(1..=8).contains(&size) {
+        panic!("Integer size {size} is not between 1 and 8 bytes");
+    }
+}
@@ -1,3 +1,13 @@
+use std::io;
+use std::ops::Range;
+
+#[cfg(doc)]
+use crate::srecord::{
+    OverlapPolicy, SRecordFile, SRecordParser, SerializationOptions, Transaction,
+};
+#[cfg(all(doc, feature = "unstable"))]
+use crate::srecord::{Pipeline, RemapTable, StartAddressPolicy};
+
 /// Contains error information about an error encountered in an [`SRecordFile`].
 #[derive(Debug, PartialEq, Eq)]
 pub struct SRecordParseError {
@@ -6,7 +16,7 @@ pub struct SRecordParseError {
 }
 
 /// Defines different categories of errors that are checked for.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorType {
     /// Early, unexpected end of line when parsing record type (S*)
     EolWhileParsingRecordType,
@@ -41,6 +51,9 @@ pub enum ErrorType {
     InvalidData,
     /// Overlapping data (data for same address encountered multiple times
     OverlappingData,
+    /// Data extends past the highest address of the record type, i.e. 0xFFFF for S1, 0xFFFFFF for
+    /// S2 or 0xFFFFFFFF for S3
+    AddressOverflowForRecordType,
 
     /// Invalid checksum (e.g. invalid characters)
     InvalidChecksum,
@@ -58,4 +71,236 @@ pub enum ErrorType {
 
     /// Record type does not match file type (e.g. S1 record in S28 file)
     RecordTypeNotMatchingFileType,
+
+    /// Data record (S1|2|3) found after the record count (S5|6)
+    DataAfterCountRecord,
+    /// Record found after the start address (S7|8|9), which must be the last record in the file
+    RecordAfterTerminator,
+    /// Line pushed with [`SRecordParser::push_line`] while an incomplete line pushed with
+    /// [`SRecordParser::push_bytes`] is pending
+    IncompleteLinePending,
+
+    /// Line contains bytes that are not ASCII, and was skipped by
+    /// [`SRecordFile::from_bytes_lossy`]
+    NonAsciiLine,
+}
+
+/// Error returned by [`SRecordFile::transplant`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum TransplantError {
+    /// Translated destination address range does not fit in a `u64`.
+    AddressOverflow,
+    /// Destination address range overlaps existing data, and the [`OverlapPolicy`] is
+    /// [`OverlapPolicy::Error`]. Contains the first overlapping address range in the destination.
+    OverlappingData(Range<u64>),
+    /// Merged start address differs from the existing start address, and the
+    /// `StartAddressPolicy` is `StartAddressPolicy::Error`. Contains the existing and the
+    /// merged start address
+    ConflictingStartAddresses(u64, u64),
+    /// Input selected by `StartAddressPolicy::RequireFromInput` does not exist or has no start
+    /// address. Reported with a `PipelineError::step_index` equal to the number of steps in the
+    /// pipeline. Contains the index of the input
+    MissingStartAddress(usize),
+    /// [`SerializationOptions`] passed to `Pipeline::write` are invalid. Reported with a
+    /// `PipelineError::step_index` equal to the number of steps in the pipeline
+    Serialization(SerializationError),
+}
+
+/// Error returned by [`SRecordFile::merge`]. Nothing has been merged.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// Data of the merged file overlaps existing data, and the [`OverlapPolicy`] is
+    /// [`OverlapPolicy::Error`]. Contains the first overlapping address range.
+    OverlappingData(Range<u64>),
+    /// Both files have different header data, and the [`OverlapPolicy`] is
+    /// [`OverlapPolicy::Error`].
+    ConflictingHeaders,
+    /// Both files have different start addresses, and the [`OverlapPolicy`] is
+    /// [`OverlapPolicy::Error`]. Contains the existing and the merged start address.
+    ConflictingStartAddresses(u64, u64),
+}
+
+/// Error returned by [`SRecordFile::offset`]. No address has been changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetError {
+    /// Offset data or start address would be below address 0.
+    AddressUnderflow,
+    /// Offset data or start address would not fit in a `u64`.
+    AddressOverflow,
+}
+
+/// Error returned by [`SRecordFile::append_trailer`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum AppendTrailerError {
+    /// Alignment is zero.
+    InvalidAlignment,
+    /// Aligned trailer address range does not fit in a `u64`.
+    AddressOverflow,
+    /// Trailer would end after the maximum end address. Contains the exclusive end address the
+    /// trailer would have had.
+    ExceedsMaxEndAddress(u64),
+}
+
+/// Error returned by [`SRecordFile::extract_symbol`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExtractSymbolError {
+    /// No symbol with the given name exists.
+    SymbolNotFound,
+    /// Alignment is zero.
+    InvalidAlignment,
+    /// Aligned symbol address range does not fit in a `u64`.
+    AddressOverflow,
+}
+
+/// Error returned by [`SRecordFile::align_regions_to`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum AlignRegionsError {
+    /// Sector size is zero.
+    InvalidSectorSize,
+    /// Aligned end address of a region does not fit in a `u64`.
+    AddressOverflow,
+}
+
+/// Error returned when serializing an [`SRecordFile`] with invalid [`SerializationOptions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SerializationError {
+    /// [`SerializationOptions::data_record_size`] is zero.
+    ZeroDataRecordSize,
+    /// [`SerializationOptions::data_record_size`] is larger than the number of data bytes that fit
+    /// in the selected type of data record. Contains the maximum data record size.
+    DataRecordSizeTooLarge(usize),
+    /// Number of data records does not fit in the selected
+    /// [`SerializationOptions::count_record_type`]. Contains the number of data records.
+    RecordCountTooLarge(usize),
+    /// Start address does not fit in the start address record matching the selected
+    /// [`SerializationOptions::data_record_type`]. Contains the start address.
+    StartAddressTooLarge(u64),
+    /// Data address does not fit in the addresses of the output format. Contains the highest data
+    /// address.
+    AddressTooLargeForFormat(u64),
+}
+
+/// Error returned by [`SRecordFile::apply`]. Nothing has been changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApplyDiffError {
+    /// Data of the file does not match the old data of the diff. Contains the first address range
+    /// that does not match.
+    DataMismatch(Range<u64>),
+    /// File contains data where the diff adds data. Contains the first address range that contains
+    /// data.
+    UnexpectedData(Range<u64>),
+}
+
+/// Error returned by [`SRecordFile::byte_swap`]. No data has been changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ByteSwapError {
+    /// Word size is not 2, 4 or 8.
+    InvalidWordSize,
+    /// Start or end of the range is not a multiple of the word size.
+    UnalignedRange,
+    /// Part of the range contains no data. Contains the first address range without data.
+    MissingData(Range<u64>),
+}
+
+/// Error returned by [`SRecordFile::normalize`] and [`SRecordFile::bridge_gaps`]. No data chunk
+/// has been changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NormalizeError {
+    /// Data of a data chunk extends past address `u64::MAX`. Contains the address of the data
+    /// chunk.
+    AddressOverflow(u64),
+    /// Two data chunks overlap. Contains the first overlapping address range.
+    OverlappingData(Range<u64>),
+}
+
+/// Error returned by [`SRecordFile::validate_start_address`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartAddressError {
+    /// There is no data at the start address. Contains the start address.
+    NotInData(u64),
+    /// Start address does not fit in any start address record (S7/S8/S9). Contains the start
+    /// address.
+    TooLarge(u64),
+}
+
+/// Error returned when reading an SRecord file from disk.
+#[derive(Debug)]
+pub enum ReadFileError {
+    /// File could not be opened or read.
+    Io(io::Error),
+    /// Contents of the file are not a valid SRecord file.
+    Parse(SRecordParseError),
+}
+
+/// Error returned when converting an object file into an [`SRecordFile`].
+#[cfg(feature = "object")]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ObjectConversionError {
+    /// Data of a segment could not be read from the object file.
+    Read(object::Error),
+    /// Data of two segments overlaps.
+    OverlappingSegments,
+}
+
+/// Error returned by [`SRecordFile::transaction`]. No edit of the transaction has been applied.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TransactionError<E> {
+    /// The closure staging the edits returned an error. Contains the error.
+    Aborted(E),
+    /// Data written by an edit ends past the highest `u64` address. Contains the index of the
+    /// edit.
+    AddressOverflow(usize),
+    /// An edit writes or erases an address in a range passed to [`Transaction::protect`].
+    /// Contains the index of the edit.
+    Protected(usize),
+    /// An edit writes an address outside of the ranges passed to
+    /// [`Transaction::memory_layout`]. Contains the index of the edit.
+    OutsideMemoryLayout(usize),
+    /// An edit added with [`Transaction::insert`] writes an address that already contains data.
+    /// Contains the index of the edit.
+    OverlappingData(usize),
+}
+
+#[cfg(feature = "unstable")]
+/// Error returned when parsing a [`RemapTable`] from text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemapTableParseError {
+    /// Zero-based index of the line that could not be parsed.
+    pub line_index: usize,
+}
+
+#[cfg(feature = "unstable")]
+/// Contains error information about an error encountered when running a [`Pipeline`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct PipelineError {
+    /// Zero-based index of the step that failed, in the order the steps were added.
+    pub step_index: usize,
+    /// Type of error encountered.
+    pub error_type: PipelineErrorType,
+}
+
+#[cfg(feature = "unstable")]
+/// Defines different categories of errors that can occur in a [`Pipeline`] step.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PipelineErrorType {
+    /// Resulting address does not fit in a `u64`
+    AddressOverflow,
+    /// Address range of a CRC step contains a gap. Contains the first gap
+    IncompleteCrcRange(Range<u64>),
+    /// CRC would be written inside the address range it is calculated over
+    CrcInsideRange,
+    /// Merged data overlaps existing data, and the [`OverlapPolicy`] is [`OverlapPolicy::Error`].
+    /// Contains the first overlapping address range
+    OverlappingData(Range<u64>),
+    /// Merged start address differs from the existing start address, and the
+    /// `StartAddressPolicy` is `StartAddressPolicy::Error`. Contains the existing and the
+    /// merged start address
+    ConflictingStartAddresses(u64, u64),
+    /// Input selected by `StartAddressPolicy::RequireFromInput` does not exist or has no start
+    /// address. Reported with a `PipelineError::step_index` equal to the number of steps in the
+    /// pipeline. Contains the index of the input
+    MissingStartAddress(usize),
+    /// [`SerializationOptions`] passed to `Pipeline::write` are invalid. Reported with a
+    /// `PipelineError::step_index` equal to the number of steps in the pipeline
+    Serialization(SerializationError),
 }
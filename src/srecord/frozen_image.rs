@@ -0,0 +1,69 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::srecord::SRecordFile;
+
+/// Immutable, shared snapshot of an [`SRecordFile`].
+///
+/// A [`FrozenImage`] is `Send + Sync`, and cloning it only clones a reference to the same
+/// underlying data, so one parsed image can be shared between threads without locking. It
+/// dereferences to [`SRecordFile`], giving access to the complete read-only API.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use std::thread;
+/// use srex::srecord::SRecordFile;
+///
+/// let image = SRecordFile::from_str("S107100000010203E2").unwrap().freeze();
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let image = image.clone();
+///         thread::spawn(move || image[0x1000 + i])
+///     })
+///     .collect();
+/// let data: Vec<u8> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+/// assert_eq!(data, [0x00, 0x01, 0x02, 0x03]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrozenImage {
+    /// Shared, never mutated file.
+    srecord_file: Arc<SRecordFile>,
+}
+
+impl From<SRecordFile> for FrozenImage {
+    /// Freezes `srecord_file`, see [`SRecordFile::freeze`].
+    fn from(srecord_file: SRecordFile) -> Self {
+        FrozenImage {
+            srecord_file: Arc::new(srecord_file),
+        }
+    }
+}
+
+impl Deref for FrozenImage {
+    type Target = SRecordFile;
+
+    /// Returns a shared reference to the frozen [`SRecordFile`].
+    fn deref(&self) -> &SRecordFile {
+        &self.srecord_file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frozen_image_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenImage>();
+    }
+
+    #[test]
+    fn test_frozen_image_clone_shares_data() {
+        let image = SRecordFile::new().freeze();
+        let clone = image.clone();
+        assert!(Arc::ptr_eq(&image.srecord_file, &clone.srecord_file));
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// Width in bytes of the module name field.
+const MODULE_NAME_LEN: usize = 20;
+/// Width in bytes of the version field.
+const VERSION_LEN: usize = 2;
+/// Width in bytes of the revision field.
+const REVISION_LEN: usize = 2;
+/// Maximum length in bytes of the description field.
+const MAX_DESCRIPTION_LEN: usize = 36;
+
+/// Fields of a header record following the common S0 convention: a 20 byte module name, a 2 byte
+/// version, a 2 byte revision and a description of up to 36 bytes, all padded with spaces.
+///
+/// Returned by [`SRecordFile::header_info`] and written with [`SRecordFile::set_header_info`].
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::HeaderInfo;
+///
+/// let header_info = HeaderInfo::from_bytes(b"bootloader          0102Release build");
+/// assert_eq!(header_info.module_name, "bootloader");
+/// assert_eq!(header_info.version, "01");
+/// assert_eq!(header_info.revision, "02");
+/// assert_eq!(header_info.description, "Release build");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HeaderInfo {
+    /// Name of the module, at most 20 bytes.
+    pub module_name: String,
+    /// Version number, at most 2 bytes.
+    pub version: String,
+    /// Revision number, at most 2 bytes.
+    pub revision: String,
+    /// Free text description, at most 36 bytes.
+    pub description: String,
+}
+
+impl HeaderInfo {
+    /// Splits `header_data` into its fields. Trailing spaces and NUL bytes are removed from every
+    /// field, and bytes that are not valid UTF-8 are replaced. Fields missing from short headers
+    /// are empty, and bytes after the description are ignored.
+    pub fn from_bytes(header_data: &[u8]) -> Self {
+        let mut remaining = header_data;
+        let mut field = |len: usize| {
+            let (field, rest) = remaining.split_at(len.min(remaining.len()));
+            remaining = rest;
+            let end = field
+                .iter()
+                .rposition(|&byte| byte != b' ' && byte != 0)
+                .map_or(0, |index| index + 1);
+            String::from_utf8_lossy(&field[..end]).into_owned()
+        };
+        HeaderInfo {
+            module_name: field(MODULE_NAME_LEN),
+            version: field(VERSION_LEN),
+            revision: field(REVISION_LEN),
+            description: field(MAX_DESCRIPTION_LEN),
+        }
+    }
+
+    /// Returns the header data with the module name, version and revision padded with spaces to
+    /// their full width, followed by the description. Fields that are too long are truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::HeaderInfo;
+    ///
+    /// let header_info = HeaderInfo {
+    ///     module_name: String::from("app"),
+    ///     version: String::from("1"),
+    ///     revision: String::from("0"),
+    ///     description: String::new(),
+    /// };
+    /// assert_eq!(header_info.to_bytes(), b"app                 1 0 ");
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut header_data = Vec::with_capacity(
+            MODULE_NAME_LEN + VERSION_LEN + REVISION_LEN + self.description.len(),
+        );
+        for (field, len) in [
+            (&self.module_name, MODULE_NAME_LEN),
+            (&self.version, VERSION_LEN),
+            (&self.revision, REVISION_LEN),
+        ] {
+            let field = &field.as_bytes()[..field.len().min(len)];
+            header_data.extend_from_slice(field);
+            header_data.resize(header_data.len() + len - field.len(), b' ');
+        }
+        let description = self.description.as_bytes();
+        header_data.extend_from_slice(&description[..description.len().min(MAX_DESCRIPTION_LEN)]);
+        header_data
+    }
+}
@@ -0,0 +1,157 @@
+use crate::srecord::error::{ErrorType, SRecordParseError, SerializationError};
+use crate::srecord::{DataChunk, SRecordFile};
+
+/// Maximum number of data bytes in each Intel HEX data record generated from an [`SRecordFile`].
+const IHEX_DATA_RECORD_SIZE: usize = 16;
+
+impl TryFrom<&[ihex::Record]> for SRecordFile {
+    type Error = SRecordParseError;
+
+    /// Collects the data and start address of Intel HEX records into an [`SRecordFile`]. Requires
+    /// the `ihex` feature.
+    ///
+    /// Returns an error if any data overlaps, if the records contain more than one start address,
+    /// or if any record comes after the end of file record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let records = [
+    ///     ihex::Record::ExtendedLinearAddress(0x0001),
+    ///     ihex::Record::Data { offset: 0x1000, value: vec![0x00, 0x01, 0x02, 0x03] },
+    ///     ihex::Record::StartLinearAddress(0x00011000),
+    ///     ihex::Record::EndOfFile,
+    /// ];
+    /// let srecord_file = SRecordFile::try_from(records.as_slice()).unwrap();
+    /// assert_eq!(srecord_file[0x11000..0x11004], [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(srecord_file.start_address, Some(0x11000));
+    /// ```
+    fn try_from(records: &[ihex::Record]) -> Result<Self, Self::Error> {
+        let mut srecord_file = SRecordFile::new();
+        let mut base_address = 0u64;
+        let mut end_of_file_found = false;
+        for record in records {
+            if end_of_file_found {
+                return Err(SRecordParseError {
+                    error_type: ErrorType::RecordAfterTerminator,
+                });
+            }
+            match record {
+                ihex::Record::Data { offset, value } => {
+                    let address = base_address + *offset as u64;
+                    match srecord_file.data_chunks.last_mut() {
+                        Some(last_chunk) if last_chunk.end_address() == address => {
+                            last_chunk.data.extend_from_slice(value)
+                        }
+                        _ => srecord_file.data_chunks.push(DataChunk {
+                            address,
+                            data: value.clone(),
+                        }),
+                    }
+                }
+                ihex::Record::EndOfFile => end_of_file_found = true,
+                ihex::Record::ExtendedSegmentAddress(segment) => {
+                    base_address = (*segment as u64) << 4
+                }
+                ihex::Record::ExtendedLinearAddress(upper_address) => {
+                    base_address = (*upper_address as u64) << 16
+                }
+                ihex::Record::StartSegmentAddress { cs, ip } => {
+                    let start_address = ((*cs as u64) << 4) + *ip as u64;
+                    if srecord_file.start_address.replace(start_address).is_some() {
+                        return Err(SRecordParseError {
+                            error_type: ErrorType::MultipleStartAddresses,
+                        });
+                    }
+                }
+                ihex::Record::StartLinearAddress(start_address) => {
+                    if srecord_file
+                        .start_address
+                        .replace(*start_address as u64)
+                        .is_some()
+                    {
+                        return Err(SRecordParseError {
+                            error_type: ErrorType::MultipleStartAddresses,
+                        });
+                    }
+                }
+            }
+        }
+        srecord_file
+            .data_chunks
+            .sort_by_key(|data_chunk| data_chunk.address);
+        srecord_file.merge_data_chunks(None)?;
+        Ok(srecord_file)
+    }
+}
+
+impl TryFrom<&SRecordFile> for Vec<ihex::Record> {
+    type Error = SerializationError;
+
+    /// Generates I32HEX records for the data and start address of `srecord_file`, ending with an
+    /// end of file record. Requires the `ihex` feature.
+    ///
+    /// Data records contain up to 16 bytes, and never cross a 64 KiB boundary. The header data has
+    /// no equivalent in Intel HEX, and is not included.
+    ///
+    /// Returns an error if any data or the start address does not fit in 32 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S3090001100000010203DF\nS70500011000E9").unwrap();
+    /// let records = Vec::<ihex::Record>::try_from(&srecord_file).unwrap();
+    /// assert_eq!(
+    ///     records,
+    ///     [
+    ///         ihex::Record::ExtendedLinearAddress(0x0001),
+    ///         ihex::Record::Data { offset: 0x1000, value: vec![0x00, 0x01, 0x02, 0x03] },
+    ///         ihex::Record::StartLinearAddress(0x00011000),
+    ///         ihex::Record::EndOfFile,
+    ///     ],
+    /// );
+    /// ```
+    fn try_from(srecord_file: &SRecordFile) -> Result<Self, Self::Error> {
+        let mut records = Vec::new();
+        let mut upper_address = 0;
+        for data_chunk in srecord_file.data_chunks.iter() {
+            let end_address = data_chunk.end_address();
+            if end_address > 1 << 32 {
+                return Err(SerializationError::AddressTooLargeForFormat(
+                    end_address - 1,
+                ));
+            }
+            let mut address = data_chunk.address;
+            while address < end_address {
+                // Records must not cross into the next 64 KiB segment
+                let segment_end_address = (address | 0xFFFF) + 1;
+                let record_end_address = (address + IHEX_DATA_RECORD_SIZE as u64)
+                    .min(segment_end_address)
+                    .min(end_address);
+                if address >> 16 != upper_address {
+                    upper_address = address >> 16;
+                    records.push(ihex::Record::ExtendedLinearAddress(upper_address as u16));
+                }
+                let start_index = (address - data_chunk.address) as usize;
+                let end_index = (record_end_address - data_chunk.address) as usize;
+                records.push(ihex::Record::Data {
+                    offset: address as u16,
+                    value: data_chunk.data[start_index..end_index].to_vec(),
+                });
+                address = record_end_address;
+            }
+        }
+        if let Some(start_address) = srecord_file.start_address {
+            let start_address = u32::try_from(start_address)
+                .map_err(|_| SerializationError::StartAddressTooLarge(start_address))?;
+            records.push(ihex::Record::StartLinearAddress(start_address));
+        }
+        records.push(ihex::Record::EndOfFile);
+        Ok(records)
+    }
+}
@@ -0,0 +1,320 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::srecord::error::{ErrorType, ReadFileError, SRecordParseError};
+use crate::srecord::utils::validate_record_bytes;
+use crate::srecord::{Record, RecordType};
+
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// First bytes of an index file written by [`IndexedSRecordFile::save_index`].
+const INDEX_MAGIC: &[u8; 8] = b"SREXIDX1";
+
+/// Read-only handle to an SRecord file on disk, that reads data by parsing only the records that
+/// contain it.
+///
+/// Opening the file validates every record and builds an index of the address and file offset of
+/// each data record, but keeps no data in memory. Each [`get`](IndexedSRecordFile::get) then reads
+/// and parses only the records overlapping the requested address range, which makes repeated
+/// small reads from huge files much cheaper than parsing the whole file into an [`SRecordFile`].
+/// The index can be saved and reused with [`open_with_index`](IndexedSRecordFile::open_with_index)
+/// to skip validating the file on later opens.
+///
+/// The file must not be modified while the handle is open.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::IndexedSRecordFile;
+///
+/// let indexed_file = IndexedSRecordFile::open("tests/srec_files/wikipedia.s19").unwrap();
+/// assert_eq!(indexed_file.get(0x38..0x3D).unwrap().unwrap(), b"Hello");
+/// assert_eq!(indexed_file.get(0x44..0x50).unwrap(), None);
+/// assert_eq!(indexed_file.start_address(), Some(0));
+/// ```
+#[derive(Debug)]
+pub struct IndexedSRecordFile {
+    /// The indexed SRecord file.
+    file: File,
+    /// Length of the file in bytes when it was indexed.
+    file_length: u64,
+    /// Data records sorted by address.
+    index: Vec<IndexEntry>,
+    /// Start address of the start address record (S7/S8/S9), if any.
+    start_address: Option<u64>,
+}
+
+/// Location of a data record in an indexed file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IndexEntry {
+    /// Address of the first data byte of the record.
+    address: u64,
+    /// Offset of the first character of the record in the file.
+    offset: u64,
+    /// Length of the record in bytes, without line ending.
+    line_length: u16,
+    /// Number of data bytes in the record.
+    num_data_bytes: u8,
+}
+
+impl IndexEntry {
+    /// Returns the address after the last data byte of the record.
+    fn end_address(&self) -> u64 {
+        self.address + self.num_data_bytes as u64
+    }
+}
+
+impl IndexedSRecordFile {
+    /// Opens and indexes the SRecord file at `path`.
+    ///
+    /// Every line must be a single valid record, as checked by
+    /// [`validate_record_bytes`](crate::srecord::utils::validate_record_bytes), and the count
+    /// record, if any, is checked against the number of data records. Returns an error if the file
+    /// cannot be read, if any record is invalid, if any data overlaps, or if there is more than one
+    /// header or start address record. Unlike [`SRecordFile::from_bytes`], there are no parse
+    /// options: invalid lines are never skipped, and the data is not read until requested.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ReadFileError> {
+        let file = File::open(path).map_err(ReadFileError::Io)?;
+        let mut reader = BufReader::new(&file);
+        let mut index = Vec::new();
+        let mut start_address = None;
+        let mut header_found = false;
+        let mut record_counts = Vec::new();
+        let mut num_data_records = 0u64;
+        let mut offset = 0u64;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let num_read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(ReadFileError::Io)?;
+            if num_read == 0 {
+                break;
+            }
+            let line_offset = offset;
+            offset += num_read as u64;
+            let record_bytes = line.strip_suffix(b"\n").unwrap_or(&line);
+            let record_bytes = record_bytes.strip_suffix(b"\r").unwrap_or(record_bytes);
+            let record_info = validate_record_bytes(record_bytes).map_err(parse_error)?;
+            match record_info.record_type {
+                RecordType::S0 => match header_found {
+                    true => return Err(parse_error(ErrorType::MultipleHeaderRecords)),
+                    false => header_found = true,
+                },
+                RecordType::S1 | RecordType::S2 | RecordType::S3 => {
+                    num_data_records += 1;
+                    if record_info.num_data_bytes > 0 {
+                        index.push(IndexEntry {
+                            address: record_info.address,
+                            offset: line_offset,
+                            line_length: record_bytes.len() as u16,
+                            num_data_bytes: record_info.num_data_bytes as u8,
+                        });
+                    }
+                }
+                RecordType::S5 | RecordType::S6 => record_counts.push(record_info.address),
+                RecordType::S7 | RecordType::S8 | RecordType::S9 => {
+                    if start_address.replace(record_info.address).is_some() {
+                        return Err(parse_error(ErrorType::MultipleStartAddresses));
+                    }
+                }
+            }
+        }
+        if record_counts
+            .iter()
+            .any(|&record_count| record_count != num_data_records)
+        {
+            return Err(parse_error(
+                ErrorType::CalculatedNumRecordsNotMatchingParsedNumRecords,
+            ));
+        }
+        index.sort_by_key(|entry| entry.address);
+        if index
+            .windows(2)
+            .any(|entries| entries[0].end_address() > entries[1].address)
+        {
+            return Err(parse_error(ErrorType::OverlappingData));
+        }
+        Ok(IndexedSRecordFile {
+            file,
+            file_length: offset,
+            index,
+            start_address,
+        })
+    }
+
+    /// Opens the SRecord file at `path` using the index saved at `index_path`, or indexes the file
+    /// with [`open`](IndexedSRecordFile::open) and saves the index to `index_path` if there is no
+    /// valid index for the file.
+    ///
+    /// An index is only used if the length of the file matches the length when it was indexed.
+    pub fn open_with_index<P: AsRef<Path>, Q: AsRef<Path>>(
+        path: P,
+        index_path: Q,
+    ) -> Result<Self, ReadFileError> {
+        let file = File::open(&path).map_err(ReadFileError::Io)?;
+        let file_length = file.metadata().map_err(ReadFileError::Io)?.len();
+        if let Ok(index_file) = File::open(&index_path) {
+            if let Ok(Some((index, start_address))) =
+                read_index(BufReader::new(index_file), file_length)
+            {
+                return Ok(IndexedSRecordFile {
+                    file,
+                    file_length,
+                    index,
+                    start_address,
+                });
+            }
+        }
+        let indexed_file = IndexedSRecordFile::open(path)?;
+        indexed_file
+            .save_index(index_path)
+            .map_err(ReadFileError::Io)?;
+        Ok(indexed_file)
+    }
+
+    /// Saves the index of the file to `index_path`, to be used by
+    /// [`open_with_index`](IndexedSRecordFile::open_with_index).
+    pub fn save_index<P: AsRef<Path>>(&self, index_path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(index_path)?);
+        writer.write_all(INDEX_MAGIC)?;
+        writer.write_all(&self.file_length.to_le_bytes())?;
+        match self.start_address {
+            Some(start_address) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&start_address.to_le_bytes())?;
+            }
+            None => writer.write_all(&[0; 9])?,
+        }
+        writer.write_all(&(self.index.len() as u64).to_le_bytes())?;
+        for entry in self.index.iter() {
+            writer.write_all(&entry.address.to_le_bytes())?;
+            writer.write_all(&entry.offset.to_le_bytes())?;
+            writer.write_all(&entry.line_length.to_le_bytes())?;
+            writer.write_all(&[entry.num_data_bytes])?;
+        }
+        writer.flush()
+    }
+
+    /// Returns the start address of the file, if any.
+    pub fn start_address(&self) -> Option<u64> {
+        self.start_address
+    }
+
+    /// Reads the data in `range` from the file, or returns `None` if any address in `range` has no
+    /// data. Only the records overlapping `range` are read and parsed.
+    ///
+    /// Returns an error if the file cannot be read, or if a record no longer matches the index
+    /// because the file was modified.
+    pub fn get(&self, range: Range<u64>) -> Result<Option<Vec<u8>>, ReadFileError> {
+        if range.start >= range.end {
+            return Ok(Some(Vec::new()));
+        }
+        let first_index = self
+            .index
+            .partition_point(|entry| entry.end_address() <= range.start);
+        let last_index = self
+            .index
+            .partition_point(|entry| entry.address < range.end);
+        // Indexed records do not overlap, so `range` is covered only if they hold all its bytes.
+        // Checking first also bounds the allocation by the size of the file, not of `range`.
+        let num_covered_bytes: u64 = self.index[first_index..last_index]
+            .iter()
+            .map(|entry| entry.end_address().min(range.end) - entry.address.max(range.start))
+            .sum();
+        if num_covered_bytes != range.end - range.start {
+            return Ok(None);
+        }
+        let mut data = Vec::with_capacity(num_covered_bytes as usize);
+        let mut address = range.start;
+        let mut line = Vec::new();
+        let mut data_buffer = [0u8; 256];
+        for entry in self.index[first_index..].iter() {
+            if address == range.end {
+                break;
+            }
+            if entry.address > address {
+                return Ok(None);
+            }
+            line.resize(entry.line_length as usize, 0);
+            let mut file = &self.file;
+            file.seek(SeekFrom::Start(entry.offset))
+                .map_err(ReadFileError::Io)?;
+            file.read_exact(&mut line).map_err(ReadFileError::Io)?;
+            let record =
+                Record::from_bytes(&line, &mut data_buffer).map_err(ReadFileError::Parse)?;
+            let record_data = match record {
+                Record::S1Record(data_record)
+                | Record::S2Record(data_record)
+                | Record::S3Record(data_record)
+                    if data_record.address == entry.address
+                        && data_record.data.len() == entry.num_data_bytes as usize =>
+                {
+                    data_record.data
+                }
+                _ => return Err(parse_error(ErrorType::InvalidAddress)),
+            };
+            let end_address = range.end.min(entry.end_address());
+            data.extend_from_slice(
+                &record_data
+                    [(address - entry.address) as usize..(end_address - entry.address) as usize],
+            );
+            address = end_address;
+        }
+        match address == range.end {
+            true => Ok(Some(data)),
+            false => Ok(None),
+        }
+    }
+}
+
+/// Returns a [`ReadFileError`] for a record of type `error_type`.
+fn parse_error(error_type: ErrorType) -> ReadFileError {
+    ReadFileError::Parse(SRecordParseError { error_type })
+}
+
+/// Reads an index written by [`IndexedSRecordFile::save_index`], and returns the index and start
+/// address, or `None` if it is not an index of a file of `file_length` bytes.
+fn read_index<R: Read>(
+    mut reader: R,
+    file_length: u64,
+) -> io::Result<Option<(Vec<IndexEntry>, Option<u64>)>> {
+    fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != INDEX_MAGIC || read_u64(&mut reader)? != file_length {
+        return Ok(None);
+    }
+    let mut has_start_address = [0u8; 1];
+    reader.read_exact(&mut has_start_address)?;
+    let start_address = read_u64(&mut reader)?;
+    let start_address = match has_start_address[0] {
+        0 => None,
+        _ => Some(start_address),
+    };
+    let num_entries = read_u64(&mut reader)?;
+    let mut index = Vec::new();
+    for _ in 0..num_entries {
+        let address = read_u64(&mut reader)?;
+        let offset = read_u64(&mut reader)?;
+        let mut line_length = [0u8; 2];
+        reader.read_exact(&mut line_length)?;
+        let mut num_data_bytes = [0u8; 1];
+        reader.read_exact(&mut num_data_bytes)?;
+        index.push(IndexEntry {
+            address,
+            offset,
+            line_length: u16::from_le_bytes(line_length),
+            num_data_bytes: num_data_bytes[0],
+        });
+    }
+    Ok(Some((index, start_address)))
+}
@@ -1,12 +1,85 @@
+mod address;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+pub mod checksum;
 mod data_chunk;
+mod data_reader;
+#[cfg(feature = "unstable")]
+mod document;
+mod endianness;
 mod error;
+mod frozen_image;
+mod header_info;
+#[cfg(feature = "ihex")]
+mod ihex_conversion;
+mod indexed_srecord_file;
+#[cfg(feature = "object")]
+mod object_conversion;
+mod overlap_policy;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod parse_options;
+#[cfg(feature = "unstable")]
+mod pipeline;
 pub mod record;
+mod record_layout;
 mod record_type;
+#[cfg(feature = "unstable")]
+mod remap_table;
+mod serialization_options;
 pub mod slice_index;
+mod srecord_cursor;
+mod srecord_diff;
 mod srecord_file;
+mod srecord_parser;
+mod start_address_info;
+#[cfg(feature = "unstable")]
+mod start_address_policy;
+mod symbol;
+#[cfg(feature = "unstable")]
+mod tektronix;
+mod transaction;
 pub mod utils;
+mod validation;
 
+pub use self::address::{Address, IntoAddress};
 pub use self::data_chunk::DataChunk;
-pub use self::record::{CountRecord, DataRecord, HeaderRecord, Record, StartAddressRecord};
+pub use self::data_reader::DataReader;
+#[cfg(feature = "unstable")]
+pub use self::document::SRecordDocument;
+pub use self::endianness::Endianness;
+#[cfg(feature = "object")]
+pub use self::error::ObjectConversionError;
+pub use self::error::{
+    AlignRegionsError, AppendTrailerError, ApplyDiffError, ByteSwapError, ErrorType,
+    ExtractSymbolError, MergeError, NormalizeError, OffsetError, ReadFileError, SRecordParseError,
+    SerializationError, StartAddressError, TransactionError, TransplantError,
+};
+#[cfg(feature = "unstable")]
+pub use self::error::{PipelineError, PipelineErrorType, RemapTableParseError};
+pub use self::frozen_image::FrozenImage;
+pub use self::header_info::HeaderInfo;
+pub use self::indexed_srecord_file::IndexedSRecordFile;
+pub use self::overlap_policy::OverlapPolicy;
+pub use self::parse_options::{GapFill, ParseOptions, ParseStats, ParseWarning};
+#[cfg(feature = "unstable")]
+pub use self::pipeline::Pipeline;
+pub use self::record::{
+    iter_records_str, BankRecord, CountRecord, DataRecord, HeaderRecord, OwnedDataRecord,
+    OwnedHeaderRecord, OwnedRecord, Record, RecordInfo, StartAddressRecord,
+};
+pub use self::record_layout::RecordLayout;
 pub use self::record_type::RecordType;
+#[cfg(feature = "unstable")]
+pub use self::remap_table::{RemapEntry, RemapTable};
+pub use self::serialization_options::{CountRecordType, DataRecordType, SerializationOptions};
+pub use self::srecord_cursor::{SRecordCursor, SRecordCursorMut};
+pub use self::srecord_diff::{ChangedData, SRecordDiff};
 pub use self::srecord_file::SRecordFile;
+pub use self::srecord_parser::SRecordParser;
+pub use self::start_address_info::StartAddressInfo;
+#[cfg(feature = "unstable")]
+pub use self::start_address_policy::StartAddressPolicy;
+pub use self::symbol::Symbol;
+pub use self::transaction::Transaction;
+pub use self::validation::{Severity, ValidationIssue, ValidationOptions};
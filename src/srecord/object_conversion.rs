@@ -0,0 +1,39 @@
+use object::read::{File, ReadRef};
+use object::{Object, ObjectKind, ObjectSegment};
+
+use crate::srecord::error::ObjectConversionError;
+use crate::srecord::{DataChunk, SRecordFile};
+
+impl<'data, R: ReadRef<'data>> TryFrom<&File<'data, R>> for SRecordFile {
+    type Error = ObjectConversionError;
+
+    /// Collects the file data of the loadable segments of an object file into an [`SRecordFile`],
+    /// with the entry point of executables as start address. Requires the `object` feature.
+    ///
+    /// Data that only occupies memory, e.g. the zero-initialized part of a segment, has no file
+    /// data and is not included.
+    ///
+    /// Returns an error if the segment data cannot be read, or if any segments overlap.
+    fn try_from(file: &File<'data, R>) -> Result<Self, Self::Error> {
+        let mut srecord_file = SRecordFile::new();
+        for segment in file.segments() {
+            let data = segment.data().map_err(ObjectConversionError::Read)?;
+            if !data.is_empty() {
+                srecord_file.data_chunks.push(DataChunk {
+                    address: segment.address(),
+                    data: data.to_vec(),
+                });
+            }
+        }
+        srecord_file
+            .data_chunks
+            .sort_by_key(|data_chunk| data_chunk.address);
+        srecord_file
+            .merge_data_chunks(None)
+            .map_err(|_| ObjectConversionError::OverlappingSegments)?;
+        if file.kind() == ObjectKind::Executable {
+            srecord_file.start_address = Some(file.entry());
+        }
+        Ok(srecord_file)
+    }
+}
@@ -0,0 +1,14 @@
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// Decides what happens when data written into an [`SRecordFile`] overlaps data that already
+/// exists at the same addresses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Overlapping data is an error, and nothing is written.
+    Error,
+    /// Existing data is kept, and only the addresses that do not contain any data are written.
+    KeepExisting,
+    /// Existing data is overwritten by the incoming data.
+    Overwrite,
+}
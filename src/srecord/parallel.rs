@@ -0,0 +1,229 @@
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::srecord::data_chunk::merge_chunk_lists;
+use crate::srecord::utils::{data_within, lines, normalized};
+use crate::srecord::{
+    DataChunk, OverlapPolicy, ParseOptions, ParseStats, Record, RecordLayout, SRecordFile,
+};
+
+/// Minimum number of bytes of input in each batch of lines parsed by one thread.
+const BATCH_SIZE: usize = 1 << 20;
+
+/// Records parsed from one batch of lines, before they are validated against the other batches.
+#[derive(Default)]
+struct ParsedBatch {
+    /// Number of records (lines) in the batch.
+    num_records: usize,
+    /// Number of data records (S1/S2/S3) in the batch.
+    num_data_records: usize,
+    /// Sorted and merged data of the data records.
+    data_chunks: Vec<DataChunk>,
+    /// Address ranges of the data records in the order they appear, if
+    /// [`ParseOptions::record_layout`] is set.
+    data_records: Vec<Range<u64>>,
+    /// Data of the header records (S0).
+    header_data: Vec<Vec<u8>>,
+    /// Record counts of the count records (S5/S6).
+    record_counts: Vec<usize>,
+    /// Start addresses of the start address records (S7/S8/S9).
+    start_addresses: Vec<u64>,
+    /// Line index in the batch of the last data record.
+    last_data_line: Option<usize>,
+    /// Line index in the batch of the first count or start address record.
+    first_trailer_line: Option<usize>,
+    /// Line index in the batch of the first start address record.
+    first_terminator_line: Option<usize>,
+}
+
+/// Parses `srecord_bytes` by splitting it into batches of lines that are parsed in parallel, and
+/// merging the parsed data at the end.
+///
+/// Returns `None` if the input is too small to be split, or if any problem is found, e.g. an
+/// invalid record, overlapping data or records that are out of place. The input must then be
+/// parsed sequentially, which reports the problem for the correct line.
+pub(crate) fn from_bytes_with_options(
+    srecord_bytes: &[u8],
+    options: &ParseOptions,
+) -> Option<(SRecordFile, ParseStats)> {
+    let batches = split_lines(srecord_bytes);
+    if batches.len() < 2 {
+        return None;
+    }
+    let address_filter = options.address_filter.clone().map(normalized);
+    let parsed_batches = batches
+        .par_iter()
+        .map(|batch| parse_batch(batch, options, address_filter.as_deref()))
+        .collect::<Option<Vec<ParsedBatch>>>()?;
+
+    // Records are only in place if all count and start address records come after all data
+    // records, and no record comes after a start address record
+    let mut line_index = 0;
+    let mut last_data_line = None;
+    let mut first_trailer_line = None;
+    let mut first_terminator_line = None;
+    for parsed_batch in parsed_batches.iter() {
+        let global = |line: Option<usize>| line.map(|line| line_index + line);
+        last_data_line = global(parsed_batch.last_data_line).or(last_data_line);
+        first_trailer_line = first_trailer_line.or(global(parsed_batch.first_trailer_line));
+        first_terminator_line =
+            first_terminator_line.or(global(parsed_batch.first_terminator_line));
+        line_index += parsed_batch.num_records;
+    }
+    let num_records = line_index;
+    if matches!((first_trailer_line, last_data_line), (Some(trailer), Some(data)) if data > trailer)
+    {
+        return None;
+    }
+    if matches!(first_terminator_line, Some(terminator) if terminator + 1 != num_records) {
+        return None;
+    }
+
+    let mut srecord_file = SRecordFile::new();
+    let mut stats = ParseStats {
+        num_records,
+        ..Default::default()
+    };
+    let mut chunk_lists = Vec::with_capacity(parsed_batches.len());
+    let mut data_records = Vec::new();
+    let mut record_counts = Vec::new();
+    let mut start_addresses = Vec::new();
+    for parsed_batch in parsed_batches {
+        stats.num_data_records += parsed_batch.num_data_records;
+        chunk_lists.push(parsed_batch.data_chunks);
+        data_records.extend(parsed_batch.data_records);
+        for header_data in parsed_batch.header_data {
+            if srecord_file.header_data.replace(header_data).is_some() {
+                return None;
+            }
+        }
+        record_counts.extend(parsed_batch.record_counts);
+        start_addresses.extend(parsed_batch.start_addresses);
+    }
+    if record_counts
+        .iter()
+        .any(|&record_count| record_count != stats.num_data_records)
+        || start_addresses.len() > 1
+    {
+        return None;
+    }
+    srecord_file.start_address = start_addresses.first().copied();
+    srecord_file.data_chunks = merge_chunk_lists(chunk_lists, OverlapPolicy::Error).ok()?;
+    if options.gap_fill.is_some() {
+        srecord_file.merge_data_chunks(options.gap_fill).ok()?;
+    }
+    if options.record_layout {
+        srecord_file.record_layout = Some(RecordLayout::new(data_records));
+    }
+    Some((srecord_file, stats))
+}
+
+/// Splits `srecord_bytes` into batches of whole lines, each at least [`BATCH_SIZE`] bytes long
+/// except for the last.
+fn split_lines(srecord_bytes: &[u8]) -> Vec<&[u8]> {
+    let mut batches = Vec::new();
+    let mut remaining = srecord_bytes;
+    while remaining.len() > BATCH_SIZE {
+        match remaining[BATCH_SIZE..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+        {
+            Some(position) => {
+                let (batch, rest) = remaining.split_at(BATCH_SIZE + position + 1);
+                batches.push(batch);
+                remaining = rest;
+            }
+            None => break,
+        }
+    }
+    if !remaining.is_empty() {
+        batches.push(remaining);
+    }
+    batches
+}
+
+/// Parses every line in `batch`, or returns `None` if any line is not a valid record. Only data
+/// inside the normalized `address_filter` is kept, if given.
+fn parse_batch(
+    batch: &[u8],
+    options: &ParseOptions,
+    address_filter: Option<&[Range<u64>]>,
+) -> Option<ParsedBatch> {
+    let mut parsed_batch = ParsedBatch::default();
+    let mut data_chunks_sorted = true;
+    let mut data_buffer = [0u8; 256];
+    for (line_index, line) in lines(batch).enumerate() {
+        parsed_batch.num_records += 1;
+        match Record::from_bytes(line, &mut data_buffer).ok()? {
+            Record::S0Record(header_record) => {
+                parsed_batch.header_data.push(header_record.data.to_vec())
+            }
+            Record::S1Record(data_record)
+            | Record::S2Record(data_record)
+            | Record::S3Record(data_record) => {
+                let mut push_data = |address: u64, data: &[u8]| {
+                    match parsed_batch.data_chunks.last_mut() {
+                        Some(last_chunk) if last_chunk.end_address() == address => {
+                            last_chunk.data.extend_from_slice(data)
+                        }
+                        last_chunk => {
+                            if matches!(last_chunk, Some(last_chunk) if last_chunk.end_address() > address)
+                            {
+                                data_chunks_sorted = false;
+                            }
+                            parsed_batch.data_chunks.push(DataChunk {
+                                address,
+                                data: data.to_vec(),
+                            });
+                        }
+                    }
+                    if options.record_layout {
+                        let end_address = address + data.len() as u64;
+                        parsed_batch.data_records.push(address..end_address);
+                    }
+                };
+                match address_filter {
+                    Some(address_filter) => {
+                        for (address, data) in
+                            data_within(data_record.address, data_record.data, address_filter)
+                        {
+                            push_data(address, data);
+                        }
+                    }
+                    None => push_data(data_record.address, data_record.data),
+                }
+                parsed_batch.num_data_records += 1;
+                parsed_batch.last_data_line = Some(line_index);
+            }
+            // Bank records are never returned by Record::from_bytes, and change the addresses of
+            // the following lines, so they are only parsed sequentially
+            Record::S4Record(_) => return None,
+            Record::S5Record(count_record) | Record::S6Record(count_record) => {
+                parsed_batch.record_counts.push(count_record.record_count);
+                parsed_batch.first_trailer_line.get_or_insert(line_index);
+            }
+            Record::S7Record(start_address_record)
+            | Record::S8Record(start_address_record)
+            | Record::S9Record(start_address_record) => {
+                parsed_batch
+                    .start_addresses
+                    .push(start_address_record.start_address);
+                parsed_batch.first_trailer_line.get_or_insert(line_index);
+                parsed_batch.first_terminator_line.get_or_insert(line_index);
+            }
+        }
+    }
+
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.data_chunks = parsed_batch.data_chunks;
+    if !data_chunks_sorted {
+        srecord_file
+            .data_chunks
+            .sort_by_key(|data_chunk| data_chunk.address);
+    }
+    // Gaps are filled after merging all batches, as they may contain data from other batches
+    srecord_file.merge_data_chunks(None).ok()?;
+    parsed_batch.data_chunks = srecord_file.data_chunks;
+    Some(parsed_batch)
+}
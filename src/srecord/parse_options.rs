@@ -0,0 +1,75 @@
+use std::ops::Range;
+
+use crate::srecord::error::ErrorType;
+#[cfg(doc)]
+use crate::srecord::{BankRecord, SRecordFile, SerializationOptions};
+
+/// Options controlling how an SRecord file is parsed into an [`SRecordFile`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// If `true`, records that are out of place in the file are errors. If `false`, they are
+    /// accepted and reported as [`ParseWarning`]s in [`ParseStats::warnings`] instead.
+    ///
+    /// Out of place records are:
+    /// - Data records (S1/S2/S3) after a count record (S5/S6), see
+    ///   [`ErrorType::DataAfterCountRecord`].
+    /// - Any record after a start address record (S7/S8/S9), see
+    ///   [`ErrorType::RecordAfterTerminator`].
+    ///
+    /// Lines skipped by [`SRecordFile::from_bytes_lossy`] are also errors, see
+    /// [`ErrorType::NonAsciiLine`].
+    pub strict: bool,
+    /// If `true`, the address ranges of the data records and the parsed text are kept in the
+    /// [`SRecordFile::record_layout`], so that the file can be serialized with the same record
+    /// layout.
+    pub record_layout: bool,
+    /// If set, gaps of at most [`GapFill::max_gap`] bytes that remain between data records after
+    /// all records are parsed are filled with [`GapFill::value`], so that extremely fragmented
+    /// files are stored in fewer and larger [`DataChunk`](crate::srecord::DataChunk)s. The filled
+    /// bytes become part of the data.
+    pub gap_fill: Option<GapFill>,
+    /// If set, only data inside these address ranges is kept, which is useful to extract a small
+    /// region from a large file without keeping all of its data in memory. The ranges may overlap
+    /// and be in any order. The header and start address are always kept.
+    ///
+    /// Data records outside of the ranges are still validated and counted, but their data is
+    /// dropped while parsing, so overlapping data outside of the ranges is not detected.
+    pub address_filter: Option<Vec<Range<u64>>>,
+    /// If `true`, nonstandard S4 bank records are accepted, and the addresses of the data records
+    /// after a bank record are relative to its bank, see [`BankRecord`]. This parses files
+    /// serialized with [`SerializationOptions::bank_records`]. If `false`, S4 records are errors,
+    /// see [`ErrorType::S4Reserved`].
+    pub bank_records: bool,
+}
+
+/// Configures how small gaps between data records are filled when parsing, see
+/// [`ParseOptions::gap_fill`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GapFill {
+    /// Maximum size of a gap, in bytes, that is filled.
+    pub max_gap: u64,
+    /// Value written to every address in a filled gap.
+    pub value: u8,
+}
+
+/// Statistics collected while parsing an SRecord file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseStats {
+    /// Total number of records (lines) parsed.
+    pub num_records: usize,
+    /// Number of data records (S1/S2/S3) parsed.
+    pub num_data_records: usize,
+    /// Problems that were accepted because [`ParseOptions::strict`] was `false`.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// A problem found in a parsed file that was accepted in non-strict mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Zero-based index of the line where the problem was found.
+    pub line_index: usize,
+    /// The error that would have been returned in strict mode.
+    pub error_type: ErrorType,
+}
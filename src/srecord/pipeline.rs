@@ -0,0 +1,439 @@
+use std::cmp::{max, min};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::{mem, slice};
+
+use crate::srecord::data_chunk::merge_chunk_lists;
+use crate::srecord::error::{PipelineError, PipelineErrorType};
+use crate::srecord::utils::{crc32, difference, intersection, normalized, union};
+use crate::srecord::{
+    OverlapPolicy, RemapEntry, RemapTable, SRecordFile, SerializationOptions, StartAddressPolicy,
+};
+
+/// A single operation in a [`Pipeline`].
+#[derive(Debug)]
+enum Step {
+    /// Keep only data inside the address ranges.
+    Crop(Vec<Range<u64>>),
+    /// Move all data and the start address by a signed offset.
+    Offset(i64),
+    /// Fill gaps in the address range with a byte value.
+    Fill { range: Range<u64>, value: u8 },
+    /// Write the little-endian CRC-32 of the address range at an address.
+    Crc32 { range: Range<u64>, address: u64 },
+    /// Merge data, header and start address from another file.
+    Merge {
+        srecord_file: SRecordFile,
+        policy: OverlapPolicy,
+    },
+}
+
+/// Builder for a sequence of operations applied to an [`SRecordFile`], similar to an `srec_cat`
+/// command line.
+///
+/// Steps are applied in the order they are added. Before any step is applied, all steps are
+/// validated against the predicted memory map of the file, so that an invalid pipeline fails
+/// without doing any work. The predicted memory map can also be inspected without running the
+/// pipeline, using [`dry_run`](Pipeline::dry_run).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::{OverlapPolicy, Pipeline, SRecordFile};
+///
+/// let application = SRecordFile::from_str("S107100000010203E2").unwrap();
+/// let bootloader = SRecordFile::from_str("S1050000AABB95").unwrap();
+///
+/// let pipeline = Pipeline::load(application)
+///     .offset(-0x0F00)
+///     .fill(0x0100..0x0108, 0xFF)
+///     .crc32_le(0x0100..0x0108, 0x0108)
+///     .merge(bootloader, OverlapPolicy::Error);
+/// assert_eq!(pipeline.dry_run().unwrap(), [0x0000..0x0002, 0x0100..0x010C]);
+///
+/// let srecord_file = pipeline.run().unwrap();
+/// assert_eq!(srecord_file[0x0100..0x0108], [0x00, 0x01, 0x02, 0x03, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[derive(Debug)]
+pub struct Pipeline {
+    /// File that the steps are applied to.
+    srecord_file: SRecordFile,
+    /// Steps to apply, in order.
+    steps: Vec<Step>,
+    /// Decides which start address is kept when merging files.
+    start_address_policy: StartAddressPolicy,
+}
+
+impl Pipeline {
+    /// Creates a new [`Pipeline`] operating on `srecord_file`.
+    pub fn load(srecord_file: SRecordFile) -> Self {
+        Pipeline {
+            srecord_file,
+            steps: Vec::new(),
+            start_address_policy: StartAddressPolicy::default(),
+        }
+    }
+
+    /// Sets how start addresses of merged files are handled. Defaults to
+    /// [`StartAddressPolicy::KeepExisting`].
+    pub fn start_address_policy(mut self, policy: StartAddressPolicy) -> Self {
+        self.start_address_policy = policy;
+        self
+    }
+
+    /// Adds a step that keeps only the data inside `ranges`, discarding everything else.
+    pub fn crop(mut self, ranges: &[Range<u64>]) -> Self {
+        self.steps.push(Step::Crop(ranges.to_vec()));
+        self
+    }
+
+    /// Adds a step that moves all data, and the start address if present, by `delta` bytes.
+    pub fn offset(mut self, delta: i64) -> Self {
+        self.steps.push(Step::Offset(delta));
+        self
+    }
+
+    /// Adds a step that fills all addresses in `range` that do not contain data with `value`.
+    /// Existing data is not modified.
+    pub fn fill(mut self, range: Range<u64>, value: u8) -> Self {
+        self.steps.push(Step::Fill { range, value });
+        self
+    }
+
+    /// Adds a step that calculates the CRC-32 of the data in `range` and writes it as 4
+    /// little-endian bytes at `address`. `range` must not contain any gaps.
+    pub fn crc32_le(mut self, range: Range<u64>, address: u64) -> Self {
+        self.steps.push(Step::Crc32 { range, address });
+        self
+    }
+
+    /// Adds a step that merges the data of `srecord_file` into the file, using `policy` to handle
+    /// overlapping data. The header data of `srecord_file` is only used if the file does not
+    /// already have any. The start address is selected by the
+    /// [`start_address_policy`](Pipeline::start_address_policy).
+    pub fn merge(mut self, srecord_file: SRecordFile, policy: OverlapPolicy) -> Self {
+        self.steps.push(Step::Merge {
+            srecord_file,
+            policy,
+        });
+        self
+    }
+
+    /// Validates all steps and returns the predicted memory map after running the pipeline, as
+    /// sorted, non-adjacent address ranges containing data. No step is applied.
+    pub fn dry_run(&self) -> Result<Vec<Range<u64>>, PipelineError> {
+        let (memory_map, _) = self.validate()?;
+        Ok(memory_map)
+    }
+
+    /// Validates all steps and returns a [`RemapTable`] describing where the data of the loaded
+    /// file ends up after running the pipeline, as changed by crop and offset steps. Data added by
+    /// other steps is not included. No step is applied.
+    pub fn remap_table(&self) -> Result<RemapTable, PipelineError> {
+        self.validate()?;
+        let mut entries: Vec<RemapEntry> = memory_map_of(&self.srecord_file)
+            .into_iter()
+            .map(|range| RemapEntry {
+                destination: range.start,
+                source: range,
+            })
+            .collect();
+        for step in self.steps.iter() {
+            match step {
+                Step::Crop(ranges) => {
+                    let ranges = normalized(ranges.clone());
+                    entries = entries
+                        .into_iter()
+                        .flat_map(|entry| {
+                            let destination_range = entry.destination_range();
+                            intersection(slice::from_ref(&destination_range), &ranges)
+                                .into_iter()
+                                .map(move |range| {
+                                    let source_start = entry.source.start
+                                        + (range.start - destination_range.start);
+                                    RemapEntry {
+                                        source: source_start
+                                            ..source_start + (range.end - range.start),
+                                        destination: range.start,
+                                    }
+                                })
+                        })
+                        .collect();
+                }
+                Step::Offset(delta) => {
+                    // Overflow has already been checked in validate
+                    for entry in entries.iter_mut() {
+                        entry.destination = entry.destination.wrapping_add_signed(*delta);
+                    }
+                }
+                Step::Fill { .. } | Step::Crc32 { .. } | Step::Merge { .. } => {}
+            }
+        }
+        Ok(RemapTable { entries })
+    }
+
+    /// Validates all steps, and returns the predicted memory map and start address after running
+    /// the pipeline.
+    fn validate(&self) -> Result<(Vec<Range<u64>>, Option<u64>), PipelineError> {
+        let mut memory_map = memory_map_of(&self.srecord_file);
+        let mut start_address = match self.start_address_policy {
+            StartAddressPolicy::RequireFromInput(input_index) if input_index != 0 => None,
+            _ => self.srecord_file.start_address,
+        };
+        let mut input_index = 0;
+
+        let mut steps = self.steps.iter().enumerate().peekable();
+        while let Some((step_index, step)) = steps.next() {
+            let error = |error_type| PipelineError {
+                step_index,
+                error_type,
+            };
+            memory_map = match step {
+                Step::Crop(ranges) => intersection(&memory_map, &normalized(ranges.clone())),
+                Step::Offset(delta) => {
+                    let offset_address = |address: u64| {
+                        address
+                            .checked_add_signed(*delta)
+                            .ok_or(error(PipelineErrorType::AddressOverflow))
+                    };
+                    if let Some(address) = start_address {
+                        start_address = Some(offset_address(address)?);
+                    }
+                    // The exclusive end address must also fit in a u64, as by SRecordFile::offset
+                    let mut offset_memory_map = Vec::with_capacity(memory_map.len());
+                    for range in memory_map.iter() {
+                        offset_memory_map
+                            .push(offset_address(range.start)?..offset_address(range.end)?);
+                    }
+                    offset_memory_map
+                }
+                Step::Fill { range, .. } => union(&memory_map, slice::from_ref(range)),
+                Step::Crc32 { range, address } => {
+                    let crc_range = *address
+                        ..address
+                            .checked_add(4)
+                            .ok_or(error(PipelineErrorType::AddressOverflow))?;
+                    if let Some(gap) = difference(slice::from_ref(range), &memory_map).first() {
+                        return Err(error(PipelineErrorType::IncompleteCrcRange(gap.clone())));
+                    }
+                    if range.start < crc_range.end && crc_range.start < range.end {
+                        return Err(error(PipelineErrorType::CrcInsideRange));
+                    }
+                    union(&memory_map, &[crc_range])
+                }
+                Step::Merge { .. } => {
+                    // Consecutive merges are validated against a map from start to end address,
+                    // instead of rebuilding the memory map for every merged file
+                    let mut merged_memory_map: BTreeMap<u64, u64> = memory_map
+                        .iter()
+                        .map(|range| (range.start, range.end))
+                        .collect();
+                    let mut merge_step = Some((step_index, step));
+                    while let Some((
+                        step_index,
+                        Step::Merge {
+                            srecord_file,
+                            policy,
+                        },
+                    )) = merge_step
+                    {
+                        let error = |error_type| PipelineError {
+                            step_index,
+                            error_type,
+                        };
+                        for range in memory_map_of(srecord_file) {
+                            if let Some(overlap) = insert_range(&mut merged_memory_map, range) {
+                                if *policy == OverlapPolicy::Error {
+                                    return Err(error(PipelineErrorType::OverlappingData(overlap)));
+                                }
+                            }
+                        }
+                        input_index += 1;
+                        start_address = self
+                            .merged_start_address(start_address, input_index, srecord_file)
+                            .map_err(error)?;
+                        merge_step = steps.next_if(|(_, step)| matches!(step, Step::Merge { .. }));
+                    }
+                    merged_memory_map
+                        .into_iter()
+                        .map(|(start, end)| start..end)
+                        .collect()
+                }
+            };
+        }
+
+        if let StartAddressPolicy::RequireFromInput(required_index) = self.start_address_policy {
+            if required_index > input_index || start_address.is_none() {
+                return Err(PipelineError {
+                    step_index: self.steps.len(),
+                    error_type: PipelineErrorType::MissingStartAddress(required_index),
+                });
+            }
+        }
+
+        Ok((memory_map, start_address))
+    }
+
+    /// Returns the start address after merging `srecord_file` as input `input_index`, when the
+    /// start address before the merge is `start_address`.
+    fn merged_start_address(
+        &self,
+        start_address: Option<u64>,
+        input_index: usize,
+        srecord_file: &SRecordFile,
+    ) -> Result<Option<u64>, PipelineErrorType> {
+        Ok(match (self.start_address_policy, start_address) {
+            (StartAddressPolicy::Error, Some(existing)) => match srecord_file.start_address {
+                Some(merged) if merged != existing => {
+                    return Err(PipelineErrorType::ConflictingStartAddresses(
+                        existing, merged,
+                    ))
+                }
+                _ => Some(existing),
+            },
+            (StartAddressPolicy::Error | StartAddressPolicy::KeepExisting, _) => {
+                start_address.or(srecord_file.start_address)
+            }
+            (StartAddressPolicy::Overwrite, _) => srecord_file.start_address.or(start_address),
+            (StartAddressPolicy::RequireFromInput(required_index), _) => {
+                if required_index == input_index {
+                    srecord_file.start_address
+                } else {
+                    start_address
+                }
+            }
+        })
+    }
+
+    /// Validates and applies all steps, and returns the resulting [`SRecordFile`].
+    pub fn run(self) -> Result<SRecordFile, PipelineError> {
+        let (_, start_address) = self.validate()?;
+
+        let mut srecord_file = self.srecord_file;
+        // The resulting start address has already been decided in validate, and is only set after
+        // the last step
+        srecord_file.start_address = None;
+        let mut steps = self.steps.into_iter().enumerate().peekable();
+        while let Some((step_index, step)) = steps.next() {
+            let error = |error_type| PipelineError {
+                step_index,
+                error_type,
+            };
+            match step {
+                Step::Crop(ranges) => srecord_file.crop(&ranges),
+                // Overflow has already been checked in validate, but is still reported instead of
+                // panicking
+                Step::Offset(delta) => srecord_file
+                    .offset(delta)
+                    .map_err(|_| error(PipelineErrorType::AddressOverflow))?,
+                Step::Fill { range, value } => {
+                    let gaps: Vec<Range<u64>> = srecord_file.gaps_within(range).collect();
+                    for gap in gaps {
+                        let fill_data = vec![value; (gap.end - gap.start) as usize];
+                        srecord_file.set_data(gap.start, &fill_data);
+                    }
+                }
+                Step::Crc32 { range, address } => {
+                    let mut data = Vec::new();
+                    for (_, chunk_data) in srecord_file.iter_data_in_range(range) {
+                        data.extend_from_slice(chunk_data);
+                    }
+                    srecord_file.set_data(address, &crc32(&data).to_le_bytes());
+                }
+                Step::Merge {
+                    srecord_file: other,
+                    policy,
+                } => {
+                    // Consecutive merges with the same policy are merged in a single pass
+                    let mut others = vec![other];
+                    while let Some((
+                        _,
+                        Step::Merge {
+                            srecord_file: other,
+                            ..
+                        },
+                    )) = steps.next_if(|(_, step)| {
+                        matches!(step, Step::Merge { policy: next_policy, .. } if *next_policy == policy)
+                    }) {
+                        others.push(other);
+                    }
+                    let mut chunk_lists = vec![mem::take(&mut srecord_file.data_chunks)];
+                    for other in others {
+                        chunk_lists.push(other.data_chunks);
+                        if srecord_file.header_data.is_none() {
+                            srecord_file.header_data = other.header_data;
+                        }
+                    }
+                    // Overlaps have already been checked in validate, but are still reported
+                    // instead of panicking
+                    srecord_file.data_chunks = merge_chunk_lists(chunk_lists, policy)
+                        .map_err(|range| error(PipelineErrorType::OverlappingData(range)))?;
+                }
+            }
+        }
+        srecord_file.start_address = start_address;
+
+        Ok(srecord_file)
+    }
+
+    /// Validates and applies all steps, and serializes the resulting [`SRecordFile`] as configured
+    /// by `options`, with one record per line.
+    pub fn write(self, options: &SerializationOptions) -> Result<String, PipelineError> {
+        let step_index = self.steps.len();
+        let srecord_file = self.run()?;
+        let records = srecord_file
+            .iter_records_with_options(options)
+            .map_err(|error| PipelineError {
+                step_index,
+                error_type: PipelineErrorType::Serialization(error),
+            })?;
+        let mut srecord_str = String::new();
+        for record in records {
+            srecord_str.push_str(&record.serialize());
+            srecord_str.push('\n');
+        }
+        Ok(srecord_str)
+    }
+}
+
+/// Inserts `range` into `memory_map`, a map from start to end address of sorted, non-adjacent
+/// address ranges, merging it with the ranges it overlaps or is adjacent to. Returns the first
+/// address range that already contained data, if any.
+fn insert_range(memory_map: &mut BTreeMap<u64, u64>, range: Range<u64>) -> Option<Range<u64>> {
+    let mut overlap = None;
+    let (mut start_address, mut end_address) = (range.start, range.end);
+    if let Some((&previous_start, &previous_end)) = memory_map.range(..=range.start).next_back() {
+        if previous_end >= range.start {
+            if previous_end > range.start {
+                overlap = Some(range.start..min(previous_end, range.end));
+            }
+            memory_map.remove(&previous_start);
+            start_address = previous_start;
+            end_address = max(end_address, previous_end);
+        }
+    }
+    while let Some((&next_start, &next_end)) = memory_map.range(start_address..).next() {
+        if next_start > end_address {
+            break;
+        }
+        if next_start < range.end && overlap.is_none() {
+            overlap = Some(next_start..min(next_end, range.end));
+        }
+        memory_map.remove(&next_start);
+        end_address = max(end_address, next_end);
+    }
+    memory_map.insert(start_address, end_address);
+    overlap
+}
+
+/// Returns the normalized address ranges that contain data in `srecord_file`.
+fn memory_map_of(srecord_file: &SRecordFile) -> Vec<Range<u64>> {
+    normalized(
+        srecord_file
+            .data_chunks
+            .iter()
+            .map(|data_chunk| data_chunk.address..data_chunk.end_address())
+            .collect(),
+    )
+}
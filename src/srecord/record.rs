@@ -1,18 +1,23 @@
-use crate::srecord::error::SRecordParseError;
+use std::str::FromStr;
+
+use crate::srecord::error::{ErrorType, SRecordParseError};
 use crate::srecord::utils::{
-    calculate_checksum, parse_address, parse_byte_count, parse_data_and_checksum, parse_record_type,
+    calculate_checksum, check_data_end_address, encode_hex_upper, parse_address, parse_byte_count,
+    parse_data_and_checksum, parse_record_type,
 };
-use crate::srecord::{DataChunk, RecordType, SRecordFile};
+#[cfg(doc)]
+use crate::srecord::{DataChunk, ParseOptions, SRecordFile, SerializationOptions};
+use crate::srecord::{IntoAddress, RecordType};
 
 /// Contains the [`data`](`SRecordFile::header_data`) found in the header of an [`SRecordFile`].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct HeaderRecord<'a> {
     /// Reference to [`SRecordFile::header_data`].
     pub data: &'a [u8],
 }
 
 /// Contains a slice of data in a [`DataChunk`], starting at [`address`](`DataRecord::address`).
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DataRecord<'a> {
     /// Address where record starts.
     pub address: u64,
@@ -21,22 +26,140 @@ pub struct DataRecord<'a> {
     pub data: &'a [u8],
 }
 
+impl<'a> DataRecord<'a> {
+    /// Creates a new [`DataRecord`] with `data` starting at `address`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{Address, DataRecord, Record};
+    ///
+    /// let data_record = DataRecord::new(Address::from(0x1000u16), &[0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(Record::S1Record(data_record).serialize(), "S107100000010203E2");
+    /// ```
+    pub fn new<A: IntoAddress>(address: A, data: &'a [u8]) -> Self {
+        DataRecord {
+            address: address.into_address().value(),
+            data,
+        }
+    }
+}
+
 /// Contains the number of data records found in an [`SRecordFile`].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct CountRecord {
     /// Number of data records.
     pub record_count: usize,
 }
 
 /// Contains the execution start address found in an [`SRecordFile`].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct StartAddressRecord {
     /// Execution start address.
     pub start_address: u64,
 }
 
+impl StartAddressRecord {
+    /// Creates a new [`StartAddressRecord`] with execution start address `start_address`.
+    pub fn new<A: IntoAddress>(start_address: A) -> Self {
+        StartAddressRecord {
+            start_address: start_address.into_address().value(),
+        }
+    }
+}
+
+/// Nonstandard record selecting the bank of the data records after it, for data above the 32-bit
+/// address range of S3 records. The address of a data record after a [`BankRecord`] is relative
+/// to the base address `bank << 32`.
+///
+/// Bank records use the reserved S4 record type, with a 32-bit address field containing the bank
+/// and no data. They are only emitted with [`SerializationOptions::bank_records`] and parsed with
+/// [`ParseOptions::bank_records`], other parsers, including [`Record::from_str`], reject them.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::{BankRecord, Record};
+///
+/// let record = Record::S4Record(BankRecord { bank: 2 });
+/// assert_eq!(record.serialize(), "S40500000002F8");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BankRecord {
+    /// Upper 32 bits of the addresses of the following data records.
+    pub bank: u32,
+}
+
+impl BankRecord {
+    /// Parses the ASCII bytes of a single record starting with `S4` to a [`BankRecord`].
+    pub(crate) fn from_bytes(record_bytes: &[u8]) -> Result<BankRecord, SRecordParseError> {
+        // Laid out like an S7 record, with a 32-bit address field and no data
+        let byte_count = parse_byte_count(record_bytes)?;
+        let bank = parse_address(record_bytes, &RecordType::S7)?;
+        let mut data_buffer = [0u8; 256];
+        parse_data_and_checksum(
+            record_bytes,
+            &RecordType::S7,
+            &byte_count,
+            &bank,
+            &mut data_buffer,
+        )?;
+        match RecordType::S7.num_data_bytes(byte_count as usize) {
+            0 => Ok(BankRecord { bank: bank as u32 }),
+            _ => Err(SRecordParseError {
+                error_type: ErrorType::InvalidData,
+            }),
+        }
+    }
+}
+
+/// Owned version of [`HeaderRecord`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedHeaderRecord {
+    /// Header data.
+    pub data: Vec<u8>,
+}
+
+/// Owned version of [`DataRecord`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedDataRecord {
+    /// Address where record starts.
+    pub address: u64,
+    /// Data starting at [`address`](`OwnedDataRecord::address`).
+    pub data: Vec<u8>,
+}
+
+impl OwnedDataRecord {
+    /// Creates a new [`OwnedDataRecord`] with `data` starting at `address`.
+    pub fn new<A: IntoAddress>(address: A, data: Vec<u8>) -> Self {
+        OwnedDataRecord {
+            address: address.into_address().value(),
+            data,
+        }
+    }
+
+    /// Returns a [`DataRecord`] borrowing the data of `self`.
+    pub fn as_data_record(&self) -> DataRecord<'_> {
+        DataRecord {
+            address: self.address,
+            data: &self.data,
+        }
+    }
+}
+
+/// Summary of a record validated by [`validate_record_bytes`](crate::srecord::utils::validate_record_bytes).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecordInfo {
+    /// Type of the record.
+    pub record_type: RecordType,
+    /// Address field of the record. For count records this is the record count.
+    pub address: u64,
+    /// Number of data bytes in the record.
+    pub num_data_bytes: usize,
+}
+
 /// Contains the different types of records that are possible in an [`SRecordFile`].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Record<'a> {
     /// Header record.
     S0Record(HeaderRecord<'a>),
@@ -46,6 +169,8 @@ pub enum Record<'a> {
     S2Record(DataRecord<'a>),
     /// Data record with 32-bit address.
     S3Record(DataRecord<'a>),
+    /// Nonstandard bank record, see [`BankRecord`].
+    S4Record(BankRecord),
     /// 16-bit data record count, max 65,535.
     S5Record(CountRecord),
     /// 24-bit data record count, max 16,777,215.
@@ -78,27 +203,41 @@ impl Record<'_> {
     /// ```
     #[inline]
     pub fn from_str<'a>(s: &str, data: &'a mut [u8]) -> Result<Record<'a>, SRecordParseError> {
-        let record_type = parse_record_type(s)?;
-        let byte_count = parse_byte_count(s)?;
-        let address = parse_address(s, &record_type)?;
+        Record::from_bytes(s.as_bytes(), data)
+    }
+
+    /// Parses the ASCII bytes of a single record to a [`Record`], writing the data (if S0-S3
+    /// record) into `data`. Same as [`Record::from_str`], but without requiring valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{CountRecord, Record};
+    ///
+    /// let mut data_buffer = [0u8; 256];
+    /// let record = Record::from_bytes(b"S5030003F9", &mut data_buffer).unwrap();
+    /// assert_eq!(record, Record::S5Record(CountRecord { record_count: 3 }));
+    /// ```
+    #[inline]
+    pub fn from_bytes<'a>(
+        record_bytes: &[u8],
+        data: &'a mut [u8],
+    ) -> Result<Record<'a>, SRecordParseError> {
+        let record_type = parse_record_type(record_bytes)?;
+        let byte_count = parse_byte_count(record_bytes)?;
+        let address = parse_address(record_bytes, &record_type)?;
+        parse_data_and_checksum(record_bytes, &record_type, &byte_count, &address, data)?;
         let num_data_types = record_type.num_data_bytes(byte_count as usize);
-        parse_data_and_checksum(s, &record_type, &byte_count, &address, data)?;
         let data = &data[..num_data_types];
 
+        check_data_end_address(&record_type, address, data.len())
+            .map_err(|error_type| SRecordParseError { error_type })?;
+
         match record_type {
             RecordType::S0 => Ok(Record::S0Record(HeaderRecord { data })),
-            RecordType::S1 => {
-                // TODO: Validate that data does not extend past max 16-bit address
-                Ok(Record::S1Record(DataRecord { address, data }))
-            }
-            RecordType::S2 => {
-                // TODO: Validate that data does not extend past max 24-bit address
-                Ok(Record::S2Record(DataRecord { address, data }))
-            }
-            RecordType::S3 => {
-                // TODO: Validate that data does not extend past max 32-bit address
-                Ok(Record::S3Record(DataRecord { address, data }))
-            }
+            RecordType::S1 => Ok(Record::S1Record(DataRecord { address, data })),
+            RecordType::S2 => Ok(Record::S2Record(DataRecord { address, data })),
+            RecordType::S3 => Ok(Record::S3Record(DataRecord { address, data })),
             RecordType::S5 => Ok(Record::S5Record(CountRecord {
                 record_count: address as usize,
             })),
@@ -108,10 +247,10 @@ impl Record<'_> {
             RecordType::S7 => Ok(Record::S7Record(StartAddressRecord {
                 start_address: address,
             })),
-            RecordType::S8 => Ok(Record::S7Record(StartAddressRecord {
+            RecordType::S8 => Ok(Record::S8Record(StartAddressRecord {
                 start_address: address,
             })),
-            RecordType::S9 => Ok(Record::S7Record(StartAddressRecord {
+            RecordType::S9 => Ok(Record::S9Record(StartAddressRecord {
                 start_address: address,
             })),
         }
@@ -139,7 +278,7 @@ impl Record<'_> {
                 let checksum = calculate_checksum(&byte_count, &0, header_record.data);
                 format!(
                     "S0{byte_count:02X}0000{}{checksum:02X}",
-                    hex::encode_upper(header_record.data)
+                    encode_hex_upper(header_record.data)
                 )
             }
             Record::S1Record(data_record) => {
@@ -150,7 +289,7 @@ impl Record<'_> {
                     calculate_checksum(&byte_count, &data_record.address, data_record.data);
                 format!(
                     "S1{byte_count:02X}{address:04X}{}{checksum:02X}",
-                    hex::encode_upper(data_record.data)
+                    encode_hex_upper(data_record.data)
                 )
             }
             Record::S2Record(data_record) => {
@@ -161,7 +300,7 @@ impl Record<'_> {
                     calculate_checksum(&byte_count, &data_record.address, data_record.data);
                 format!(
                     "S2{byte_count:02X}{address:06X}{}{checksum:02X}",
-                    hex::encode_upper(data_record.data)
+                    encode_hex_upper(data_record.data)
                 )
             }
             Record::S3Record(data_record) => {
@@ -172,9 +311,16 @@ impl Record<'_> {
                     calculate_checksum(&byte_count, &data_record.address, data_record.data);
                 format!(
                     "S3{byte_count:02X}{address:08X}{}{checksum:02X}",
-                    hex::encode_upper(data_record.data)
+                    encode_hex_upper(data_record.data)
                 )
             }
+            Record::S4Record(bank_record) => {
+                // 4 address bytes, 1 checksum byte
+                let byte_count = 5;
+                let bank = bank_record.bank;
+                let checksum = calculate_checksum(&byte_count, &(bank as u64), &[]);
+                format!("S4{byte_count:02X}{bank:08X}{checksum:02X}")
+            }
             Record::S5Record(count_record) => {
                 // 2 address bytes, 1 checksum byte
                 let byte_count = 3;
@@ -215,9 +361,140 @@ impl Record<'_> {
     }
 }
 
+/// Owned version of [`Record`], which can be stored and returned without borrowing a data buffer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OwnedRecord {
+    /// Header record.
+    S0Record(OwnedHeaderRecord),
+    /// Data record with 16-bit address.
+    S1Record(OwnedDataRecord),
+    /// Data record with 24-bit address.
+    S2Record(OwnedDataRecord),
+    /// Data record with 32-bit address.
+    S3Record(OwnedDataRecord),
+    /// Nonstandard bank record, see [`BankRecord`].
+    S4Record(BankRecord),
+    /// 16-bit data record count, max 65,535.
+    S5Record(CountRecord),
+    /// 24-bit data record count, max 16,777,215.
+    S6Record(CountRecord),
+    /// 32-bit execution start address.
+    S7Record(StartAddressRecord),
+    /// 24-bit execution start address.
+    S8Record(StartAddressRecord),
+    /// 16-bit execution start address.
+    S9Record(StartAddressRecord),
+}
+
+impl OwnedRecord {
+    /// Returns a [`Record`] borrowing the data of `self`.
+    pub fn as_record(&self) -> Record<'_> {
+        match self {
+            OwnedRecord::S0Record(header_record) => Record::S0Record(HeaderRecord {
+                data: &header_record.data,
+            }),
+            OwnedRecord::S1Record(record) => Record::S1Record(record.as_data_record()),
+            OwnedRecord::S2Record(record) => Record::S2Record(record.as_data_record()),
+            OwnedRecord::S3Record(record) => Record::S3Record(record.as_data_record()),
+            OwnedRecord::S4Record(bank_record) => Record::S4Record(*bank_record),
+            OwnedRecord::S5Record(count_record) => Record::S5Record(*count_record),
+            OwnedRecord::S6Record(count_record) => Record::S6Record(*count_record),
+            OwnedRecord::S7Record(start_address_record) => Record::S7Record(*start_address_record),
+            OwnedRecord::S8Record(start_address_record) => Record::S8Record(*start_address_record),
+            OwnedRecord::S9Record(start_address_record) => Record::S9Record(*start_address_record),
+        }
+    }
+
+    /// Serializes record into string, see [`Record::serialize`].
+    pub fn serialize(&self) -> String {
+        self.as_record().serialize()
+    }
+}
+
+impl From<Record<'_>> for OwnedRecord {
+    /// Copies the data of `record` into an [`OwnedRecord`].
+    fn from(record: Record<'_>) -> Self {
+        let data_record = |data_record: DataRecord| OwnedDataRecord {
+            address: data_record.address,
+            data: data_record.data.to_vec(),
+        };
+        match record {
+            Record::S0Record(header_record) => OwnedRecord::S0Record(OwnedHeaderRecord {
+                data: header_record.data.to_vec(),
+            }),
+            Record::S1Record(record) => OwnedRecord::S1Record(data_record(record)),
+            Record::S2Record(record) => OwnedRecord::S2Record(data_record(record)),
+            Record::S3Record(record) => OwnedRecord::S3Record(data_record(record)),
+            Record::S4Record(bank_record) => OwnedRecord::S4Record(bank_record),
+            Record::S5Record(count_record) => OwnedRecord::S5Record(count_record),
+            Record::S6Record(count_record) => OwnedRecord::S6Record(count_record),
+            Record::S7Record(start_address_record) => OwnedRecord::S7Record(start_address_record),
+            Record::S8Record(start_address_record) => OwnedRecord::S8Record(start_address_record),
+            Record::S9Record(start_address_record) => OwnedRecord::S9Record(start_address_record),
+        }
+    }
+}
+
+impl FromStr for OwnedRecord {
+    type Err = SRecordParseError;
+
+    /// Parses a single record, see [`Record::from_str`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{OwnedDataRecord, OwnedRecord};
+    ///
+    /// let record = OwnedRecord::from_str("S107100000010203E2").unwrap();
+    /// assert_eq!(
+    ///     record,
+    ///     OwnedRecord::S1Record(OwnedDataRecord {
+    ///         address: 0x1000,
+    ///         data: vec![0x00, 0x01, 0x02, 0x03],
+    ///     }),
+    /// );
+    /// assert_eq!(record.serialize(), "S107100000010203E2");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut data_buffer = [0u8; 256];
+        Ok(OwnedRecord::from(Record::from_str(s, &mut data_buffer)?))
+    }
+}
+
+/// Lazily parses every line of `srecord_str` into an [`OwnedRecord`], without building an
+/// [`SRecordFile`].
+///
+/// Records are parsed one at a time as the iterator is advanced, so large inputs can be filtered
+/// or summarized in a single pass. Only the individual records are validated, not their order or
+/// contents across records. Parsing continues after an invalid line.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::{iter_records_str, OwnedRecord};
+///
+/// let srecord_str = "S00600004844521B\nS107100000010203E2\nS1051004AABB81\nS9031000EC";
+/// let num_data_bytes: usize = iter_records_str(srecord_str)
+///     .filter_map(|record| match record.unwrap() {
+///         OwnedRecord::S1Record(data_record) => Some(data_record.data.len()),
+///         _ => None,
+///     })
+///     .sum();
+/// assert_eq!(num_data_bytes, 6);
+/// ```
+pub fn iter_records_str(
+    srecord_str: &str,
+) -> impl Iterator<Item = Result<OwnedRecord, SRecordParseError>> + '_ {
+    srecord_str.lines().map(OwnedRecord::from_str)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{CountRecord, DataRecord, HeaderRecord, Record, StartAddressRecord};
+    use super::{
+        iter_records_str, CountRecord, DataRecord, FromStr, HeaderRecord, OwnedDataRecord,
+        OwnedHeaderRecord, OwnedRecord, Record, StartAddressRecord,
+    };
 
     #[test]
     fn test_serialize_s0() {
@@ -368,4 +645,66 @@ mod tests {
             "S9031234B6",
         );
     }
+
+    #[test]
+    fn test_owned_record_round_trip() {
+        for record_str in [
+            "S00600004844521B",
+            "S107100000010203E2",
+            "S20801000000010203F0",
+            "S3091000000000010203E0",
+            "S5030003F9",
+            "S604000003F8",
+            "S70510000000EA",
+            "S804100000EB",
+            "S9031000EC",
+        ] {
+            let mut data_buffer = [0u8; 256];
+            let record = Record::from_str(record_str, &mut data_buffer).unwrap();
+            let owned_record = OwnedRecord::from_str(record_str).unwrap();
+            assert_eq!(owned_record.as_record(), record);
+            assert_eq!(OwnedRecord::from(record), owned_record);
+            assert_eq!(owned_record.serialize(), record_str);
+        }
+    }
+
+    #[test]
+    fn test_owned_record_outlives_buffer() {
+        let parse = |s: &str| {
+            let mut data_buffer = [0u8; 256];
+            OwnedRecord::from(Record::from_str(s, &mut data_buffer).unwrap())
+        };
+        assert_eq!(
+            parse("S00600004844521B"),
+            OwnedRecord::S0Record(OwnedHeaderRecord {
+                data: b"HDR".to_vec()
+            })
+        );
+        assert_eq!(
+            parse("S107100000010203E2"),
+            OwnedRecord::S1Record(OwnedDataRecord {
+                address: 0x1000,
+                data: vec![0x00, 0x01, 0x02, 0x03],
+            })
+        );
+        assert!(OwnedRecord::from_str("S107100000010203E3").is_err());
+    }
+
+    #[test]
+    fn test_iter_records_str() {
+        let mut records = iter_records_str("S5030003F9\nS5030003F8\n\nS9031000EC\n");
+        assert_eq!(
+            records.next(),
+            Some(Ok(OwnedRecord::S5Record(CountRecord { record_count: 3 })))
+        );
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().unwrap().is_err());
+        assert_eq!(
+            records.next(),
+            Some(Ok(OwnedRecord::S9Record(StartAddressRecord {
+                start_address: 0x1000
+            })))
+        );
+        assert!(records.next().is_none());
+    }
 }
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+#[cfg(doc)]
+use crate::srecord::{ParseOptions, SRecordFile};
+
+/// Layout of the records in a parsed SRecord file, returned by [`SRecordFile::record_layout`] when
+/// parsing with [`ParseOptions::record_layout`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecordLayout {
+    /// Address ranges of the data records (S1/S2/S3), in the order they were parsed.
+    pub data_records: Vec<Range<u64>>,
+    /// The parsed input, used to reproduce the file byte-for-byte if it is not edited.
+    source: Vec<u8>,
+    /// Byte range in `source` of every line, without line ending.
+    lines: Vec<Range<usize>>,
+    /// Whether the lines of `source` end with `\r\n` instead of `\n`.
+    crlf_line_endings: bool,
+    /// Fingerprint of the file contents when it was parsed from `source`, see
+    /// [`is_unedited`](RecordLayout::is_unedited), or `None` if there is no parsed input.
+    fingerprint: Option<u64>,
+}
+
+impl RecordLayout {
+    /// Creates a [`RecordLayout`] with data records of the address ranges `data_records`, in the
+    /// given order, and no parsed text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{RecordLayout, SRecordFile, SerializationOptions};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.set_record_layout(Some(RecordLayout::new(vec![0x1002..0x1004, 0x1000..0x1002])));
+    /// let mut options = SerializationOptions::default();
+    /// options.preserve_layout = true;
+    /// let records: Vec<String> = srecord_file
+    ///     .iter_records_with_options(&options)
+    ///     .unwrap()
+    ///     .map(|record| record.serialize())
+    ///     .collect();
+    /// assert_eq!(records, ["S307000010020203E1", "S307000010000001E7", "S5030002FA"]);
+    /// ```
+    pub fn new(data_records: Vec<Range<u64>>) -> Self {
+        RecordLayout {
+            data_records,
+            source: Vec::new(),
+            lines: Vec::new(),
+            crlf_line_endings: false,
+            fingerprint: None,
+        }
+    }
+
+    /// Creates a [`RecordLayout`] of the data records `data_records` parsed from `source`, for a
+    /// file whose contents have the fingerprint `fingerprint`.
+    pub(crate) fn with_source(
+        data_records: Vec<Range<u64>>,
+        source: Vec<u8>,
+        fingerprint: u64,
+    ) -> Self {
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+        for line in source.split_inclusive(|&byte| byte == b'\n') {
+            let line_end = line_start + line.len();
+            let content = match line.strip_suffix(b"\n") {
+                Some(line) => line.strip_suffix(b"\r").unwrap_or(line),
+                None => line,
+            };
+            lines.push(line_start..line_start + content.len());
+            line_start = line_end;
+        }
+        let crlf_line_endings = source.windows(2).any(|bytes| bytes == b"\r\n");
+        RecordLayout {
+            data_records,
+            source,
+            lines,
+            crlf_line_endings,
+            fingerprint: Some(fingerprint),
+        }
+    }
+
+    /// Returns the parsed input, or an empty slice if the layout was not parsed.
+    pub fn source(&self) -> &[u8] {
+        &self.source
+    }
+
+    /// Iterates over the lines of the parsed input, without line endings.
+    pub(crate) fn lines(&self) -> impl Iterator<Item = &[u8]> {
+        self.lines.iter().map(|line| &self.source[line.clone()])
+    }
+
+    /// Returns the line ending of the parsed input, `\n` if it has none.
+    pub(crate) fn line_ending(&self) -> &'static str {
+        match self.crlf_line_endings {
+            true => "\r\n",
+            false => "\n",
+        }
+    }
+
+    /// Returns `true` if the parsed input ends with a line ending.
+    pub(crate) fn ends_with_line_ending(&self) -> bool {
+        self.source.ends_with(b"\n")
+    }
+
+    /// Returns `true` if `fingerprint`, of the current file contents, matches the contents the
+    /// layout was parsed with, so that [`source`](RecordLayout::source) still reproduces the file.
+    pub(crate) fn is_unedited(&self, fingerprint: u64) -> bool {
+        self.fingerprint == Some(fingerprint)
+    }
+}
@@ -1,9 +1,10 @@
 use std::fmt;
 
+#[cfg(doc)]
 use crate::srecord::Record;
 
 /// Enum containing which type a [`Record`] is.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum RecordType {
     /// Header record. 16-bit address that must be 0x0000.
     S0,
@@ -64,6 +65,21 @@ impl RecordType {
     pub fn num_data_bytes(&self, byte_count: usize) -> usize {
         byte_count - (self.num_address_bytes() + 1)
     }
+
+    /// Returns the maximum number of data bytes a record of a certain record type can contain,
+    /// limited by the 8-bit byte count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use srex::srecord::RecordType;
+    ///
+    /// assert_eq!(RecordType::S1.max_num_data_bytes(), 252);
+    /// assert_eq!(RecordType::S3.max_num_data_bytes(), 250);
+    /// ```
+    pub fn max_num_data_bytes(&self) -> usize {
+        self.num_data_bytes(u8::MAX as usize)
+    }
 }
 
 impl fmt::Display for RecordType {
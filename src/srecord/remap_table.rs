@@ -0,0 +1,146 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::srecord::error::{RemapTableParseError, TransplantError};
+use crate::srecord::{OverlapPolicy, SRecordFile};
+
+#[cfg(doc)]
+use crate::srecord::Pipeline;
+
+/// An address range that was moved to a new address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemapEntry {
+    /// Address range before the move.
+    pub source: Range<u64>,
+    /// Address that `source.start` was moved to.
+    pub destination: u64,
+}
+
+impl RemapEntry {
+    /// Returns the address range after the move.
+    pub fn destination_range(&self) -> Range<u64> {
+        self.destination..self.destination + (self.source.end - self.source.start)
+    }
+}
+
+/// Table of how address ranges were moved, e.g. by the crop and offset steps of a [`Pipeline`].
+///
+/// The table can be used by debug tooling to translate symbol addresses, and can be applied to
+/// another image to move it the same way. It is serialized as text with one entry per line,
+/// containing the start and exclusive end address of the source range and the destination
+/// address, as hexadecimal numbers prefixed with `0x` and separated by spaces.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::{Pipeline, RemapTable, SRecordFile};
+///
+/// let application = SRecordFile::from_str("S107100000010203E2").unwrap();
+/// let pipeline = Pipeline::load(application).crop(&[0x1001..0x1003]).offset(0x1000);
+/// let remap_table = pipeline.remap_table().unwrap();
+/// assert_eq!(remap_table.serialize(), "0x1001 0x1003 0x2001\n");
+/// assert_eq!(remap_table.translate(0x1002), Some(0x2002));
+/// assert_eq!(remap_table.translate(0x1003), None);
+///
+/// let debug_info = SRecordFile::from_str("S107100010111213A2").unwrap();
+/// let remap_table = RemapTable::from_str("0x1001 0x1003 0x2001\n").unwrap();
+/// let moved = remap_table.apply(&debug_info).unwrap();
+/// assert_eq!(moved[0x2001..0x2003], [0x11, 0x12]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemapTable {
+    /// Moved address ranges, in the order they were added.
+    pub entries: Vec<RemapEntry>,
+}
+
+impl RemapTable {
+    /// Returns the address that `address` was moved to, or `None` if it is not in the source range
+    /// of any entry.
+    pub fn translate(&self, address: u64) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|entry| entry.source.contains(&address))
+            .map(|entry| entry.destination + (address - entry.source.start))
+    }
+
+    /// Returns a new [`SRecordFile`] with the data of `srecord_file` moved as described by the
+    /// table. Data outside the source ranges of the table is not included. The header data is
+    /// copied, and the start address is translated with [`translate`](RemapTable::translate).
+    ///
+    /// Returns an error if moved data overlaps, or if a destination range does not fit in a `u64`.
+    pub fn apply(&self, srecord_file: &SRecordFile) -> Result<SRecordFile, TransplantError> {
+        let mut remapped_file = SRecordFile {
+            header_data: srecord_file.header_data.clone(),
+            start_address: srecord_file
+                .start_address
+                .and_then(|address| self.translate(address)),
+            ..SRecordFile::new()
+        };
+        for entry in self.entries.iter() {
+            remapped_file.transplant(
+                srecord_file,
+                entry.source.clone(),
+                entry.destination,
+                OverlapPolicy::Error,
+            )?;
+        }
+        Ok(remapped_file)
+    }
+
+    /// Serializes the table with one entry per line, see [`RemapTable`].
+    pub fn serialize(&self) -> String {
+        let mut remap_str = String::new();
+        for entry in self.entries.iter() {
+            remap_str.push_str(&format!(
+                "{:#X} {:#X} {:#X}\n",
+                entry.source.start, entry.source.end, entry.destination
+            ));
+        }
+        remap_str
+    }
+}
+
+impl FromStr for RemapTable {
+    type Err = RemapTableParseError;
+
+    /// Parses a table serialized with [`RemapTable::serialize`]. Empty lines are ignored.
+    fn from_str(remap_str: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        for (line_index, line) in remap_str.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = RemapTableParseError { line_index };
+            let mut addresses = line.split_whitespace().map(|field| {
+                let digits = field
+                    .strip_prefix("0x")
+                    .or_else(|| field.strip_prefix("0X"))
+                    .ok_or(error)?;
+                u64::from_str_radix(digits, 16).map_err(|_| error)
+            });
+            let (Some(start), Some(end), Some(destination), None) = (
+                addresses.next(),
+                addresses.next(),
+                addresses.next(),
+                addresses.next(),
+            ) else {
+                return Err(error);
+            };
+            let entry = RemapEntry {
+                source: start?..end?,
+                destination: destination?,
+            };
+            if entry.source.start > entry.source.end
+                || entry
+                    .destination
+                    .checked_add(entry.source.end - entry.source.start)
+                    .is_none()
+            {
+                return Err(error);
+            }
+            entries.push(entry);
+        }
+        Ok(RemapTable { entries })
+    }
+}
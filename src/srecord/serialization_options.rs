@@ -0,0 +1,133 @@
+use crate::srecord::error::SerializationError;
+use crate::srecord::{CountRecord, Record};
+
+#[cfg(doc)]
+use crate::srecord::{BankRecord, ParseOptions, SRecordFile};
+
+/// Selects which type of data [`Record`] is used when serializing an [`SRecordFile`].
+///
+/// The start address record has the matching address width: S9 for S1, S8 for S2 and S7 for S3.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DataRecordType {
+    /// Data records with 16-bit addresses.
+    S1,
+    /// Data records with 24-bit addresses.
+    S2,
+    /// Data records with 32-bit addresses.
+    #[default]
+    S3,
+    /// The narrowest of [`S1`](DataRecordType::S1), [`S2`](DataRecordType::S2) and
+    /// [`S3`](DataRecordType::S3) that fits the highest data address and the start address in the
+    /// [`SRecordFile`], see [`SRecordFile::minimal_record_type`].
+    Auto,
+}
+
+/// Selects which type of count [`Record`] is used when serializing an [`SRecordFile`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CountRecordType {
+    /// 16-bit count record. The number of data records must be less than 65536.
+    S5,
+    /// 24-bit count record. The number of data records must be less than 16777216.
+    S6,
+    /// [`S5`](CountRecordType::S5) if the number of data records fits in 16 bits, otherwise
+    /// [`S6`](CountRecordType::S6) if it fits in 24 bits, otherwise no count record.
+    #[default]
+    Auto,
+    /// No count record.
+    Omit,
+}
+
+impl CountRecordType {
+    /// Returns the count record of this type for `num_data_records` data records, or `None` if no
+    /// count record is emitted.
+    ///
+    /// Returns an error if `num_data_records` does not fit in an explicitly selected
+    /// [`S5`](CountRecordType::S5) or [`S6`](CountRecordType::S6) record.
+    pub(crate) fn count_record(
+        self,
+        num_data_records: usize,
+    ) -> Result<Option<Record<'static>>, SerializationError> {
+        let count_record = CountRecord {
+            record_count: num_data_records,
+        };
+        match self {
+            CountRecordType::S5 if num_data_records < 1 << 16 => {
+                Ok(Some(Record::S5Record(count_record)))
+            }
+            CountRecordType::S6 if num_data_records < 1 << 24 => {
+                Ok(Some(Record::S6Record(count_record)))
+            }
+            CountRecordType::S5 | CountRecordType::S6 => {
+                Err(SerializationError::RecordCountTooLarge(num_data_records))
+            }
+            CountRecordType::Auto if num_data_records < 1 << 16 => {
+                Ok(Some(Record::S5Record(count_record)))
+            }
+            CountRecordType::Auto if num_data_records < 1 << 24 => {
+                Ok(Some(Record::S6Record(count_record)))
+            }
+            CountRecordType::Auto | CountRecordType::Omit => Ok(None),
+        }
+    }
+}
+
+/// Options controlling how an [`SRecordFile`] is serialized into [`Record`]s.
+///
+/// Serialization is deterministic: the records depend only on the contents of the [`SRecordFile`]
+/// and the options, and never contain timestamps, paths or other metadata of the environment.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::{CountRecordType, DataRecordType, SerializationOptions};
+///
+/// let mut options = SerializationOptions::default();
+/// options.data_record_type = DataRecordType::Auto;
+/// assert_eq!(options.data_record_size, 16);
+/// assert_eq!(options.count_record_type, CountRecordType::Auto);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SerializationOptions {
+    /// Maximum number of data bytes in each data record.
+    pub data_record_size: usize,
+    /// Type of data records to emit.
+    pub data_record_type: DataRecordType,
+    /// Type of count record to emit.
+    pub count_record_type: CountRecordType,
+    /// If `true` and the file has a [`SRecordFile::record_layout`], data records are emitted with
+    /// the same addresses, lengths and order as in the parsed file. Data outside of the record layout is
+    /// emitted afterwards, in records of [`data_record_size`](SerializationOptions::data_record_size)
+    /// bytes.
+    pub preserve_layout: bool,
+    /// If `true`, data records are aligned to multiples of
+    /// [`data_record_size`](SerializationOptions::data_record_size): a data record starting at an
+    /// unaligned address is cut short at the next aligned address, so that the following records
+    /// start on aligned addresses. Records from a preserved record layout are not affected.
+    pub align_data_records: bool,
+    /// If `true`, data above the 32-bit address range is emitted with nonstandard S4 bank
+    /// records: a [`BankRecord`] is emitted before the first data record of each 4 GiB bank,
+    /// and the addresses of the data records are relative to the bank. Data records are split at
+    /// bank boundaries. Files without data above the 32-bit address range are emitted without bank
+    /// records, and the same as without this option.
+    ///
+    /// Most tools reject S4 records, parse the output with [`ParseOptions::bank_records`]. See
+    /// also [`SRecordFile::split_banks`] for a standard alternative.
+    pub bank_records: bool,
+}
+
+impl Default for SerializationOptions {
+    /// Generates options for 16 data bytes per record, [`S3`](DataRecordType::S3) data records and
+    /// an [`Auto`](CountRecordType::Auto) count record, without preserving the record layout,
+    /// aligning data records or bank records.
+    fn default() -> Self {
+        SerializationOptions {
+            data_record_size: 16,
+            data_record_type: DataRecordType::default(),
+            count_record_type: CountRecordType::default(),
+            preserve_layout: false,
+            align_data_records: false,
+            bank_records: false,
+        }
+    }
+}
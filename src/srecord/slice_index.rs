@@ -1,13 +1,21 @@
-use std::ops::Range;
+#[cfg(doc)]
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
 
-use crate::srecord::{DataChunk, SRecordFile};
+#[cfg(doc)]
+use crate::srecord::{Address, DataChunk, SRecordFile};
 
 /// Trait that helps index into data structures with different index and return types.
 ///
 /// The `get` and `get_mut` methods of [`DataChunk`] and [`SRecordFile`] can be used to optionally
 /// get data from their respective data structure, using any indexing type that implements
 /// [`SliceIndex`]. Currently, `u64` is used to get the data at a single address, and
-/// [`Range<u64>`](`Range`) is used to index a slice of contiguous data.
+/// [`Range<u64>`](`Range`) is used to index a slice of contiguous data. An [`Address`] can also be
+/// used to get the data at a single address in an [`SRecordFile`].
+///
+/// [`RangeInclusive<u64>`](`RangeInclusive`), [`RangeFrom<u64>`](`RangeFrom`),
+/// [`RangeTo<u64>`](`RangeTo`) and [`RangeFull`] can also be used to index a slice of contiguous
+/// data. An unbounded start or end extends to the start or end of the [`DataChunk`] containing the
+/// other end of the range, and [`RangeFull`] returns all data if it is contiguous.
 pub trait SliceIndex<T: ?Sized>: private::Sealed {
     /// The output type returned by methods.
     type Output: ?Sized;
@@ -19,9 +27,16 @@ pub trait SliceIndex<T: ?Sized>: private::Sealed {
 }
 
 mod private {
-    use std::ops::Range;
+    use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
+
+    use crate::srecord::Address;
 
     pub trait Sealed {}
     impl Sealed for u64 {}
     impl Sealed for Range<u64> {}
+    impl Sealed for RangeInclusive<u64> {}
+    impl Sealed for RangeFrom<u64> {}
+    impl Sealed for RangeTo<u64> {}
+    impl Sealed for RangeFull {}
+    impl Sealed for Address {}
 }
@@ -0,0 +1,179 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::srecord::SRecordFile;
+
+/// Cursor implementing [`Read`] and [`Seek`] over the data of an [`SRecordFile`], created with
+/// [`SRecordFile::cursor`].
+///
+/// The position of the cursor is an address, starting at 0. Reads return the data at the position,
+/// with addresses without data substituted by the fill byte, until the end address of the last data
+/// chunk, where the stream ends. Seeking relative to [`SeekFrom::End`] is relative to that end
+/// address.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Read, Seek, SeekFrom};
+/// use std::str::FromStr;
+/// use srex::srecord::SRecordFile;
+///
+/// let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+/// let mut cursor = srecord_file.cursor(0xFF);
+/// cursor.seek(SeekFrom::Start(0x1000)).unwrap();
+/// let mut data = Vec::new();
+/// cursor.read_to_end(&mut data).unwrap();
+/// assert_eq!(data, [0xAA, 0xBB, 0xFF, 0xFF, 0xCC, 0xDD]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SRecordCursor<'a> {
+    /// File that is read.
+    srecord_file: &'a SRecordFile,
+    /// Byte returned for addresses without data.
+    fill: u8,
+    /// Address of the next byte to read.
+    position: u64,
+}
+
+impl<'a> SRecordCursor<'a> {
+    /// Creates a new [`SRecordCursor`] at address 0 reading from `srecord_file`, substituting
+    /// `fill` for addresses without data.
+    pub(crate) fn new(srecord_file: &'a SRecordFile, fill: u8) -> Self {
+        SRecordCursor {
+            srecord_file,
+            fill,
+            position: 0,
+        }
+    }
+
+    /// Returns the address of the next byte to read.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Sets the address of the next byte to read.
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+impl Read for SRecordCursor<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let end_address = end_address(self.srecord_file);
+        if self.position >= end_address {
+            return Ok(0);
+        }
+        let num_bytes = buf.len().min((end_address - self.position) as usize);
+        let buf = &mut buf[..num_bytes];
+        buf.fill(self.fill);
+        let range = self.position..self.position + num_bytes as u64;
+        for (address, data) in self.srecord_file.iter_data_in_range(range) {
+            let start_index = (address - self.position) as usize;
+            buf[start_index..start_index + data.len()].copy_from_slice(data);
+        }
+        self.position += num_bytes as u64;
+        Ok(num_bytes)
+    }
+}
+
+impl Seek for SRecordCursor<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, end_address(self.srecord_file), pos)?;
+        Ok(self.position)
+    }
+}
+
+/// Cursor implementing [`Write`] and [`Seek`] into the data of an [`SRecordFile`], created with
+/// [`SRecordFile::cursor_mut`].
+///
+/// The position of the cursor is an address, starting at 0. Writes overwrite the data at the
+/// position, allocating data and merging data chunks as by [`SRecordFile::set_data`]. Seeking
+/// relative to [`SeekFrom::End`] is relative to the end address of the last data chunk.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Seek, SeekFrom, Write};
+/// use srex::srecord::SRecordFile;
+///
+/// let mut srecord_file = SRecordFile::new();
+/// let mut cursor = srecord_file.cursor_mut();
+/// cursor.seek(SeekFrom::Start(0x1000)).unwrap();
+/// cursor.write_all(b"Hello").unwrap();
+/// cursor.seek(SeekFrom::End(1)).unwrap();
+/// cursor.write_all(b"world").unwrap();
+/// assert_eq!(srecord_file.data_chunks.len(), 2);
+/// assert_eq!(srecord_file[0x1006..0x100B], *b"world");
+/// ```
+#[derive(Debug)]
+pub struct SRecordCursorMut<'a> {
+    /// File that is written.
+    srecord_file: &'a mut SRecordFile,
+    /// Address of the next byte to write.
+    position: u64,
+}
+
+impl<'a> SRecordCursorMut<'a> {
+    /// Creates a new [`SRecordCursorMut`] at address 0 writing into `srecord_file`.
+    pub(crate) fn new(srecord_file: &'a mut SRecordFile) -> Self {
+        SRecordCursorMut {
+            srecord_file,
+            position: 0,
+        }
+    }
+
+    /// Returns the address of the next byte to write.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Sets the address of the next byte to write.
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+impl Write for SRecordCursorMut<'_> {
+    /// Writes `buf` at the position of the cursor. Only the bytes up to address `u64::MAX` are
+    /// written, and nothing is written at address `u64::MAX` itself.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let num_bytes = buf
+            .len()
+            .min(usize::try_from(u64::MAX - self.position).unwrap_or(usize::MAX));
+        self.srecord_file.set_data(self.position, &buf[..num_bytes]);
+        self.position += num_bytes as u64;
+        Ok(num_bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SRecordCursorMut<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_position(self.position, end_address(self.srecord_file), pos)?;
+        Ok(self.position)
+    }
+}
+
+/// Returns the end address of the last data chunk of `srecord_file`, or 0 if the file contains no
+/// data.
+fn end_address(srecord_file: &SRecordFile) -> u64 {
+    srecord_file
+        .address_range()
+        .map_or(0, |address_range| address_range.end)
+}
+
+/// Returns the position after seeking to `pos` from `position`, in a stream ending at
+/// `end_address`, or an error if the new position is negative or does not fit in a `u64`.
+fn seek_position(position: u64, end_address: u64, pos: SeekFrom) -> io::Result<u64> {
+    let position = match pos {
+        SeekFrom::Start(position) => Some(position),
+        SeekFrom::End(offset) => end_address.checked_add_signed(offset),
+        SeekFrom::Current(offset) => position.checked_add_signed(offset),
+    };
+    position.ok_or(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "Invalid seek to a negative or overflowing position",
+    ))
+}
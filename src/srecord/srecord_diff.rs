@@ -0,0 +1,170 @@
+use std::ops::Range;
+
+use crate::srecord::error::ApplyDiffError;
+use crate::srecord::utils::{difference, intersection};
+use crate::srecord::{DataChunk, SRecordFile};
+
+/// Differences between the data of two [`SRecordFile`]s, returned by [`SRecordFile::diff`] and
+/// applied as a patch with [`SRecordFile::apply`].
+///
+/// Only the data is compared. The header data, start address and record layout are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use srex::srecord::{ChangedData, DataChunk, SRecordFile};
+///
+/// let old = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+/// let new = SRecordFile::from_str("S107100000FF0203E4\nS1053000CCDD21").unwrap();
+/// let diff = old.diff(&new);
+/// assert_eq!(diff.only_in_self, [DataChunk { address: 0x2000, data: vec![0xAA, 0xBB] }]);
+/// assert_eq!(diff.only_in_other, [DataChunk { address: 0x3000, data: vec![0xCC, 0xDD] }]);
+/// assert_eq!(
+///     diff.changed,
+///     [ChangedData { address: 0x1001, old_data: vec![0x01], new_data: vec![0xFF] }],
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SRecordDiff {
+    /// Data at addresses that only contain data in the compared file, in ascending address order.
+    pub only_in_self: Vec<DataChunk>,
+    /// Data at addresses that only contain data in the other file, in ascending address order.
+    pub only_in_other: Vec<DataChunk>,
+    /// Runs of consecutive bytes that differ between the two files, in ascending address order.
+    pub changed: Vec<ChangedData>,
+}
+
+/// Run of consecutive bytes that differ between two [`SRecordFile`]s, see [`SRecordDiff`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChangedData {
+    /// Address of the first differing byte.
+    pub address: u64,
+    /// Data in the compared file.
+    pub old_data: Vec<u8>,
+    /// Data in the other file, with the same length as `old_data`.
+    pub new_data: Vec<u8>,
+}
+
+impl ChangedData {
+    /// Returns the address range of the changed data.
+    pub fn range(&self) -> Range<u64> {
+        self.address..self.address + self.old_data.len() as u64
+    }
+}
+
+impl SRecordDiff {
+    /// Returns whether the data of both files is identical.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl SRecordFile {
+    /// Compares the data with the data of `other`, and returns the data that is only in `self`,
+    /// the data that is only in `other` and the bytes that differ, see [`SRecordDiff`].
+    pub fn diff(&self, other: &SRecordFile) -> SRecordDiff {
+        let self_ranges = data_ranges(self);
+        let other_ranges = data_ranges(other);
+        let mut changed: Vec<ChangedData> = Vec::new();
+        for range in intersection(&self_ranges, &other_ranges) {
+            let old_data = &self[range.clone()];
+            let new_data = &other[range.clone()];
+            for (index, (&old_byte, &new_byte)) in old_data.iter().zip(new_data).enumerate() {
+                if old_byte == new_byte {
+                    continue;
+                }
+                let address = range.start + index as u64;
+                match changed.last_mut() {
+                    Some(last_change) if last_change.range().end == address => {
+                        last_change.old_data.push(old_byte);
+                        last_change.new_data.push(new_byte);
+                    }
+                    _ => changed.push(ChangedData {
+                        address,
+                        old_data: vec![old_byte],
+                        new_data: vec![new_byte],
+                    }),
+                }
+            }
+        }
+        SRecordDiff {
+            only_in_self: data_chunks_in(self, &difference(&self_ranges, &other_ranges)),
+            only_in_other: data_chunks_in(other, &difference(&other_ranges, &self_ranges)),
+            changed,
+        }
+    }
+
+    /// Applies `diff` as a patch: removes the data that was only in the compared file, writes the
+    /// changed bytes and adds the data that was only in the other file. Applying the diff from
+    /// [`diff`](SRecordFile::diff) to the compared file makes its data equal to the other file.
+    ///
+    /// Returns an error, without changing anything, if the data of the file does not match the
+    /// old data of `diff`, i.e. the file is not the one the diff was made from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{ApplyDiffError, SRecordFile};
+    ///
+    /// let old = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// let new = SRecordFile::from_str("S107100000FF0203E4\nS1053000CCDD21").unwrap();
+    /// let diff = old.diff(&new);
+    ///
+    /// let mut patched = old.clone();
+    /// patched.apply(&diff).unwrap();
+    /// assert_eq!(patched.data_chunks, new.data_chunks);
+    /// assert_eq!(patched.apply(&diff), Err(ApplyDiffError::DataMismatch(0x2000..0x2002)));
+    /// ```
+    pub fn apply(&mut self, diff: &SRecordDiff) -> Result<(), ApplyDiffError> {
+        for data_chunk in diff.only_in_self.iter() {
+            let range = data_chunk.address..data_chunk.end_address();
+            if self.get(range.clone()) != Some(data_chunk.data.as_slice()) {
+                return Err(ApplyDiffError::DataMismatch(range));
+            }
+        }
+        for change in diff.changed.iter() {
+            if self.get(change.range()) != Some(change.old_data.as_slice()) {
+                return Err(ApplyDiffError::DataMismatch(change.range()));
+            }
+        }
+        for data_chunk in diff.only_in_other.iter() {
+            let range = data_chunk.address..data_chunk.end_address();
+            if self.iter_data_in_range(range.clone()).next().is_some() {
+                return Err(ApplyDiffError::UnexpectedData(range));
+            }
+        }
+
+        for data_chunk in diff.only_in_self.iter() {
+            self.erase(data_chunk.address..data_chunk.end_address());
+        }
+        for change in diff.changed.iter() {
+            self.set_data(change.address, &change.new_data);
+        }
+        for data_chunk in diff.only_in_other.iter() {
+            self.set_data(data_chunk.address, &data_chunk.data);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the address ranges of the data chunks of `srecord_file`.
+fn data_ranges(srecord_file: &SRecordFile) -> Vec<Range<u64>> {
+    srecord_file
+        .data_chunks
+        .iter()
+        .map(|data_chunk| data_chunk.address..data_chunk.end_address())
+        .collect()
+}
+
+/// Returns the data of `srecord_file` in `ranges`, which must all contain data.
+fn data_chunks_in(srecord_file: &SRecordFile, ranges: &[Range<u64>]) -> Vec<DataChunk> {
+    ranges
+        .iter()
+        .map(|range| DataChunk {
+            address: range.start,
+            data: srecord_file[range.clone()].to_vec(),
+        })
+        .collect()
+}
@@ -1,23 +1,80 @@
-use std::cmp::Ordering;
-use std::ops::{Index, IndexMut, Range};
+use std::borrow::Cow;
+use std::cmp::{max, min};
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "mmap")]
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
+#[cfg(feature = "mmap")]
+use std::path::Path;
 use std::str::FromStr;
+use std::{iter, mem, slice};
 
-use crate::srecord::data_chunk::{DataChunk, DataChunkIterator};
-use crate::srecord::error::{ErrorType, SRecordParseError};
+use crate::srecord::address::address_range;
+use crate::srecord::data_chunk::{exclusive_range, merge_chunk_lists, DataChunk};
+use crate::srecord::endianness::{check_int_size, int_from_bytes, int_to_bytes};
+#[cfg(feature = "mmap")]
+use crate::srecord::error::ReadFileError;
+use crate::srecord::error::{
+    AlignRegionsError, AppendTrailerError, ByteSwapError, ErrorType, ExtractSymbolError,
+    MergeError, NormalizeError, OffsetError, SRecordParseError, SerializationError,
+    StartAddressError, TransactionError, TransplantError,
+};
+#[cfg(feature = "rayon")]
+use crate::srecord::parallel;
 use crate::srecord::slice_index::SliceIndex;
-use crate::srecord::{CountRecord, HeaderRecord, Record, StartAddressRecord};
+use crate::srecord::utils::{difference, intersection, lines, normalized, validate_record_bytes};
+use crate::srecord::{
+    Address, BankRecord, CountRecordType, DataReader, DataRecord, DataRecordType, Endianness,
+    FrozenImage, GapFill, HeaderInfo, HeaderRecord, IntoAddress, OverlapPolicy, ParseOptions,
+    ParseStats, Record, RecordLayout, RecordType, SRecordCursor, SRecordCursorMut, SRecordParser,
+    SerializationOptions, StartAddressInfo, StartAddressRecord, Symbol, Transaction,
+    ValidationIssue, ValidationOptions,
+};
 
-/// Struct that represents an SRecord file. It only contains the raw data, not the layout of the
-/// input file.
-#[derive(Debug)]
+/// Struct that represents an SRecord file. It contains the raw data, and optionally the layout of
+/// the records in the input file, see [`record_layout`](SRecordFile::record_layout).
+///
+/// Two files are equal if their header data, data chunks and start address are equal. The record
+/// layout is not compared or hashed, so files with the same data are equal however they were
+/// parsed.
+#[derive(Clone, Debug)]
 pub struct SRecordFile {
     /// Byte vector with data in header (S0).
     pub header_data: Option<Vec<u8>>,
     /// Byte vector with actual file data (S1/S2/S3).
-    // TODO: Make private?
+    ///
+    /// Prefer [`chunks`](SRecordFile::chunks), [`chunks_mut`](SRecordFile::chunks_mut) and
+    /// [`into_chunks`](SRecordFile::into_chunks), as this field is planned to become private so
+    /// that the storage of the data can change.
+    // TODO: Make private
     pub data_chunks: Vec<DataChunk>,
-    /// Start address at the end of the file (S7/S8/S9).
+    /// Start address at the end of the file (S7/S8/S9). Not validated against the data, see
+    /// [`start_address_info`](SRecordFile::start_address_info).
     pub start_address: Option<u64>,
+    /// Layout of the parsed records, see [`record_layout`](SRecordFile::record_layout).
+    pub(crate) record_layout: Option<RecordLayout>,
+    /// Number of bytes to reserve at least for each new data chunk, see
+    /// [`with_capacity`](SRecordFile::with_capacity).
+    pub(crate) chunk_capacity: usize,
+}
+
+impl PartialEq for SRecordFile {
+    fn eq(&self, other: &Self) -> bool {
+        self.header_data == other.header_data
+            && self.data_chunks == other.data_chunks
+            && self.start_address == other.start_address
+    }
+}
+
+impl Eq for SRecordFile {}
+
+impl Hash for SRecordFile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.header_data.hash(state);
+        self.data_chunks.hash(state);
+        self.start_address.hash(state);
+    }
 }
 
 impl Default for SRecordFile {
@@ -29,14 +86,484 @@ impl Default for SRecordFile {
 
 impl SRecordFile {
     /// Creates a new [`SRecordFile`] object with empty [`data_chunks`](`SRecordFile::data_chunks`)
-    /// and `None` [`header_data`](`SRecordFile::header_data`) and
-    /// [`start_address`](`SRecordFile::start_address`).
+    /// and `None` [`header_data`](`SRecordFile::header_data`),
+    /// [`start_address`](`SRecordFile::start_address`) and
+    /// [`record_layout`](`SRecordFile::record_layout`).
     pub fn new() -> Self {
         SRecordFile {
             header_data: None,
             data_chunks: Vec::<DataChunk>::new(),
             start_address: None,
+            record_layout: None,
+            chunk_capacity: 0,
+        }
+    }
+
+    /// Creates a new, empty [`SRecordFile`] like [`new`](SRecordFile::new), with room for at least
+    /// `num_chunks` [`data_chunks`](SRecordFile::data_chunks) before reallocating.
+    ///
+    /// Every data chunk created by [`set_data`](SRecordFile::set_data), and the methods writing
+    /// through it, reserves at least `bytes_per_chunk` bytes, so that data written in address order
+    /// does not reallocate the chunk until it grows past `bytes_per_chunk` bytes. Data chunks
+    /// pushed to [`data_chunks`](SRecordFile::data_chunks) directly can be pre-allocated with
+    /// [`DataChunk::with_capacity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::with_capacity(2, 0x100);
+    /// assert!(srecord_file.data_chunks.capacity() >= 2);
+    /// assert!(srecord_file.data_chunks.is_empty());
+    ///
+    /// for address in (0x1000..0x1100).step_by(16) {
+    ///     srecord_file.set_data(address, &[0xFF; 16]);
+    /// }
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert!(srecord_file.data_chunks[0].data.capacity() >= 0x100);
+    /// ```
+    pub fn with_capacity(num_chunks: usize, bytes_per_chunk: usize) -> Self {
+        SRecordFile {
+            data_chunks: Vec::with_capacity(num_chunks),
+            chunk_capacity: bytes_per_chunk,
+            ..SRecordFile::new()
+        }
+    }
+
+    /// Creates a new [`SRecordFile`] with `data` in a single data chunk at `base_address`, e.g.
+    /// from the contents of a raw binary file. The header data and start address can be added with
+    /// [`with_header`](SRecordFile::with_header) and
+    /// [`with_start_address`](SRecordFile::with_start_address).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_binary(0x1000, &[0x00, 0x01, 0x02, 0x03])
+    ///     .with_header("HDR")
+    ///     .with_start_address(0x1000);
+    /// assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(srecord_file.header_data, Some(b"HDR".to_vec()));
+    /// assert_eq!(srecord_file.start_address, Some(0x1000));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end address of the data, `base_address + data.len()`, does not fit in a
+    /// `u64`, see [`set_data`](SRecordFile::set_data).
+    pub fn from_binary(base_address: u64, data: &[u8]) -> Self {
+        let mut srecord_file = SRecordFile::new();
+        srecord_file.set_data(base_address, data);
+        srecord_file
+    }
+
+    /// Same as [`SRecordFile::from_binary`], but skips runs of more than `max_filler_run`
+    /// consecutive `filler` bytes, e.g. erased flash in a memory dump, so that only the
+    /// programmed data is kept. Shorter runs of `filler` are kept as data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let data = [0xFF, 0xFF, 0xFF, 0x01, 0xFF, 0x02, 0xFF, 0xFF, 0xFF, 0x03];
+    /// let srecord_file = SRecordFile::from_binary_sparse(0x1000, &data, 0xFF, 2);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1003..0x1006], [0x01, 0xFF, 0x02]);
+    /// assert_eq!(srecord_file[0x1009], 0x03);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end address of the data, `base_address + data.len()`, does not fit in a
+    /// `u64`, see [`set_data`](SRecordFile::set_data).
+    pub fn from_binary_sparse(
+        base_address: u64,
+        data: &[u8],
+        filler: u8,
+        max_filler_run: usize,
+    ) -> Self {
+        if base_address.checked_add(data.len() as u64).is_none() {
+            panic!("End address of data at {base_address:#08X} does not fit in a u64");
+        }
+        let mut srecord_file = SRecordFile::new();
+        // Start index of the data that is not yet written
+        let mut start_index = 0;
+        let mut index = 0;
+        while index < data.len() {
+            if data[index] != filler {
+                index += 1;
+                continue;
+            }
+            let run_length = data[index..]
+                .iter()
+                .take_while(|&&byte| byte == filler)
+                .count();
+            if run_length > max_filler_run {
+                srecord_file.set_data(base_address + start_index as u64, &data[start_index..index]);
+                start_index = index + run_length;
+            }
+            index += run_length;
+        }
+        srecord_file.set_data(base_address + start_index as u64, &data[start_index..]);
+        srecord_file
+    }
+
+    /// Sets the [`header_data`](SRecordFile::header_data) to the bytes of `header` and returns the
+    /// file.
+    pub fn with_header(mut self, header: &str) -> Self {
+        self.set_header(header);
+        self
+    }
+
+    /// Sets the [`start_address`](SRecordFile::start_address) to `start_address` and returns the
+    /// file.
+    pub fn with_start_address(mut self, start_address: u64) -> Self {
+        self.set_start_address(start_address);
+        self
+    }
+
+    /// Sets the [`header_data`](SRecordFile::header_data) to the bytes of `header`.
+    pub fn set_header(&mut self, header: &str) {
+        self.header_data = Some(header.as_bytes().to_vec());
+    }
+
+    /// Returns the [`header_data`](SRecordFile::header_data) as text, with bytes that are not valid
+    /// UTF-8 replaced, or `None` if the file has no header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S00600004844521B").unwrap();
+    /// assert_eq!(srecord_file.header_str().unwrap(), "HDR");
+    /// ```
+    pub fn header_str(&self) -> Option<Cow<'_, str>> {
+        self.header_data
+            .as_ref()
+            .map(|header_data| String::from_utf8_lossy(header_data))
+    }
+
+    /// Returns the fields of the [`header_data`](SRecordFile::header_data) following the common
+    /// S0 convention, see [`HeaderInfo`], or `None` if the file has no header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{HeaderInfo, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.set_header_info(&HeaderInfo {
+    ///     module_name: String::from("bootloader"),
+    ///     version: String::from("01"),
+    ///     revision: String::from("02"),
+    ///     description: String::from("Release build"),
+    /// });
+    /// assert_eq!(srecord_file.header_str().unwrap(), "bootloader          0102Release build");
+    /// assert_eq!(srecord_file.header_info().unwrap().module_name, "bootloader");
+    /// ```
+    pub fn header_info(&self) -> Option<HeaderInfo> {
+        self.header_data.as_deref().map(HeaderInfo::from_bytes)
+    }
+
+    /// Sets the [`header_data`](SRecordFile::header_data) to the fields of `header_info`, see
+    /// [`HeaderInfo::to_bytes`].
+    pub fn set_header_info(&mut self, header_info: &HeaderInfo) {
+        self.header_data = Some(header_info.to_bytes());
+    }
+
+    /// Sets the [`start_address`](SRecordFile::start_address) to `start_address`.
+    pub fn set_start_address<A: IntoAddress>(&mut self, start_address: A) {
+        self.start_address = Some(start_address.into_address().value());
+    }
+
+    /// Returns the layout of the parsed records, if parsed with [`ParseOptions::record_layout`].
+    /// Used when serializing with [`SerializationOptions::preserve_layout`] or
+    /// [`serialize_original`](SRecordFile::serialize_original).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ParseOptions, SRecordFile};
+    ///
+    /// let mut options = ParseOptions::default();
+    /// options.record_layout = true;
+    /// let (srecord_file, _) =
+    ///     SRecordFile::from_str_with_options("S1051004AABB81\nS107100000010203E2", &options)
+    ///         .unwrap();
+    /// let record_layout = srecord_file.record_layout().unwrap();
+    /// assert_eq!(record_layout.data_records, [0x1004..0x1006, 0x1000..0x1004]);
+    /// ```
+    pub fn record_layout(&self) -> Option<&RecordLayout> {
+        self.record_layout.as_ref()
+    }
+
+    /// Replaces the [`record_layout`](SRecordFile::record_layout), e.g. with a
+    /// [`RecordLayout::new`] to serialize the data in records of chosen address ranges with
+    /// [`SerializationOptions::preserve_layout`], or with `None` to forget the parsed layout.
+    pub fn set_record_layout(&mut self, record_layout: Option<RecordLayout>) {
+        self.record_layout = record_layout;
+    }
+
+    /// Removes all data, keeping the header data and start address, so that the file can be
+    /// reused. The [`record_layout`](SRecordFile::record_layout) is removed together with the data
+    /// it describes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::new().with_header("HDR").with_start_address(0x1000);
+    /// for value in 0..4 {
+    ///     srecord_file.clear();
+    ///     srecord_file.fill(0x1000..0x1004, value);
+    ///     assert_eq!(srecord_file[0x1000..0x1004], [value; 4]);
+    /// }
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(srecord_file.start_address, Some(0x1000));
+    /// ```
+    pub fn clear(&mut self) {
+        self.data_chunks.clear();
+        self.record_layout = None;
+    }
+
+    /// Removes all data, the header data and the start address, leaving the file as created by
+    /// [`SRecordFile::new`]. Unlike replacing the file with a new one, the memory allocated for the
+    /// data chunks is kept.
+    pub fn clear_all(&mut self) {
+        self.clear();
+        self.header_data = None;
+        self.start_address = None;
+    }
+
+    /// Converts the [`SRecordFile`] into an immutable [`FrozenImage`] that can be cheaply cloned and
+    /// shared between threads.
+    pub fn freeze(self) -> FrozenImage {
+        FrozenImage::from(self)
+    }
+
+    /// Returns a [`DataReader`] for fast repeated reads of bytes or address ranges that are close
+    /// to each other.
+    pub fn reader(&self) -> DataReader<'_> {
+        DataReader::new(self)
+    }
+
+    /// Returns an [`SRecordCursor`] implementing [`Read`](std::io::Read) and
+    /// [`Seek`](std::io::Seek) over the data of the file, with addresses without data substituted
+    /// by `fill`.
+    pub fn cursor(&self, fill: u8) -> SRecordCursor<'_> {
+        SRecordCursor::new(self, fill)
+    }
+
+    /// Returns an [`SRecordCursorMut`] implementing [`Write`](std::io::Write) and
+    /// [`Seek`](std::io::Seek) into the data of the file.
+    pub fn cursor_mut(&mut self) -> SRecordCursorMut<'_> {
+        SRecordCursorMut::new(self)
+    }
+
+    /// Parses an SRecord file as configured by `options`, and returns the [`SRecordFile`]
+    /// containing the data in the file together with [`ParseStats`] about the parsed records.
+    ///
+    /// With the `rayon` feature enabled, large inputs are split into batches of lines that are
+    /// parsed in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ErrorType, ParseOptions, SRecordFile};
+    ///
+    /// let srecord_str = "S107100000010203E2\n\
+    ///                    S9030000FC\n\
+    ///                    S107200000010203D2";
+    ///
+    /// let lenient = ParseOptions::default();
+    /// let (srecord_file, stats) = SRecordFile::from_str_with_options(srecord_str, &lenient).unwrap();
+    /// assert_eq!(srecord_file[0x2000], 0x00);
+    /// assert_eq!(stats.warnings[0].line_index, 2);
+    /// assert_eq!(stats.warnings[0].error_type, ErrorType::RecordAfterTerminator);
+    ///
+    /// let mut strict = ParseOptions::default();
+    /// strict.strict = true;
+    /// let error = SRecordFile::from_str_with_options(srecord_str, &strict).unwrap_err();
+    /// assert_eq!(error.error_type, ErrorType::RecordAfterTerminator);
+    /// ```
+    pub fn from_str_with_options(
+        srecord_str: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseStats), SRecordParseError> {
+        SRecordFile::from_bytes_with_options(srecord_str.as_bytes(), options)
+    }
+
+    /// Parses an SRecord file from its ASCII bytes, e.g. a buffer read directly from a file,
+    /// without first validating that the bytes are UTF-8. Otherwise the same as
+    /// [`SRecordFile::from_str`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_bytes = b"S107100000010203E2\r\nS9031000EC\r\n";
+    /// let srecord_file = SRecordFile::from_bytes(srecord_bytes).unwrap();
+    /// assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(srecord_file.start_address, Some(0x1000));
+    /// ```
+    pub fn from_bytes(srecord_bytes: &[u8]) -> Result<Self, SRecordParseError> {
+        let (srecord_file, _) =
+            SRecordFile::from_bytes_with_options(srecord_bytes, &ParseOptions::default())?;
+        Ok(srecord_file)
+    }
+
+    /// Memory-maps the file at `path` and parses it with [`SRecordFile::from_bytes`], so that the
+    /// text of the file does not have to be read into memory first. Requires the `mmap` feature.
+    ///
+    /// The file must not be modified by another process while it is being parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_mmap("tests/srec_files/wikipedia.s19").unwrap();
+    /// assert_eq!(srecord_file.start_address, Some(0));
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<Path>>(path: P) -> Result<Self, ReadFileError> {
+        let file = File::open(path).map_err(ReadFileError::Io)?;
+        // SAFETY: The map is only read while parsing, and the file is required not to be modified
+        // in the meantime
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(ReadFileError::Io)?;
+        SRecordFile::from_bytes(&mmap).map_err(ReadFileError::Parse)
+    }
+
+    /// Same as [`SRecordFile::from_str_with_options`], but parses the ASCII bytes of an SRecord
+    /// file, see [`SRecordFile::from_bytes`]. If [`ParseOptions::record_layout`] is set, bytes that
+    /// are not valid UTF-8 are replaced in [`RecordLayout::source`].
+    pub fn from_bytes_with_options(
+        srecord_bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseStats), SRecordParseError> {
+        SRecordFile::parse_bytes(srecord_bytes, options, false)
+    }
+
+    /// Same as [`SRecordFile::from_bytes_with_options`], but lines that contain bytes that are not
+    /// ASCII, e.g. noise in a capture from a serial port, are skipped instead of failing the whole
+    /// file. Every skipped line is reported as a [`ParseWarning`](crate::srecord::ParseWarning)
+    /// with [`ErrorType::NonAsciiLine`], or returned as an error if [`ParseOptions::strict`] is
+    /// set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ErrorType, ParseOptions, SRecordFile};
+    ///
+    /// let srecord_bytes = b"S107100000010203E2\n\xFF\xFES1\x00\nS1051004AABB81\n";
+    /// let (srecord_file, stats) =
+    ///     SRecordFile::from_bytes_lossy(srecord_bytes, &ParseOptions::default()).unwrap();
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB]);
+    /// assert_eq!(stats.warnings[0].line_index, 1);
+    /// assert_eq!(stats.warnings[0].error_type, ErrorType::NonAsciiLine);
+    /// ```
+    pub fn from_bytes_lossy(
+        srecord_bytes: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseStats), SRecordParseError> {
+        SRecordFile::parse_bytes(srecord_bytes, options, true)
+    }
+
+    /// Extracts the records embedded in arbitrary text, e.g. a log file where SRecord lines are
+    /// interleaved with other output, as configured by `options`.
+    ///
+    /// A record may be preceded by other text on its line, e.g. a timestamp, but must be the rest
+    /// of the line, apart from trailing whitespace. Lines that do not end with a valid record,
+    /// including records with invalid checksums, are skipped and reported as
+    /// [`ParseWarning`](crate::srecord::ParseWarning)s, or returned as an error if
+    /// [`ParseOptions::strict`] is set. The extracted records are validated against each other
+    /// like the lines of an SRecord file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ErrorType, ParseOptions, SRecordFile};
+    ///
+    /// let log = "boot: dumping flash\n\
+    ///            [12:00:01] S107100000010203E2\n\
+    ///            [12:00:01] S1051004AABB00\n\
+    ///            [12:00:02] S1051004AABB81\n";
+    /// let (srecord_file, stats) =
+    ///     SRecordFile::extract_records_from_text(log, &ParseOptions::default()).unwrap();
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB]);
+    /// assert_eq!(stats.warnings.len(), 2);
+    /// assert_eq!(stats.warnings[0].line_index, 0);
+    /// assert_eq!(
+    ///     stats.warnings[1].error_type,
+    ///     ErrorType::CalculatedChecksumNotMatchingParsedChecksum,
+    /// );
+    /// ```
+    pub fn extract_records_from_text(
+        text: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, ParseStats), SRecordParseError> {
+        let mut parser = SRecordParser::with_options(options.clone());
+        for line in text.lines() {
+            let line = line.trim_end().as_bytes();
+            // Report the error of the last candidate, as it is the most likely to be the record
+            let mut record_start = Err(ErrorType::InvalidFirstCharacter);
+            let candidates = (0..line.len()).filter(|index| line[*index] == b'S');
+            for index in candidates {
+                record_start = validate_record_bytes(&line[index..]).map(|_| index);
+                if record_start.is_ok() {
+                    break;
+                }
+            }
+            match record_start {
+                Ok(index) => parser.parse_line(&line[index..])?,
+                Err(error_type) => parser.skip_line(error_type)?,
+            }
+        }
+        parser.finish_with_stats()
+    }
+
+    /// Parses `srecord_bytes` as configured by `options`, skipping lines that are not ASCII if
+    /// `skip_non_ascii_lines` is set.
+    fn parse_bytes(
+        srecord_bytes: &[u8],
+        options: &ParseOptions,
+        skip_non_ascii_lines: bool,
+    ) -> Result<(Self, ParseStats), SRecordParseError> {
+        // Lines to skip are invalid records, so such input falls back to the sequential parser
+        #[cfg(feature = "rayon")]
+        if let Some((mut srecord_file, stats)) =
+            parallel::from_bytes_with_options(srecord_bytes, options)
+        {
+            srecord_file.attach_source(srecord_bytes);
+            return Ok((srecord_file, stats));
+        }
+
+        let mut parser = SRecordParser::with_options(options.clone());
+        // Most files are written with 16 data bytes per record. An S1 record is the shortest such
+        // line: record type, then byte count, address, data and checksum as two hex digits per
+        // byte, and a line feed, i.e. 43 bytes. Data records of the same chunk follow each other
+        // in most files, so reserve for all data in every chunk, and let the parser release what
+        // is not used.
+        const DATA_BYTES_PER_RECORD: usize = 16;
+        let line_length =
+            2 + 2 * (1 + RecordType::S1.num_address_bytes() + DATA_BYTES_PER_RECORD + 1) + 1;
+        parser.reserve(0, srecord_bytes.len() / line_length * DATA_BYTES_PER_RECORD);
+        for line in lines(srecord_bytes) {
+            match skip_non_ascii_lines && !line.is_ascii() {
+                true => parser.skip_line(ErrorType::NonAsciiLine)?,
+                false => parser.parse_line(line)?,
+            }
         }
+        let (mut srecord_file, stats) = parser.finish_with_stats()?;
+        srecord_file.attach_source(srecord_bytes);
+        Ok((srecord_file, stats))
     }
 
     /// Returns a reference to a byte or byte data subslice depending on the type of index.
@@ -107,17 +634,141 @@ impl SRecordFile {
         index.get_mut(self)
     }
 
+    /// Returns the unsigned integer of `size` bytes at `address` with byte order `endianness`, or
+    /// `None` if any of the bytes has no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{Endianness, SRecordFile};
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// assert_eq!(srecord_file.read_int(0x1000, 3, Endianness::Big), Some(0x000102));
+    /// assert_eq!(srecord_file.read_u32_le(0x1000), Some(0x03020100));
+    /// assert_eq!(srecord_file.read_u16_be(0x1003), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`read_int`](SRecordFile::read_int) will [`panic!`] if `size` is not between 1 and 8.
+    pub fn read_int<A: IntoAddress>(
+        &self,
+        address: A,
+        size: usize,
+        endianness: Endianness,
+    ) -> Option<u64> {
+        check_int_size(size);
+        let address = address.into_address().value();
+        let end_address = address.checked_add(size as u64)?;
+        Some(int_from_bytes(self.get(address..end_address)?, endianness))
+    }
+
+    /// Returns the little-endian `u16` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u16_le<A: IntoAddress>(&self, address: A) -> Option<u16> {
+        self.read_array(address).map(u16::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u16` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u16_be<A: IntoAddress>(&self, address: A) -> Option<u16> {
+        self.read_array(address).map(u16::from_be_bytes)
+    }
+
+    /// Returns the little-endian `u32` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u32_le<A: IntoAddress>(&self, address: A) -> Option<u32> {
+        self.read_array(address).map(u32::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u32` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u32_be<A: IntoAddress>(&self, address: A) -> Option<u32> {
+        self.read_array(address).map(u32::from_be_bytes)
+    }
+
+    /// Returns the little-endian `u64` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u64_le<A: IntoAddress>(&self, address: A) -> Option<u64> {
+        self.read_array(address).map(u64::from_le_bytes)
+    }
+
+    /// Returns the big-endian `u64` at `address`, or `None` if any of its bytes has no data.
+    pub fn read_u64_be<A: IntoAddress>(&self, address: A) -> Option<u64> {
+        self.read_array(address).map(u64::from_be_bytes)
+    }
+
+    /// Returns the data in `range`, with addresses without data substituted by `fill`. Unlike
+    /// [`get`](SRecordFile::get), which returns `None` as soon as `range` crosses a gap, this
+    /// always returns `range.end - range.start` bytes.
+    ///
+    /// Same as [`to_binary_range`](SRecordFile::to_binary_range), which should be used instead.
+    #[deprecated(since = "0.1.0", note = "use `to_binary_range` instead")]
+    pub fn read_padded(&self, range: Range<u64>, fill: u8) -> Vec<u8> {
+        self.to_binary_range(range, fill)
+    }
+
+    /// Returns the `N` bytes at `address` as an array, or `None` if any of the bytes has no data.
+    fn read_array<const N: usize, A: IntoAddress>(&self, address: A) -> Option<[u8; N]> {
+        let address = address.into_address().value();
+        let end_address = address.checked_add(N as u64)?;
+        self.get(address..end_address)?.try_into().ok()
+    }
+
+    /// Returns whether there is data at `address`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// assert!(srecord_file.contains_address(0x1003));
+    /// assert!(!srecord_file.contains_address(0x1004));
+    /// ```
+    pub fn contains_address(&self, address: u64) -> bool {
+        self.get_data_chunk_index(address, false).is_ok()
+    }
+
+    /// Returns whether there is data at every address in `range`, i.e. whether `range` is inside
+    /// a single data chunk. Empty ranges are always contained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// assert!(srecord_file.contains_range(0x1000..0x1004));
+    /// assert!(!srecord_file.contains_range(0x1000..0x1005));
+    /// assert!(srecord_file.contains_range(0x2000..0x2000));
+    /// ```
+    pub fn contains_range(&self, range: Range<u64>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        match self.get_data_chunk_index(range.start, false) {
+            Ok(index) => range.end <= self.data_chunks[index].end_address(),
+            Err(_) => false,
+        }
+    }
+
     /// Iterate over records in file.
     ///
     /// - First, a S0 record is returned if there is header data in the [`SRecordFile`].
     /// - Then, S3 records are returned for each data chunk, where each record is (at most)
-    ///   `data_record_size` long.
+    ///   `data_record_size` long. Use
+    ///   [`iter_records_with_options`](SRecordFile::iter_records_with_options) to emit S1 or S2
+    ///   records instead.
     /// - Then, an S5 record is returned if the number of data records fits in 16 bites. Otherwise,
     ///   an S6 record is returned if the number of data records fits in 24 bits. Otherwise, no
-    ///   count record is returned.
+    ///   count record is returned. Use
+    ///   [`iter_records_with_options`](SRecordFile::iter_records_with_options) to select the count
+    ///   record type.
     /// - Finally, if a [`start_address`](`SRecordFile.start_address`) is configured in the
     ///   [`SRecordFile`] then an S7 record is returned.
     ///
+    /// Returns an error if `data_record_size` is zero or does not fit in an S3 record, see
+    /// [`RecordType::max_num_data_bytes`], or if the start address does not fit in 32 bits.
+    ///
     /// # Examples
     ///
     /// ```
@@ -134,152 +785,2567 @@ impl SRecordFile {
     ///                    S9030000FC";
     /// let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
     ///
-    /// for record in srecord_file.iter_records(16) {
+    /// for record in srecord_file.iter_records(16).unwrap() {
     ///     println!("{}", record.serialize());
     /// }
+    /// assert!(srecord_file.iter_records(300).is_err());
     /// ```
-    // TODO: Allow different file types
-    pub fn iter_records(&self, data_record_size: usize) -> SRecordFileIterator {
-        SRecordFileIterator {
-            srecord_file: self,
-            stage: SRecordFileIteratorStage::Header,
-            data_chunk_index: 0,
-            data_chunk_iterator: None,
+    pub fn iter_records(
+        &self,
+        data_record_size: usize,
+    ) -> Result<SRecordFileIterator<'_>, SerializationError> {
+        self.iter_records_with_options(&SerializationOptions {
             data_record_size,
-            num_data_records: 0,
-        }
+            ..Default::default()
+        })
     }
 
-    /// Optionally return an index in [`data_chunks`](`SRecordFile::data_chunks`) where `address` is
-    /// found, or `None` if out of bounds.
+    /// Iterate over records in file, as configured by `options`.
     ///
-    /// If `inclusive_end` is `true`, then data chunk's [`end_address`](`DataChunk::end_address`) is
-    /// counted as in bounds, otherwise it is counted as out of bounds. This helps in selecting a
-    /// data chunk index when trying to get data in the [`SRecordFile`] vs. allocating more address
-    /// ranges.
-    // TODO: Unit tests
-    fn get_data_chunk_index(&self, address: u64, inclusive_end: bool) -> Result<usize, usize> {
-        let mut left_index = 0;
-        let mut right_index = self.data_chunks.len();
-        loop {
-            let index_diff = right_index - left_index;
-            if index_diff == 0 {
-                return Err(left_index);
-            }
-            let data_chunk = &self.data_chunks[left_index];
-            let data_chunk_start_address = data_chunk.address;
-            let mut data_chunk_end_address =
-                data_chunk_start_address + data_chunk.data.len() as u64;
-            if inclusive_end {
-                data_chunk_end_address += 1;
-            }
-            if index_diff == 1 {
-                if address >= data_chunk_start_address && address < data_chunk_end_address {
-                    return Ok(left_index);
-                } else if address < data_chunk_start_address {
-                    return Err(left_index);
-                } else {
-                    return Err(right_index);
-                }
-            } else {
-                let middle_index = left_index + index_diff / 2;
-                if address < data_chunk_start_address {
-                    right_index = middle_index;
-                } else if address >= data_chunk_end_address {
-                    left_index = middle_index;
-                } else {
-                    return Ok(left_index);
-                }
-            }
-        }
-    }
-
-    // TODO: Tests
-    /// Returns a reference to the element in [`SRecordFile::data_chunks`] which contains `address`,
-    /// or `None` if out of bounds.
+    /// Works like [`iter_records`](SRecordFile::iter_records), but data records are returned as
+    /// the type selected by [`SerializationOptions::data_record_type`], and the count record as the
+    /// type selected by [`SerializationOptions::count_record_type`]. The start address record
+    /// matches the type of the data records: S9 for S1, S8 for S2 and S7 for S3.
+    ///
+    /// Returns an error if [`SerializationOptions::data_record_size`] is zero or does not fit in
+    /// the selected type of data record, if any data does not fit in the addresses of the selected
+    /// type of data record, if the number of data records does not fit in the selected type of
+    /// count record, or if the start address does not fit in the start address record.
     ///
     /// # Examples
     ///
     /// ```
-    /// use srex::srecord::{DataChunk, SRecordFile};
+    /// use std::str::FromStr;
     ///
-    /// let srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk{
-    ///         address: 0x1000,
-    ///         data: vec![0x01, 0x02, 0x03, 0x04],
-    ///     }],
-    ///     start_address: None,
+    /// use srex::srecord::{
+    ///     CountRecordType, DataRecordType, Record, SerializationOptions, SRecordFile,
     /// };
-    /// assert_eq!(srecord_file.get(0x1001), Some(&0x02u8));
-    /// assert!(srecord_file.get(0x1004).is_none());
+    ///
+    /// let srecord_file = SRecordFile::from_str("S3090000100000010203E0\nS70500001000EA").unwrap();
+    /// let mut options = SerializationOptions::default();
+    /// options.data_record_size = 16;
+    /// options.data_record_type = DataRecordType::Auto;
+    /// options.count_record_type = CountRecordType::S6;
+    /// let mut records = srecord_file.iter_records_with_options(&options).unwrap();
+    /// assert_eq!(records.next().unwrap().serialize(), "S107100000010203E2");
+    /// assert_eq!(records.next().unwrap().serialize(), "S604000001FA");
+    /// assert_eq!(records.next().unwrap().serialize(), "S9031000EC");
     /// ```
-    pub(crate) fn get_data_chunk(&self, address: u64) -> Option<&DataChunk> {
-        match self.get_data_chunk_index(address, false) {
-            Ok(data_chunk_index) => Some(&self.data_chunks[data_chunk_index]),
+    pub fn iter_records_with_options(
+        &self,
+        options: &SerializationOptions,
+    ) -> Result<SRecordFileIterator<'_>, SerializationError> {
+        let data_record_type = match options.data_record_type {
+            DataRecordType::S1 => RecordType::S1,
+            DataRecordType::S2 => RecordType::S2,
+            DataRecordType::S3 => RecordType::S3,
+            DataRecordType::Auto => self.minimal_record_type(),
+        };
+        let max_data_record_size = data_record_type.max_num_data_bytes();
+        if options.data_record_size == 0 {
+            return Err(SerializationError::ZeroDataRecordSize);
+        }
+        let record_layout = match self.record_layout.as_ref() {
+            Some(record_layout) if options.preserve_layout => Some(&record_layout.data_records),
+            _ => None,
+        };
+        let max_layout_record_size = record_layout
+            .into_iter()
+            .flatten()
+            .map(|range| (range.end - range.start) as usize)
+            .max()
+            .unwrap_or(0);
+        if max(options.data_record_size, max_layout_record_size) > max_data_record_size {
+            return Err(SerializationError::DataRecordSizeTooLarge(
+                max_data_record_size,
+            ));
+        }
+        let max_end_address = 1 << (8 * data_record_type.num_address_bytes());
+        match options.bank_records {
+            // Addresses are relative to the bank, and data records are split at bank boundaries
+            true => {
+                let too_large = |data_chunk: &&DataChunk| {
+                    let last_address = data_chunk.end_address() - 1;
+                    let bank_address = last_address & !0xFFFF_FFFF;
+                    match data_chunk.address < bank_address {
+                        true => 1 << 32 > max_end_address,
+                        false => last_address - bank_address + 1 > max_end_address,
+                    }
+                };
+                if let Some(data_chunk) = self.data_chunks.iter().find(too_large) {
+                    return Err(SerializationError::AddressTooLargeForFormat(
+                        data_chunk.end_address() - 1,
+                    ));
+                }
+            }
+            false => {
+                if let Some(last_chunk) = self.data_chunks.last() {
+                    if last_chunk.end_address() > max_end_address {
+                        return Err(SerializationError::AddressTooLargeForFormat(
+                            last_chunk.end_address() - 1,
+                        ));
+                    }
+                }
+            }
+        }
+        let start_address_record_type = match data_record_type {
+            RecordType::S1 => RecordType::S9,
+            RecordType::S2 => RecordType::S8,
+            _ => RecordType::S7,
+        };
+        if let Some(start_address) = self.start_address {
+            if !Address::new(start_address).fits(&start_address_record_type) {
+                return Err(SerializationError::StartAddressTooLarge(start_address));
+            }
+        }
+        // Only explicitly selected count record types can fail, so avoid counting the records
+        // otherwise
+        if matches!(
+            options.count_record_type,
+            CountRecordType::S5 | CountRecordType::S6
+        ) {
+            let num_data_records = self.iter_data_records(options, record_layout).count();
+            options.count_record_type.count_record(num_data_records)?;
+        }
+        Ok(SRecordFileIterator {
+            srecord_file: self,
+            stage: SRecordFileIteratorStage::Header,
+            data_records: self.iter_data_records(options, record_layout),
+            data_record_type,
+            count_record_type: options.count_record_type,
+            start_address_record_type,
+            num_data_records: 0,
+            bank_records: options.bank_records,
+            bank: 0,
+            pending_data_record: None,
+        })
+    }
+
+    /// Serializes the file so that it reproduces the parsed text as closely as possible.
+    ///
+    /// If the file was parsed with [`ParseOptions::record_layout`] and has not been edited since,
+    /// the parsed text is returned byte-for-byte. If it has been edited, the records are serialized
+    /// with [`SerializationOptions::preserve_layout`], using the same type of data and count
+    /// records and the same line endings as the parsed text. Without a record layout, the file is
+    /// serialized with the default [`SerializationOptions`].
+    ///
+    /// Edits are detected by comparing a hash of the data, header data, start address and record
+    /// layout with the one taken when parsing, so the parsed text is not parsed again. Invalid
+    /// UTF-8 in the parsed text, e.g. in lines skipped by [`SRecordFile::from_bytes_lossy`], is
+    /// replaced, see [`serialize_original_bytes`](SRecordFile::serialize_original_bytes).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ParseOptions, SRecordFile};
+    ///
+    /// let srecord_str = "S107100000010203E2\r\nS10510040405DD\r\n";
+    /// let mut options = ParseOptions::default();
+    /// options.record_layout = true;
+    /// let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    /// assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+    ///
+    /// srecord_file[0x1005] = 0xFF;
+    /// assert_eq!(
+    ///     srecord_file.serialize_original().unwrap(),
+    ///     "S107100000010203E2\r\nS105100404FFE3\r\n",
+    /// );
+    /// ```
+    pub fn serialize_original(&self) -> Result<String, SerializationError> {
+        let srecord_bytes = self.serialize_original_bytes()?;
+        match String::from_utf8(srecord_bytes) {
+            Ok(srecord_str) => Ok(srecord_str),
+            Err(error) => Ok(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+        }
+    }
+
+    /// Same as [`serialize_original`](SRecordFile::serialize_original), but returns the parsed
+    /// input byte-for-byte even if it is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{ParseOptions, SRecordFile};
+    ///
+    /// let srecord_bytes = b"S107100000010203E2\n\xFF\xFE\nS9031000EC\n";
+    /// let mut options = ParseOptions::default();
+    /// options.record_layout = true;
+    /// let (srecord_file, _) = SRecordFile::from_bytes_lossy(srecord_bytes, &options).unwrap();
+    /// assert_eq!(srecord_file.serialize_original_bytes().unwrap(), srecord_bytes);
+    /// ```
+    pub fn serialize_original_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let record_layout = match self.record_layout.as_ref() {
+            Some(record_layout) => record_layout,
+            None => {
+                return self
+                    .serialize_lines(&SerializationOptions::default(), "\n")
+                    .map(String::into_bytes)
+            }
+        };
+        if record_layout.is_unedited(self.fingerprint(&record_layout.data_records)) {
+            return Ok(record_layout.source().to_vec());
+        }
+
+        let mut options = SerializationOptions {
+            data_record_type: record_layout
+                .lines()
+                .find_map(|line| match line.get(..2) {
+                    Some(b"S1") => Some(DataRecordType::S1),
+                    Some(b"S2") => Some(DataRecordType::S2),
+                    Some(b"S3") => Some(DataRecordType::S3),
+                    _ => None,
+                })
+                .unwrap_or(DataRecordType::Auto),
+            count_record_type: record_layout
+                .lines()
+                .find_map(|line| match line.get(..2) {
+                    Some(b"S5") => Some(CountRecordType::S5),
+                    Some(b"S6") => Some(CountRecordType::S6),
+                    _ => None,
+                })
+                .unwrap_or(CountRecordType::Omit),
+            preserve_layout: true,
+            ..Default::default()
+        };
+        if let Some(max_layout_record_size) = record_layout
+            .data_records
+            .iter()
+            .map(|range| (range.end - range.start) as usize)
+            .max()
+        {
+            options.data_record_size = max(max_layout_record_size, 1);
+        }
+        let line_ending = record_layout.line_ending();
+        let mut srecord_str = self.serialize_lines(&options, line_ending)?;
+        if !record_layout.ends_with_line_ending() && srecord_str.ends_with(line_ending) {
+            srecord_str.truncate(srecord_str.len() - line_ending.len());
+        }
+        Ok(srecord_str.into_bytes())
+    }
+
+    /// Returns a fingerprint of the contents of the file, as compared by [`PartialEq`], and of the
+    /// address ranges `data_records` of its record layout, used to detect edits after parsing.
+    fn fingerprint(&self, data_records: &[Range<u64>]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        data_records.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Keeps `source` as the parsed input of the [`record_layout`](SRecordFile::record_layout), if
+    /// the file has one, so that [`serialize_original`](SRecordFile::serialize_original)
+    /// reproduces it until the file is edited.
+    pub(crate) fn attach_source(&mut self, source: impl Into<Vec<u8>>) {
+        if let Some(record_layout) = self.record_layout.take() {
+            let fingerprint = self.fingerprint(&record_layout.data_records);
+            self.record_layout = Some(RecordLayout::with_source(
+                record_layout.data_records,
+                source.into(),
+                fingerprint,
+            ));
+        }
+    }
+
+    /// Serializes the file as configured by `options`, ending every record with `line_ending`.
+    fn serialize_lines(
+        &self,
+        options: &SerializationOptions,
+        line_ending: &str,
+    ) -> Result<String, SerializationError> {
+        let mut srecord_str = String::new();
+        for record in self.iter_records_with_options(options)? {
+            srecord_str.push_str(&record.serialize());
+            srecord_str.push_str(line_ending);
+        }
+        Ok(srecord_str)
+    }
+
+    /// Iterates over the data in the file as [`DataRecord`]s of at most
+    /// [`SerializationOptions::data_record_size`] bytes, aligned if
+    /// [`SerializationOptions::align_data_records`] is set.
+    ///
+    /// If `record_layout` is given, the data inside each of its address ranges is returned first,
+    /// in the order of `record_layout`. The data outside of `record_layout` is returned afterwards.
+    fn iter_data_records<'a>(
+        &'a self,
+        options: &SerializationOptions,
+        record_layout: Option<&'a Vec<Range<u64>>>,
+    ) -> Box<dyn Iterator<Item = DataRecord<'a>> + 'a> {
+        if options.bank_records {
+            let options = SerializationOptions {
+                bank_records: false,
+                ..options.clone()
+            };
+            return Box::new(
+                self.iter_data_records(&options, record_layout)
+                    .flat_map(split_at_bank),
+            );
+        }
+        let data_record_size = options.data_record_size;
+        let alignment = match options.align_data_records {
+            true => data_record_size as u64,
+            false => 1,
+        };
+        let record_layout = match record_layout {
+            Some(record_layout) => record_layout,
+            None => {
+                return Box::new(self.data_chunks.iter().flat_map(move |data_chunk| {
+                    data_chunk.iter_records_aligned(data_record_size, alignment)
+                }))
+            }
+        };
+        let layout_records = record_layout.iter().flat_map(move |range| {
+            self.iter_data_in_range(range.clone())
+                .map(|(address, data)| DataRecord { address, data })
+        });
+        let covered_ranges = normalized(record_layout.clone());
+        let uncovered_ranges: Vec<Range<u64>> = self
+            .data_chunks
+            .iter()
+            .flat_map(|data_chunk| {
+                difference(
+                    slice::from_ref(&(data_chunk.address..data_chunk.end_address())),
+                    &covered_ranges,
+                )
+            })
+            .collect();
+        let uncovered_records = uncovered_ranges.into_iter().flat_map(move |range| {
+            let mut address = range.start;
+            iter::from_fn(move || {
+                if address >= range.end {
+                    return None;
+                }
+                let mut end_address = min(address + data_record_size as u64, range.end);
+                if !address.is_multiple_of(alignment) {
+                    end_address = min(end_address, address.next_multiple_of(alignment));
+                }
+                let data_record = DataRecord {
+                    address,
+                    data: &self[address..end_address],
+                };
+                address = end_address;
+                Some(data_record)
+            })
+        });
+        Box::new(layout_records.chain(uncovered_records))
+    }
+
+    /// Returns the lowest address containing data, or `None` if the file has no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.min_address(), Some(0x1000));
+    /// assert_eq!(SRecordFile::new().min_address(), None);
+    /// ```
+    pub fn min_address(&self) -> Option<u64> {
+        self.data_chunks
+            .first()
+            .map(|data_chunk| data_chunk.address)
+    }
+
+    /// Returns the highest address containing data, or `None` if the file has no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.max_address(), Some(0x2001));
+    /// assert_eq!(SRecordFile::new().max_address(), None);
+    /// ```
+    pub fn max_address(&self) -> Option<u64> {
+        self.data_chunks
+            .last()
+            .map(|data_chunk| data_chunk.end_address() - 1)
+    }
+
+    /// Returns the address range from the lowest address containing data up to and excluding the
+    /// end address of the last data, or `None` if the file has no data. Gaps between data chunks
+    /// are included in the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.address_range(), Some(0x1000..0x2002));
+    /// assert_eq!(SRecordFile::new().address_range(), None);
+    /// ```
+    pub fn address_range(&self) -> Option<Range<u64>> {
+        match (self.data_chunks.first(), self.data_chunks.last()) {
+            (Some(first_chunk), Some(last_chunk)) => {
+                Some(first_chunk.address..last_chunk.end_address())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the total number of data bytes in the file, not counting gaps between data chunks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.total_data_len(), 6);
+    /// ```
+    pub fn total_data_len(&self) -> u64 {
+        self.data_chunks
+            .iter()
+            .map(|data_chunk| data_chunk.data.len() as u64)
+            .sum()
+    }
+
+    /// Returns the number of contiguous data chunks in the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.chunk_count(), 2);
+    /// ```
+    pub fn chunk_count(&self) -> usize {
+        self.data_chunks.len()
+    }
+
+    /// Iterates over the contiguous data chunks of the file, in ascending address order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// let addresses: Vec<_> = srecord_file.chunks().map(|data_chunk| data_chunk.address).collect();
+    /// assert_eq!(addresses, [0x1000, 0x2000]);
+    /// ```
+    pub fn chunks(&self) -> impl ExactSizeIterator<Item = &DataChunk> + '_ {
+        self.data_chunks.iter()
+    }
+
+    /// Iterates over mutable references to the contiguous data chunks of the file, in ascending
+    /// address order.
+    ///
+    /// The data of the chunks can be modified freely, but the address and length of a chunk must
+    /// not be changed so that it overlaps or touches another chunk, or breaks the address order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// for data_chunk in srecord_file.chunks_mut() {
+    ///     data_chunk.data.reverse();
+    /// }
+    /// assert_eq!(srecord_file[0x1000..0x1004], [0x03, 0x02, 0x01, 0x00]);
+    /// assert_eq!(srecord_file[0x2000..0x2002], [0xBB, 0xAA]);
+    /// ```
+    pub fn chunks_mut(&mut self) -> impl ExactSizeIterator<Item = &mut DataChunk> + '_ {
+        self.data_chunks.iter_mut()
+    }
+
+    /// Consumes the file and returns its contiguous data chunks, in ascending address order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// let data_chunks = srecord_file.into_chunks();
+    /// assert_eq!(data_chunks.len(), 2);
+    /// assert_eq!(data_chunks[1].data, [0xAA, 0xBB]);
+    /// ```
+    pub fn into_chunks(self) -> Vec<DataChunk> {
+        self.data_chunks
+    }
+
+    /// Returns whether the file contains no data. Header data and start address are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// assert!(SRecordFile::from_str("S9031001EB").unwrap().is_empty());
+    /// assert!(!SRecordFile::from_str("S107100000010203E2").unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.data_chunks.is_empty()
+    }
+
+    /// Returns the [`start_address`](SRecordFile::start_address) together with the narrowest record
+    /// type that can hold it and whether it points to data, or `None` if there is no start address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{Address, RecordType, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031001EB").unwrap();
+    /// let start_address_info = srecord_file.start_address_info().unwrap();
+    /// assert_eq!(start_address_info.address, Address::new(0x1001));
+    /// assert_eq!(start_address_info.record_type, Some(RecordType::S9));
+    /// assert!(start_address_info.points_to_data);
+    ///
+    /// srecord_file.start_address = Some(0x012000);
+    /// let start_address_info = srecord_file.start_address_info().unwrap();
+    /// assert_eq!(start_address_info.record_type, Some(RecordType::S8));
+    /// assert!(!start_address_info.is_valid());
+    /// ```
+    pub fn start_address_info(&self) -> Option<StartAddressInfo> {
+        let address = Address::new(self.start_address?);
+        let record_type = [RecordType::S9, RecordType::S8, RecordType::S7]
+            .into_iter()
+            .find(|record_type| address.fits(record_type));
+        Some(StartAddressInfo {
+            address,
+            record_type,
+            points_to_data: self.contains_address(address.value()),
+        })
+    }
+
+    /// Checks that the [`start_address`](SRecordFile::start_address) fits in a start address
+    /// record and points to data, which catches typos in the start address before the file is
+    /// flashed. A file without start address is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{SRecordFile, StartAddressError};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031001EB").unwrap();
+    /// assert_eq!(srecord_file.validate_start_address(), Ok(()));
+    ///
+    /// srecord_file.start_address = Some(0x0110);
+    /// assert_eq!(
+    ///     srecord_file.validate_start_address(),
+    ///     Err(StartAddressError::NotInData(0x0110)),
+    /// );
+    /// ```
+    pub fn validate_start_address(&self) -> Result<(), StartAddressError> {
+        let Some(start_address_info) = self.start_address_info() else {
+            return Ok(());
+        };
+        let start_address = start_address_info.address.value();
+        if start_address_info.record_type.is_none() {
+            return Err(StartAddressError::TooLarge(start_address));
+        }
+        match start_address_info.points_to_data {
+            true => Ok(()),
+            false => Err(StartAddressError::NotInData(start_address)),
+        }
+    }
+
+    /// Restores the invariants of [`data_chunks`](SRecordFile::data_chunks) after they have been
+    /// edited directly: the data chunks are sorted by address, empty data chunks are removed and
+    /// adjacent data chunks are merged. Most other methods, e.g. [`get`](SRecordFile::get) and
+    /// [`iter_records`](SRecordFile::iter_records), expect a normalized file.
+    ///
+    /// Returns an error, without changing any data chunk, if any data chunk extends past address
+    /// `u64::MAX` or if any data chunks overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, NormalizeError, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1002,
+    ///     data: vec![0x02, 0x03],
+    /// });
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01],
+    /// });
+    /// srecord_file.normalize().unwrap();
+    /// assert_eq!(srecord_file.chunk_count(), 1);
+    /// assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0x03]);
+    ///
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1003,
+    ///     data: vec![0xAA, 0xBB],
+    /// });
+    /// assert_eq!(
+    ///     srecord_file.normalize(),
+    ///     Err(NormalizeError::OverlappingData(0x1003..0x1004)),
+    /// );
+    /// assert_eq!(srecord_file.chunk_count(), 2);
+    /// ```
+    pub fn normalize(&mut self) -> Result<(), NormalizeError> {
+        let mut ranges = Vec::with_capacity(self.data_chunks.len());
+        for data_chunk in self.data_chunks.iter() {
+            let end_address = data_chunk
+                .address
+                .checked_add(data_chunk.data.len() as u64)
+                .ok_or(NormalizeError::AddressOverflow(data_chunk.address))?;
+            if !data_chunk.data.is_empty() {
+                ranges.push(data_chunk.address..end_address);
+            }
+        }
+        ranges.sort_unstable_by_key(|range| range.start);
+        if let Some(ranges) = ranges
+            .windows(2)
+            .find(|ranges| ranges[1].start < ranges[0].end)
+        {
+            return Err(NormalizeError::OverlappingData(
+                ranges[1].start..min(ranges[0].end, ranges[1].end),
+            ));
+        }
+
+        self.data_chunks
+            .retain(|data_chunk| !data_chunk.data.is_empty());
+        self.data_chunks
+            .sort_unstable_by_key(|data_chunk| data_chunk.address);
+        // Cannot fail, as overlapping data chunks are rejected above
+        self.merge_data_chunks(None)
+            .expect("Data chunks do not overlap");
+        Ok(())
+    }
+
+    /// Checks the file for problems, and returns every problem found. An empty vector means that
+    /// no problems were found.
+    ///
+    /// This is mostly useful for files that are built or edited by accessing
+    /// [`data_chunks`](SRecordFile::data_chunks) directly, to find broken invariants before the
+    /// file is serialized. The following checks are done:
+    /// - Every data chunk ends at or before address `u64::MAX`, is non-empty, and the data chunks
+    ///   are sorted and do not overlap. The remaining checks are skipped if the data chunks are
+    ///   not sorted and non-overlapping.
+    /// - The file contains data.
+    /// - The file has header data and a start address, if required by `options`.
+    /// - [`validate_start_address`](SRecordFile::validate_start_address).
+    /// - The file can be serialized with [`ValidationOptions::serialization_options`], which
+    ///   checks the address width of the data records and the limit of the count record.
+    ///
+    /// Issues are returned in the order of the checks above.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{
+    ///     DataChunk, SRecordFile, Severity, StartAddressError, ValidationIssue, ValidationOptions,
+    /// };
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031001EB").unwrap();
+    /// let options = ValidationOptions::default();
+    /// assert!(srecord_file.validate(&options).is_empty());
+    ///
+    /// srecord_file.start_address = Some(0x0110);
+    /// let issues = srecord_file.validate(&options);
+    /// assert_eq!(
+    ///     issues,
+    ///     [ValidationIssue::StartAddress(StartAddressError::NotInData(0x0110))],
+    /// );
+    /// assert_eq!(issues[0].severity(), Severity::Warning);
+    ///
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1002,
+    ///     data: vec![0xAA, 0xBB, 0xCC],
+    /// });
+    /// assert_eq!(
+    ///     srecord_file.validate(&options),
+    ///     [ValidationIssue::OverlappingDataChunks(0x1002..0x1004)],
+    /// );
+    /// ```
+    pub fn validate(&self, options: &ValidationOptions) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let mut structure_valid = true;
+        let mut previous_range: Option<Range<u64>> = None;
+        for (index, data_chunk) in self.data_chunks.iter().enumerate() {
+            if data_chunk.data.is_empty() {
+                issues.push(ValidationIssue::EmptyDataChunk(index));
+            }
+            let Some(end_address) = data_chunk.address.checked_add(data_chunk.data.len() as u64)
+            else {
+                issues.push(ValidationIssue::DataChunkAddressOverflow(index));
+                structure_valid = false;
+                continue;
+            };
+            if let Some(previous_range) = previous_range {
+                if data_chunk.address < previous_range.start {
+                    issues.push(ValidationIssue::UnsortedDataChunks(index));
+                    structure_valid = false;
+                } else if data_chunk.address < previous_range.end {
+                    issues.push(ValidationIssue::OverlappingDataChunks(
+                        data_chunk.address..min(end_address, previous_range.end),
+                    ));
+                    structure_valid = false;
+                }
+            }
+            previous_range = Some(data_chunk.address..end_address);
+        }
+        if !structure_valid {
+            return issues;
+        }
+
+        if self
+            .data_chunks
+            .iter()
+            .all(|data_chunk| data_chunk.data.is_empty())
+        {
+            issues.push(ValidationIssue::NoData);
+        }
+        if options.require_header && self.header_data.is_none() {
+            issues.push(ValidationIssue::MissingHeader);
+        }
+        if options.require_start_address && self.start_address.is_none() {
+            issues.push(ValidationIssue::MissingStartAddress);
+        }
+        let start_address_error = self.validate_start_address().err();
+        if let Some(error) = start_address_error {
+            issues.push(ValidationIssue::StartAddress(error));
+        }
+        match self.iter_records_with_options(&options.serialization_options) {
+            // Already reported as an invalid start address
+            Err(SerializationError::StartAddressTooLarge(_))
+                if matches!(start_address_error, Some(StartAddressError::TooLarge(_))) => {}
+            Err(error) => issues.push(ValidationIssue::Serialization(error)),
+            Ok(_) => {}
+        }
+        issues
+    }
+
+    /// Returns the narrowest data record type (S1, S2 or S3) whose address width can hold the
+    /// highest data address and the start address in the file. This is the type of data records
+    /// emitted with [`DataRecordType::Auto`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{RecordType, SRecordFile};
+    ///
+    /// let srecord_file = SRecordFile::from_str("S3090000100000010203E0").unwrap();
+    /// assert_eq!(srecord_file.minimal_record_type(), RecordType::S1);
+    /// let srecord_file = SRecordFile::from_str("S30900FFFFFE00010203F4").unwrap();
+    /// assert_eq!(srecord_file.minimal_record_type(), RecordType::S3);
+    /// ```
+    pub fn minimal_record_type(&self) -> RecordType {
+        let max_address = max(
+            self.max_address().unwrap_or(0),
+            self.start_address.unwrap_or(0),
+        );
+        if max_address <= 0xFFFF {
+            RecordType::S1
+        } else if max_address <= 0xFF_FFFF {
+            RecordType::S2
+        } else {
+            RecordType::S3
+        }
+    }
+
+    /// Optionally return an index in [`data_chunks`](`SRecordFile::data_chunks`) where `address` is
+    /// found, or `None` if out of bounds.
+    ///
+    /// If `inclusive_end` is `true`, then data chunk's [`end_address`](`DataChunk::end_address`) is
+    /// counted as in bounds, otherwise it is counted as out of bounds. This helps in selecting a
+    /// data chunk index when trying to get data in the [`SRecordFile`] vs. allocating more address
+    /// ranges.
+    pub(crate) fn get_data_chunk_index(
+        &self,
+        address: u64,
+        inclusive_end: bool,
+    ) -> Result<usize, usize> {
+        // Compare without adding to the end address, which may be u64::MAX
+        let index = self
+            .data_chunks
+            .partition_point(|data_chunk| match inclusive_end {
+                true => data_chunk.end_address() < address,
+                false => data_chunk.end_address() <= address,
+            });
+        match self.data_chunks.get(index) {
+            Some(data_chunk) if data_chunk.address <= address => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    // TODO: Tests
+    /// Returns a reference to the element in [`SRecordFile::data_chunks`] which contains `address`,
+    /// or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x01, 0x02, 0x03, 0x04],
+    /// });
+    /// assert_eq!(srecord_file.get(0x1001), Some(&0x02u8));
+    /// assert!(srecord_file.get(0x1004).is_none());
+    /// ```
+    pub(crate) fn get_data_chunk(&self, address: u64) -> Option<&DataChunk> {
+        match self.get_data_chunk_index(address, false) {
+            Ok(data_chunk_index) => Some(&self.data_chunks[data_chunk_index]),
             Err(_) => None,
         }
     }
 
-    // TODO: Tests
-    /// Returns a mutable reference to the element in [`SRecordFile::data_chunks`] which contains
-    /// `address`, or `None` if out of bounds.
+    // TODO: Tests
+    /// Returns a mutable reference to the element in [`SRecordFile::data_chunks`] which contains
+    /// `address`, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x01, 0x02, 0x03, 0x04],
+    /// });
+    /// assert!(srecord_file.get_mut(0x1004).is_none());
+    /// assert_eq!(srecord_file.get_mut(0x1001), Some(&mut 0x02u8));
+    /// *srecord_file.get_mut(0x1001).unwrap() = 0xAA;
+    /// assert_eq!(srecord_file.get_mut(0x1001), Some(&mut 0xAAu8));
+    /// ```
+    pub(crate) fn get_data_chunk_mut(&mut self, address: u64) -> Option<&mut DataChunk> {
+        match self.get_data_chunk_index(address, false) {
+            Ok(data_chunk_index) => Some(&mut self.data_chunks[data_chunk_index]),
+            Err(_) => None,
+        }
+    }
+
+    /// Copies the data found in `src_range` of `src` into `self`, translated so that
+    /// `src_range.start` ends up at `dst_base`.
+    ///
+    /// Gaps in `src` inside `src_range` are not copied. How data that already exists in `self` at
+    /// the destination addresses is handled is decided by `policy`. The whole operation is
+    /// validated before anything is written, so `self` is left untouched if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{OverlapPolicy, SRecordFile};
+    ///
+    /// let bank_a = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// let mut image = SRecordFile::new();
+    /// image
+    ///     .transplant(&bank_a, 0x1000..0x1004, 0x8000, OverlapPolicy::Error)
+    ///     .unwrap();
+    /// assert_eq!(image[0x8000..0x8004], [0x00, 0x01, 0x02, 0x03]);
+    /// assert!(image
+    ///     .transplant(&bank_a, 0x1002..0x1004, 0x8001, OverlapPolicy::Error)
+    ///     .is_err());
+    /// ```
+    pub fn transplant(
+        &mut self,
+        src: &SRecordFile,
+        src_range: Range<u64>,
+        dst_base: u64,
+        policy: OverlapPolicy,
+    ) -> Result<(), TransplantError> {
+        let mut segments = Vec::new();
+        for (address, data) in src.iter_data_in_range(src_range.clone()) {
+            let dst_address = (address - src_range.start)
+                .checked_add(dst_base)
+                .ok_or(TransplantError::AddressOverflow)?;
+            dst_address
+                .checked_add(data.len() as u64)
+                .ok_or(TransplantError::AddressOverflow)?;
+            segments.push((dst_address, data));
+        }
+
+        if policy == OverlapPolicy::Error {
+            for (address, data) in segments.iter() {
+                let dst_range = *address..*address + data.len() as u64;
+                if let Some((overlap_address, overlap_data)) =
+                    self.iter_data_in_range(dst_range).next()
+                {
+                    return Err(TransplantError::OverlappingData(
+                        overlap_address..overlap_address + overlap_data.len() as u64,
+                    ));
+                }
+            }
+        }
+
+        for (address, data) in segments {
+            match policy {
+                OverlapPolicy::Error | OverlapPolicy::Overwrite => self.set_data(address, data),
+                OverlapPolicy::KeepExisting => {
+                    let gaps: Vec<Range<u64>> = self
+                        .gaps_within(address..address + data.len() as u64)
+                        .collect();
+                    for gap in gaps {
+                        let gap_data =
+                            &data[(gap.start - address) as usize..(gap.end - address) as usize];
+                        self.set_data(gap.start, gap_data);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the data of `other` on top of the data in `self`, e.g. a calibration or patch image
+    /// on top of the base firmware. Data in `other` always overwrites data at the same addresses
+    /// in `self`, so unlike [`merge`](SRecordFile::merge), this never fails.
+    ///
+    /// Only the data is overlaid: the header data and start address of `self` are kept. Use
+    /// [`merge`](SRecordFile::merge) with [`OverlapPolicy::Overwrite`] to also take them from
+    /// `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031000EC").unwrap();
+    /// let calibration = SRecordFile::from_str("S1071002AABBCCDDD8\nS9031002EA").unwrap();
+    /// srecord_file.overlay(&calibration);
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0xAA, 0xBB, 0xCC, 0xDD]);
+    /// assert_eq!(srecord_file.start_address, Some(0x1000));
+    /// ```
+    pub fn overlay(&mut self, other: &SRecordFile) {
+        for data_chunk in other.data_chunks.iter() {
+            self.set_data(data_chunk.address, &data_chunk.data);
+        }
+    }
+
+    /// Merges the data, header data and start address of `other` into `self`.
+    ///
+    /// `policy` decides what happens with data that exists at the same addresses in both files,
+    /// and with header data and start addresses that exist in both files but differ. Header data
+    /// and start address of `other` are always used if `self` has none. Everything is checked
+    /// before anything is merged, so `self` is left untouched if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{MergeError, OverlapPolicy, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// let other = SRecordFile::from_str("S1071002AABBCCDDD8\nS9031002EA").unwrap();
+    /// assert_eq!(
+    ///     srecord_file.merge(other, OverlapPolicy::Error),
+    ///     Err(MergeError::OverlappingData(0x1002..0x1004)),
+    /// );
+    ///
+    /// let other = SRecordFile::from_str("S1071002AABBCCDDD8\nS9031002EA").unwrap();
+    /// srecord_file.merge(other, OverlapPolicy::KeepExisting).unwrap();
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0xCC, 0xDD]);
+    /// assert_eq!(srecord_file.start_address, Some(0x1002));
+    /// ```
+    pub fn merge(&mut self, other: SRecordFile, policy: OverlapPolicy) -> Result<(), MergeError> {
+        if policy == OverlapPolicy::Error {
+            for data_chunk in other.data_chunks.iter() {
+                let range = data_chunk.address..data_chunk.end_address();
+                if let Some((address, data)) = self.iter_data_in_range(range).next() {
+                    return Err(MergeError::OverlappingData(
+                        address..address + data.len() as u64,
+                    ));
+                }
+            }
+            if let (Some(existing), Some(merged)) = (&self.header_data, &other.header_data) {
+                if existing != merged {
+                    return Err(MergeError::ConflictingHeaders);
+                }
+            }
+            if let (Some(existing), Some(merged)) = (self.start_address, other.start_address) {
+                if existing != merged {
+                    return Err(MergeError::ConflictingStartAddresses(existing, merged));
+                }
+            }
+        }
+
+        (self.header_data, self.start_address) = match policy {
+            OverlapPolicy::Error | OverlapPolicy::KeepExisting => (
+                self.header_data.take().or(other.header_data),
+                self.start_address.or(other.start_address),
+            ),
+            OverlapPolicy::Overwrite => (
+                other.header_data.or(self.header_data.take()),
+                other.start_address.or(self.start_address),
+            ),
+        };
+        let chunk_lists = vec![mem::take(&mut self.data_chunks), other.data_chunks];
+        // Overlaps have already been checked
+        self.data_chunks = merge_chunk_lists(chunk_lists, policy).unwrap();
+        Ok(())
+    }
+
+    /// Appends `bytes` after the last data in the file, at the first address that is a multiple of
+    /// `align`, and returns the address where the trailer was placed.
+    ///
+    /// `max_end` is the exclusive maximum end address of the trailer, e.g. the end of the flash
+    /// region. Nothing is written if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{AppendTrailerError, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// let crc = [0xDE, 0xAD, 0xBE, 0xEF];
+    /// assert_eq!(srecord_file.append_trailer(&crc, 0x10, 0x2000), Ok(0x1010));
+    /// assert_eq!(srecord_file[0x1010..0x1014], crc);
+    /// assert_eq!(
+    ///     srecord_file.append_trailer(&crc, 0x10, 0x1023),
+    ///     Err(AppendTrailerError::ExceedsMaxEndAddress(0x1024)),
+    /// );
+    /// ```
+    pub fn append_trailer(
+        &mut self,
+        bytes: &[u8],
+        align: u64,
+        max_end: u64,
+    ) -> Result<u64, AppendTrailerError> {
+        if align == 0 {
+            return Err(AppendTrailerError::InvalidAlignment);
+        }
+        let data_end_address = match self.data_chunks.last() {
+            Some(data_chunk) => data_chunk.end_address(),
+            None => 0,
+        };
+        let address = data_end_address
+            .checked_next_multiple_of(align)
+            .ok_or(AppendTrailerError::AddressOverflow)?;
+        let end_address = address
+            .checked_add(bytes.len() as u64)
+            .ok_or(AppendTrailerError::AddressOverflow)?;
+        if end_address > max_end {
+            return Err(AppendTrailerError::ExceedsMaxEndAddress(end_address));
+        }
+        self.set_data(address, bytes);
+        Ok(address)
+    }
+
+    /// Pads every region of contiguous data with `fill`, so that it starts and ends on multiples of
+    /// `sector_size`, e.g. the erase value of the flash sectors or pages the data is written to.
+    ///
+    /// Existing data is never overwritten, and regions whose padding makes them overlap or touch
+    /// are merged. Nothing is written if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100200010203E0\nS1051008AABB7D").unwrap();
+    /// srecord_file.align_regions_to(8, 0xFF).unwrap();
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    /// assert_eq!(
+    ///     srecord_file[0x1000..0x1010],
+    ///     [
+    ///         0xFF, 0xFF, 0x00, 0x01, 0x02, 0x03, 0xFF, 0xFF,
+    ///         0xAA, 0xBB, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    ///     ],
+    /// );
+    /// ```
+    pub fn align_regions_to(
+        &mut self,
+        sector_size: u64,
+        fill: u8,
+    ) -> Result<(), AlignRegionsError> {
+        if sector_size == 0 {
+            return Err(AlignRegionsError::InvalidSectorSize);
+        }
+        let mut aligned_ranges = Vec::with_capacity(self.data_chunks.len());
+        for data_chunk in self.data_chunks.iter() {
+            let start_address = data_chunk.address - data_chunk.address % sector_size;
+            let end_address = data_chunk
+                .end_address()
+                .checked_next_multiple_of(sector_size)
+                .ok_or(AlignRegionsError::AddressOverflow)?;
+            aligned_ranges.push(start_address..end_address);
+        }
+
+        let mut data_chunks = Vec::new();
+        for range in normalized(aligned_ranges) {
+            let mut aligned_chunk = DataChunk {
+                address: range.start,
+                data: vec![fill; (range.end - range.start) as usize],
+            };
+            for (address, data) in self.iter_data_in_range(range.clone()) {
+                let offset = (address - range.start) as usize;
+                aligned_chunk.data[offset..offset + data.len()].copy_from_slice(data);
+            }
+            data_chunks.push(aligned_chunk);
+        }
+        self.data_chunks = data_chunks;
+        Ok(())
+    }
+
+    /// Extends the start of every data chunk down and its end up to multiples of `alignment`, e.g.
+    /// the flash page size, filling the new bytes with `fill`. Chunks that become adjacent or
+    /// overlap are merged.
+    ///
+    /// Same as [`align_regions_to`](SRecordFile::align_regions_to) with `alignment` as the sector
+    /// size, which should be used instead.
+    #[deprecated(since = "0.1.0", note = "use `align_regions_to` instead")]
+    pub fn align_chunks(&mut self, alignment: u64, fill: u8) -> Result<(), AlignRegionsError> {
+        self.align_regions_to(alignment, fill)
+    }
+
+    /// Removes all data outside of `range`. Data chunks that are only partially inside `range` are
+    /// cut to fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS1051008AABB7D").unwrap();
+    /// srecord_file.truncate_to(0x1002..0x1009);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1002..0x1004], [0x02, 0x03]);
+    /// assert_eq!(srecord_file[0x1008], 0xAA);
+    /// assert!(srecord_file.get(0x1009).is_none());
+    /// ```
+    pub fn truncate_to(&mut self, range: Range<u64>) {
+        self.crop(slice::from_ref(&range));
+    }
+
+    /// Moves all data, and the start address if present, by `delta` bytes, e.g. to rebase an image
+    /// from its link address to its flash address. Nothing is moved if an error is returned.
+    ///
+    /// Address ranges of the [`record_layout`](SRecordFile::record_layout) are moved too. Ranges
+    /// that do not contain data are not checked, and are dropped if they would not fit in a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{OffsetError, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031000EC").unwrap();
+    /// srecord_file.offset(-0x0800).unwrap();
+    /// assert_eq!(srecord_file[0x0800..0x0804], [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(srecord_file.start_address, Some(0x0800));
+    /// assert_eq!(srecord_file.offset(-0x1000), Err(OffsetError::AddressUnderflow));
+    /// ```
+    pub fn offset(&mut self, delta: i64) -> Result<(), OffsetError> {
+        let out_of_range_error = match delta < 0 {
+            true => OffsetError::AddressUnderflow,
+            false => OffsetError::AddressOverflow,
+        };
+        let address_range = self.address_range();
+        let first_address = address_range.as_ref().map(|range| range.start);
+        let end_address = address_range.map(|range| range.end);
+        for address in [first_address, end_address, self.start_address]
+            .into_iter()
+            .flatten()
+        {
+            address
+                .checked_add_signed(delta)
+                .ok_or(out_of_range_error)?;
+        }
+
+        for data_chunk in self.data_chunks.iter_mut() {
+            data_chunk.address = data_chunk.address.wrapping_add_signed(delta);
+        }
+        self.start_address = self
+            .start_address
+            .map(|address| address.wrapping_add_signed(delta));
+        if let Some(record_layout) = self.record_layout.as_mut() {
+            record_layout.data_records = record_layout
+                .data_records
+                .drain(..)
+                .filter_map(|range| {
+                    Some(
+                        range.start.checked_add_signed(delta)?
+                            ..range.end.checked_add_signed(delta)?,
+                    )
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Removes all data outside of `ranges`. Data chunks that are only partially inside `ranges`
+    /// are cut to fit. The ranges may overlap and be in any order. The header data and start
+    /// address are kept, and the address ranges of the
+    /// [`record_layout`](SRecordFile::record_layout) are cut to `ranges` like the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S10B10000001020304050607C8").unwrap();
+    /// srecord_file.crop(&[0x1006..0x1010, 0x0F00..0x1002]);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0x00, 0x01]);
+    /// assert_eq!(srecord_file[0x1006..0x1008], [0x06, 0x07]);
+    /// ```
+    pub fn crop(&mut self, ranges: &[Range<u64>]) {
+        let ranges = normalized(ranges.to_vec());
+        self.data_chunks = self.cropped_data_chunks(&ranges);
+        if let Some(record_layout) = self.record_layout.as_mut() {
+            record_layout.data_records =
+                cropped_layout_records(&record_layout.data_records, &ranges);
+        }
+    }
+
+    /// Returns a copy of the file containing only the data inside `ranges`, see
+    /// [`crop`](SRecordFile::crop).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S10B10000001020304050607C8\nS9031000EC").unwrap();
+    /// let bootloader = srecord_file.cropped(&[0x1000..0x1004]);
+    /// assert_eq!(bootloader.data_chunks[0].data, [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(bootloader.start_address, Some(0x1000));
+    /// assert_eq!(srecord_file.data_chunks[0].data.len(), 8);
+    /// ```
+    pub fn cropped(&self, ranges: &[Range<u64>]) -> SRecordFile {
+        let ranges = normalized(ranges.to_vec());
+        SRecordFile {
+            header_data: self.header_data.clone(),
+            data_chunks: self.cropped_data_chunks(&ranges),
+            start_address: self.start_address,
+            record_layout: self.record_layout.as_ref().map(|record_layout| {
+                let mut record_layout = record_layout.clone();
+                record_layout.data_records =
+                    cropped_layout_records(&record_layout.data_records, &ranges);
+                record_layout
+            }),
+            chunk_capacity: self.chunk_capacity,
+        }
+    }
+
+    /// Returns copies of the data inside the normalized list `ranges`, as sorted, merged data
+    /// chunks.
+    fn cropped_data_chunks(&self, ranges: &[Range<u64>]) -> Vec<DataChunk> {
+        let mut data_chunks = Vec::new();
+        for range in ranges.iter().cloned() {
+            for (address, data) in self.iter_data_in_range(range) {
+                data_chunks.push(DataChunk {
+                    address,
+                    data: data.to_vec(),
+                });
+            }
+        }
+        data_chunks
+    }
+
+    /// Iterates over every byte of data together with its address, in ascending address order.
+    /// Gaps between data chunks are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+    /// let bytes: Vec<_> = srecord_file.iter_bytes().collect();
+    /// assert_eq!(bytes, [(0x1000, 0xAA), (0x1001, 0xBB), (0x1004, 0xCC), (0x1005, 0xDD)]);
+    /// ```
+    pub fn iter_bytes(&self) -> impl Iterator<Item = (u64, u8)> + '_ {
+        self.data_chunks.iter().flat_map(DataChunk::iter_bytes)
+    }
+
+    /// Iterates over mutable references to every byte of data together with its address, in
+    /// ascending address order. Gaps between data chunks are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+    /// for (address, byte) in srecord_file.iter_bytes_mut() {
+    ///     *byte ^= address as u8;
+    /// }
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0xAA, 0xBA]);
+    /// assert_eq!(srecord_file[0x1004..0x1006], [0xC8, 0xD8]);
+    /// ```
+    pub fn iter_bytes_mut(&mut self) -> impl Iterator<Item = (u64, &mut u8)> + '_ {
+        self.data_chunks.iter_mut().flat_map(|data_chunk| {
+            let address = data_chunk.address;
+            data_chunk
+                .data
+                .iter_mut()
+                .enumerate()
+                .map(move |(index, byte)| (address + index as u64, byte))
+        })
+    }
+
+    /// Returns the lowest address where `pattern` is found in the data, or `None` if it is not
+    /// found. Matches can span the inside of a data chunk, but not the gaps between data chunks.
+    /// An empty pattern is never found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1071000AABBAABB1E\nS1051006AABB7F").unwrap();
+    /// assert_eq!(srecord_file.find(&[0xAA, 0xBB]), Some(0x1000));
+    /// assert_eq!(srecord_file.rfind(&[0xAA, 0xBB]), Some(0x1006));
+    /// assert_eq!(srecord_file.find(&[0xBB, 0xAA, 0xBB]), Some(0x1001));
+    /// assert_eq!(srecord_file.find(&[0xBB, 0xAA, 0xBB, 0xAA]), None);
+    /// ```
+    pub fn find(&self, pattern: &[u8]) -> Option<u64> {
+        self.find_matches(pattern, None).next()
+    }
+
+    /// Returns the highest address where `pattern` is found in the data, or `None` if it is not
+    /// found, as by [`find`](SRecordFile::find).
+    pub fn rfind(&self, pattern: &[u8]) -> Option<u64> {
+        self.find_matches(pattern, None).next_back()
+    }
+
+    /// Iterates over every address where `pattern` is found in the data, in ascending address
+    /// order, as by [`find`](SRecordFile::find). Overlapping matches are all included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1071000AAAAAABB2F").unwrap();
+    /// let matches: Vec<u64> = srecord_file.find_all(&[0xAA, 0xAA]).collect();
+    /// assert_eq!(matches, [0x1000, 0x1001]);
+    /// ```
+    pub fn find_all<'a>(&'a self, pattern: &'a [u8]) -> impl DoubleEndedIterator<Item = u64> + 'a {
+        self.find_matches(pattern, None)
+    }
+
+    /// Returns the lowest address where `pattern` is found in the data, comparing only the bits
+    /// that are set in `mask`, or `None` if it is not found. A mask byte of `0x00` matches any
+    /// byte, and a mask byte of `0xFF` matches only the byte in `pattern`. Otherwise the same as
+    /// [`find`](SRecordFile::find).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1071000560102038C\nS10720005602030479").unwrap();
+    /// // Version header with any minor version
+    /// let pattern = [0x56, 0x02, 0x00];
+    /// let mask = [0xFF, 0xFF, 0x00];
+    /// assert_eq!(srecord_file.find_masked(&pattern, &mask), Some(0x2000));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`find_masked`](SRecordFile::find_masked) will [`panic!`] if `mask` and `pattern` have
+    /// different lengths.
+    pub fn find_masked(&self, pattern: &[u8], mask: &[u8]) -> Option<u64> {
+        self.find_matches(pattern, Some(mask)).next()
+    }
+
+    /// Returns the highest address where `pattern` is found in the data, comparing only the bits
+    /// that are set in `mask`, as by [`find_masked`](SRecordFile::find_masked).
+    ///
+    /// # Panics
+    ///
+    /// [`rfind_masked`](SRecordFile::rfind_masked) will [`panic!`] if `mask` and `pattern` have
+    /// different lengths.
+    pub fn rfind_masked(&self, pattern: &[u8], mask: &[u8]) -> Option<u64> {
+        self.find_matches(pattern, Some(mask)).next_back()
+    }
+
+    /// Iterates over every address where `pattern` is found in the data, comparing only the bits
+    /// that are set in `mask`, as by [`find_masked`](SRecordFile::find_masked) and
+    /// [`find_all`](SRecordFile::find_all).
+    ///
+    /// # Panics
+    ///
+    /// [`find_all_masked`](SRecordFile::find_all_masked) will [`panic!`] if `mask` and `pattern`
+    /// have different lengths.
+    pub fn find_all_masked<'a>(
+        &'a self,
+        pattern: &'a [u8],
+        mask: &'a [u8],
+    ) -> impl DoubleEndedIterator<Item = u64> + 'a {
+        self.find_matches(pattern, Some(mask))
+    }
+
+    /// Iterates over the address ranges between the data chunks that do not contain any data, in
+    /// ascending address order. The addresses below the first and above the last data chunk are not
+    /// included, use [`gaps_within`](SRecordFile::gaps_within) to check a specific address range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file =
+    ///     SRecordFile::from_str("S1051000AABB85\nS1051004AABB81\nS1051010AABB75").unwrap();
+    /// let gaps: Vec<_> = srecord_file.gaps().collect();
+    /// assert_eq!(gaps, [0x1002..0x1004, 0x1006..0x1010]);
+    /// ```
+    pub fn gaps(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.data_chunks
+            .windows(2)
+            .map(|data_chunks| data_chunks[0].end_address()..data_chunks[1].address)
+            .filter(|gap| gap.start < gap.end)
+    }
+
+    /// Iterates over the address ranges inside `range` that do not contain any data, in ascending
+    /// address order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004AABB81").unwrap();
+    /// let gaps: Vec<_> = srecord_file.gaps_within(0x0F00..0x1100).collect();
+    /// assert_eq!(gaps, [0x0F00..0x1000, 0x1002..0x1004, 0x1006..0x1100]);
+    /// assert!(srecord_file.gaps_within(0x1000..0x1002).next().is_none());
+    /// ```
+    pub fn gaps_within(&self, range: Range<u64>) -> impl Iterator<Item = Range<u64>> + '_ {
+        let mut address = range.start;
+        let end_address = range.end;
+        self.iter_data_in_range(range)
+            .map(|(data_address, data)| (data_address, data_address + data.len() as u64))
+            .chain(iter::once((end_address, end_address)))
+            .filter_map(move |(data_address, data_end_address)| {
+                let gap = address..data_address;
+                address = data_end_address;
+                match gap.start < gap.end {
+                    true => Some(gap),
+                    false => None,
+                }
+            })
+    }
+
+    /// Returns a contiguous image of all data, from the [`min_address`](SRecordFile::min_address)
+    /// to the [`max_address`](SRecordFile::max_address), with gaps filled with `fill`. Returns an
+    /// empty image if the file contains no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+    /// assert_eq!(
+    ///     srecord_file.to_binary(0xFF),
+    ///     [0xAA, 0xBB, 0xFF, 0xFF, 0xCC, 0xDD],
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image does not fit in memory, see
+    /// [`to_binary_range`](SRecordFile::to_binary_range).
+    pub fn to_binary(&self, fill: u8) -> Vec<u8> {
+        match self.address_range() {
+            Some(address_range) => self.to_binary_range(address_range, fill),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns a contiguous image of the addresses in `range`, with addresses without data filled
+    /// with `fill`. Data outside `range` is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+    /// assert_eq!(
+    ///     srecord_file.to_binary_range(0x0FFF..0x1005, 0x00),
+    ///     [0x00, 0xAA, 0xBB, 0x00, 0x00, 0xCC],
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`to_binary_range`](SRecordFile::to_binary_range) will [`panic!`] if the length of `range`
+    /// does not fit in a `usize`, or if the image cannot be allocated.
+    pub fn to_binary_range(&self, range: Range<u64>, fill: u8) -> Vec<u8> {
+        if range.start >= range.end {
+            return Vec::new();
+        }
+        let length = match usize::try_from(range.end - range.start) {
+            Ok(length) => length,
+            Err(_) => panic!(
+                "Address range {:#08X}:{:#08X} does not fit in memory",
+                range.start, range.end
+            ),
+        };
+        let mut image = vec![fill; length];
+        let start_address = range.start;
+        for (address, data) in self.iter_data_in_range(range) {
+            let start_index = (address - start_address) as usize;
+            image[start_index..start_index + data.len()].copy_from_slice(data);
+        }
+        image
+    }
+
+    /// Splits the file into the data below `address` and the data at or above `address`, splitting
+    /// a data chunk that straddles `address`. Both files get a copy of the header data, and the
+    /// start address goes to the file whose address range contains it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S10B10000001020304050607C8\nS9031000EC").unwrap();
+    /// let (bank_a, bank_b) = srecord_file.split_at(0x1004);
+    /// assert_eq!(bank_a.data_chunks[0].data, [0x00, 0x01, 0x02, 0x03]);
+    /// assert_eq!(bank_a.start_address, Some(0x1000));
+    /// assert_eq!(bank_b.data_chunks[0].address, 0x1004);
+    /// assert_eq!(bank_b.data_chunks[0].data, [0x04, 0x05, 0x06, 0x07]);
+    /// assert_eq!(bank_b.start_address, None);
+    /// ```
+    pub fn split_at(&self, address: u64) -> (SRecordFile, SRecordFile) {
+        let mut below = self.cropped(slice::from_ref(&(0..address)));
+        let mut above = self.cropped(slice::from_ref(&(address..u64::MAX)));
+        match self.start_address {
+            Some(start_address) if start_address < address => above.start_address = None,
+            _ => below.start_address = None,
+        }
+        (below, above)
+    }
+
+    /// Splits the file into files containing at most `max_bytes` bytes of data each, in address
+    /// order. Data chunks are split where needed, so every file except the last one contains
+    /// exactly `max_bytes` bytes.
+    ///
+    /// Every file gets a copy of the header data. The start address is only copied to the last
+    /// file, or to every file if `start_address_in_every_file` is set. A file without data is
+    /// returned as a single file without data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S10B10000001020304050607C8\nS9031000EC").unwrap();
+    /// let files = srecord_file.split_by_size(3, false);
+    /// assert_eq!(files.len(), 3);
+    /// assert_eq!(files[1].data_chunks[0].address, 0x1003);
+    /// assert_eq!(files[1].data_chunks[0].data, [0x03, 0x04, 0x05]);
+    /// assert_eq!(files[1].start_address, None);
+    /// assert_eq!(files[2].data_chunks[0].data, [0x06, 0x07]);
+    /// assert_eq!(files[2].start_address, Some(0x1000));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`split_by_size`](SRecordFile::split_by_size) will [`panic!`] if `max_bytes` is zero.
+    pub fn split_by_size(
+        &self,
+        max_bytes: u64,
+        start_address_in_every_file: bool,
+    ) -> Vec<SRecordFile> {
+        if max_bytes == 0 {
+            panic!("Files must contain at least one byte of data");
+        }
+        let mut data_chunk_lists: Vec<Vec<DataChunk>> = Vec::new();
+        // Number of bytes that still fit in the last file
+        let mut remaining_bytes = 0;
+        for data_chunk in self.data_chunks.iter() {
+            let end_address = data_chunk.end_address();
+            let mut address = data_chunk.address;
+            while address < end_address {
+                if remaining_bytes == 0 {
+                    data_chunk_lists.push(Vec::new());
+                    remaining_bytes = max_bytes;
+                }
+                let split_address = min(end_address, address.saturating_add(remaining_bytes));
+                let start_index = (address - data_chunk.address) as usize;
+                let end_index = (split_address - data_chunk.address) as usize;
+                data_chunk_lists.last_mut().unwrap().push(DataChunk {
+                    address,
+                    data: data_chunk.data[start_index..end_index].to_vec(),
+                });
+                remaining_bytes -= split_address - address;
+                address = split_address;
+            }
+        }
+        self.split_files(data_chunk_lists, start_address_in_every_file)
+    }
+
+    /// Splits the file into one file per data chunk, in address order. The header data and start
+    /// address are copied as by [`split_by_size`](SRecordFile::split_by_size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// let files = srecord_file.split_by_chunk(true);
+    /// assert_eq!(files.len(), 2);
+    /// assert_eq!(files[0].data_chunks[0].address, 0x1000);
+    /// assert_eq!(files[1].data_chunks[0].address, 0x2000);
+    /// ```
+    pub fn split_by_chunk(&self, start_address_in_every_file: bool) -> Vec<SRecordFile> {
+        let data_chunk_lists = self
+            .data_chunks
+            .iter()
+            .map(|data_chunk| {
+                vec![DataChunk {
+                    address: data_chunk.address,
+                    data: data_chunk.data.clone(),
+                }]
+            })
+            .collect();
+        self.split_files(data_chunk_lists, start_address_in_every_file)
+    }
+
+    /// Creates one file per list of data chunks for the split functions, with a copy of the header
+    /// data and the start address in the last or every file.
+    fn split_files(
+        &self,
+        mut data_chunk_lists: Vec<Vec<DataChunk>>,
+        start_address_in_every_file: bool,
+    ) -> Vec<SRecordFile> {
+        if data_chunk_lists.is_empty() {
+            data_chunk_lists.push(Vec::new());
+        }
+        let num_files = data_chunk_lists.len();
+        data_chunk_lists
+            .into_iter()
+            .enumerate()
+            .map(|(index, data_chunks)| SRecordFile {
+                header_data: self.header_data.clone(),
+                data_chunks,
+                start_address: match start_address_in_every_file || index == num_files - 1 {
+                    true => self.start_address,
+                    false => None,
+                },
+                record_layout: None,
+                chunk_capacity: self.chunk_capacity,
+            })
+            .collect()
+    }
+
+    /// Splits the file into 4 GiB banks, for images with data above the 32-bit address range of S3
+    /// records. This is a nonstandard extension: each bank is returned with its base address and
+    /// the data of the bank relative to the base address, so that every bank can be serialized on
+    /// its own. How the base addresses are stored alongside the banks is up to the user, or the
+    /// banks can be kept in a single file with [`SerializationOptions::bank_records`].
+    ///
+    /// Banks are returned in address order, and only banks containing data or the start address
+    /// are returned. Every bank gets a copy of the header data, and the bank containing the start
+    /// address gets the start address relative to its base address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SerializationError, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01],
+    /// });
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x2_0000_1000,
+    ///     data: vec![0xAA, 0xBB],
+    /// });
+    /// assert_eq!(
+    ///     srecord_file.iter_records(16).err(),
+    ///     Some(SerializationError::AddressTooLargeForFormat(0x2_0000_1001)),
+    /// );
+    ///
+    /// let banks = srecord_file.split_banks();
+    /// assert_eq!(banks.len(), 2);
+    /// assert_eq!(banks[0].0, 0x0);
+    /// assert_eq!(banks[0].1[0x1000..0x1002], [0x00, 0x01]);
+    /// assert_eq!(banks[1].0, 0x2_0000_0000);
+    /// assert_eq!(banks[1].1[0x1000..0x1002], [0xAA, 0xBB]);
+    /// assert!(banks[1].1.iter_records(16).is_ok());
+    /// ```
+    pub fn split_banks(&self) -> Vec<(u64, SRecordFile)> {
+        const BANK_SIZE: u64 = 1 << 32;
+        let new_bank = || SRecordFile {
+            header_data: self.header_data.clone(),
+            ..SRecordFile::new()
+        };
+        let mut banks: Vec<(u64, SRecordFile)> = Vec::new();
+        for data_chunk in self.data_chunks.iter() {
+            let end_address = data_chunk.end_address();
+            let mut address = data_chunk.address;
+            while address < end_address {
+                let bank_address = address & !(BANK_SIZE - 1);
+                let bank_end_address = min(end_address, bank_address.saturating_add(BANK_SIZE));
+                if banks.last().map(|(address, _)| *address) != Some(bank_address) {
+                    banks.push((bank_address, new_bank()));
+                }
+                let start_index = (address - data_chunk.address) as usize;
+                let end_index = (bank_end_address - data_chunk.address) as usize;
+                banks.last_mut().unwrap().1.data_chunks.push(DataChunk {
+                    address: address - bank_address,
+                    data: data_chunk.data[start_index..end_index].to_vec(),
+                });
+                address = bank_end_address;
+            }
+        }
+        if let Some(start_address) = self.start_address {
+            let bank_address = start_address & !(BANK_SIZE - 1);
+            let index = match banks.binary_search_by_key(&bank_address, |(address, _)| *address) {
+                Ok(index) => index,
+                Err(index) => {
+                    banks.insert(index, (bank_address, new_bank()));
+                    index
+                }
+            };
+            banks[index].1.start_address = Some(start_address - bank_address);
+        }
+        banks
+    }
+
+    /// Stages edits with `edit`, and applies all of them if `edit` succeeds and every edit is
+    /// valid. Otherwise no edit is applied, and the file is left unchanged.
+    ///
+    /// Every edit is checked against the data of the file as modified by the edits before it, see
+    /// [`Transaction::protect`], [`Transaction::memory_layout`] and [`Transaction::insert`]. Edits
+    /// with an empty address range do nothing, as by [`erase`](SRecordFile::erase).
+    ///
+    /// Returns [`TransactionError::Aborted`] with the error returned by `edit`, or the first
+    /// invalid edit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{SRecordFile, TransactionError};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file
+    ///     .transaction(|tx| {
+    ///         tx.set(0x1004, &[0xAA, 0xBB]).erase(0x1000..0x1002);
+    ///         Ok::<(), ()>(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(srecord_file[0x1002..0x1006], [0x02, 0x03, 0xAA, 0xBB]);
+    ///
+    /// let result = srecord_file.transaction(|tx| {
+    ///     tx.set(0x1002, &[0xFF]);
+    ///     tx.set(u64::MAX, &[0xFF]);
+    ///     Ok::<(), ()>(())
+    /// });
+    /// assert_eq!(result, Err(TransactionError::AddressOverflow(1)));
+    /// assert_eq!(srecord_file[0x1002], 0x02);
+    ///
+    /// let result = srecord_file.transaction(|tx| {
+    ///     tx.protect(0x1000..0x1004).set(0x1003, &[0xFF, 0xFF]);
+    ///     Ok::<(), ()>(())
+    /// });
+    /// assert_eq!(result, Err(TransactionError::Protected(0)));
+    /// assert_eq!(srecord_file[0x1003], 0x03);
+    /// ```
+    pub fn transaction<F, E>(&mut self, edit: F) -> Result<(), TransactionError<E>>
+    where
+        F: FnOnce(&mut Transaction) -> Result<(), E>,
+    {
+        let mut transaction = Transaction::default();
+        edit(&mut transaction).map_err(TransactionError::Aborted)?;
+        transaction.validate(self)?;
+        transaction.apply(self);
+        Ok(())
+    }
+
+    /// Returns a new [`SRecordFile`] containing the data in the address range of the symbol called
+    /// `name` in `symbols`.
+    ///
+    /// The start of the range is rounded down and the end rounded up to multiples of `align`, so
+    /// that e.g. a whole flash sector around the symbol is extracted. An `align` of 1 extracts
+    /// exactly the symbol. Gaps in the range are not filled, and the header data and start address
+    /// are not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{SRecordFile, Symbol};
+    ///
+    /// let srecord_file = SRecordFile::from_str("S10B10000001020304050607C8").unwrap();
+    /// let symbols = [Symbol {
+    ///     name: String::from("font"),
+    ///     address: 0x1003,
+    ///     size: 2,
+    /// }];
+    /// let font = srecord_file.extract_symbol(&symbols, "font", 1).unwrap();
+    /// assert_eq!(font.data_chunks[0].address, 0x1003);
+    /// assert_eq!(font.data_chunks[0].data, [0x03, 0x04]);
+    ///
+    /// let font = srecord_file.extract_symbol(&symbols, "font", 4).unwrap();
+    /// assert_eq!(font.data_chunks[0].address, 0x1000);
+    /// assert_eq!(font.data_chunks[0].data, [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]);
+    /// ```
+    pub fn extract_symbol(
+        &self,
+        symbols: &[Symbol],
+        name: &str,
+        align: u64,
+    ) -> Result<SRecordFile, ExtractSymbolError> {
+        if align == 0 {
+            return Err(ExtractSymbolError::InvalidAlignment);
+        }
+        let symbol = symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .ok_or(ExtractSymbolError::SymbolNotFound)?;
+        let range = symbol.range().ok_or(ExtractSymbolError::AddressOverflow)?;
+        let start_address = range.start - range.start % align;
+        let end_address = range
+            .end
+            .checked_next_multiple_of(align)
+            .ok_or(ExtractSymbolError::AddressOverflow)?;
+
+        let mut srecord_file = SRecordFile::new();
+        for (address, data) in self.iter_data_in_range(start_address..end_address) {
+            srecord_file.data_chunks.push(DataChunk {
+                address,
+                data: data.to_vec(),
+            });
+        }
+        Ok(srecord_file)
+    }
+
+    /// Writes `data` starting at `address`, overwriting existing data and allocating new data where
+    /// needed. Data chunks that become adjacent or overlapping are merged.
+    ///
+    /// Unlike indexing, which panics if the addresses do not already contain data, this can be used
+    /// to add data anywhere in the file.
+    ///
+    /// The records of the [`record_layout`](SRecordFile::record_layout) stay valid, as no data is
+    /// removed: overwritten data is serialized in the records it was parsed from, and new data
+    /// outside of them after them, see [`SerializationOptions::preserve_layout`]. The same holds
+    /// for [`fill`](SRecordFile::fill) and the `write_*` methods, which write through this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.set_data(0x1006, &[0xAA, 0xBB]);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// srecord_file.set_data(0x1003, &[0xFF, 0xFF, 0xFF]);
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(
+    ///     srecord_file[0x1000..0x1008],
+    ///     [0x00, 0x01, 0x02, 0xFF, 0xFF, 0xFF, 0xAA, 0xBB],
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`set_data`](SRecordFile::set_data) will [`panic!`] if the end address of the data,
+    /// `address + data.len()`, does not fit in a `u64`.
+    pub fn set_data<A: IntoAddress>(&mut self, address: A, data: &[u8]) {
+        let address = address.into_address().value();
+        if data.is_empty() {
+            return;
+        }
+        let end_address = match address.checked_add(data.len() as u64) {
+            Some(end_address) => end_address,
+            None => panic!("End address of data at {address:#08X} does not fit in a u64"),
+        };
+        // Chunks in first_index..last_index overlap or are adjacent to the written data
+        let first_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() < address);
+        let last_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.address <= end_address);
+        if first_index == last_index {
+            let mut data_chunk =
+                DataChunk::with_capacity(address, max(data.len(), self.chunk_capacity));
+            data_chunk.data.extend_from_slice(data);
+            self.data_chunks.insert(first_index, data_chunk);
+            return;
+        }
+
+        let start_address = min(address, self.data_chunks[first_index].address);
+        let end_address = max(end_address, self.data_chunks[last_index - 1].end_address());
+        let mut merged_chunks = self.data_chunks.drain(first_index..last_index);
+        let mut data_chunk = merged_chunks.next().unwrap();
+        if data_chunk.address != start_address {
+            let mut merged_data = vec![0u8; (data_chunk.address - start_address) as usize];
+            merged_data.append(&mut data_chunk.data);
+            data_chunk = DataChunk {
+                address: start_address,
+                data: merged_data,
+            };
+        }
+        data_chunk
+            .data
+            .resize((end_address - start_address) as usize, 0);
+        for merged_chunk in merged_chunks {
+            let offset = (merged_chunk.address - start_address) as usize;
+            data_chunk.data[offset..offset + merged_chunk.data.len()]
+                .copy_from_slice(&merged_chunk.data);
+        }
+        let offset = (address - start_address) as usize;
+        data_chunk.data[offset..offset + data.len()].copy_from_slice(data);
+        self.data_chunks.insert(first_index, data_chunk);
+    }
+
+    /// Writes `data_chunks` over the data of the file, overwriting existing data. The data chunks
+    /// may be in any order and overlap each other, in which case the data of the later data chunk
+    /// is kept. All data is sorted and merged in one pass, instead of writing every data chunk with
+    /// [`set_data`](SRecordFile::set_data).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end address of any data chunk does not fit in a `u64`.
+    fn overwrite_data_chunks(&mut self, data_chunks: Vec<DataChunk>) {
+        let mut chunk_lists = Vec::with_capacity(data_chunks.len() + 1);
+        chunk_lists.push(mem::take(&mut self.data_chunks));
+        for data_chunk in data_chunks {
+            if data_chunk
+                .address
+                .checked_add(data_chunk.len() as u64)
+                .is_none()
+            {
+                let address = data_chunk.address;
+                panic!("End address of data at {address:#08X} does not fit in a u64");
+            }
+            if !data_chunk.data.is_empty() {
+                chunk_lists.push(vec![data_chunk]);
+            }
+        }
+        // Every later list is written over the earlier ones
+        self.data_chunks = merge_chunk_lists(chunk_lists, OverlapPolicy::Overwrite).unwrap();
+    }
+
+    /// Writes `value` to every address in `range`, overwriting existing data and allocating data
+    /// in the gaps. Data chunks that become adjacent or overlapping are merged. Nothing is written
+    /// if `range` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.fill(0x1002..0x1008, 0xFF);
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(
+    ///     srecord_file[0x1000..0x1008],
+    ///     [0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+    /// );
+    /// ```
+    pub fn fill<A: IntoAddress>(&mut self, range: Range<A>, value: u8) {
+        let range = address_range(range);
+        if range.start >= range.end {
+            return;
+        }
+        // Chunks in first_index..last_index overlap or are adjacent to the range
+        let first_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() < range.start);
+        let last_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.address <= range.end);
+        if first_index == last_index {
+            let len = (range.end - range.start) as usize;
+            let mut data_chunk =
+                DataChunk::with_capacity(range.start, max(len, self.chunk_capacity));
+            data_chunk.data.resize(len, value);
+            self.data_chunks.insert(first_index, data_chunk);
+            return;
+        }
+
+        let start_address = min(range.start, self.data_chunks[first_index].address);
+        let end_address = max(range.end, self.data_chunks[last_index - 1].end_address());
+        let len = (end_address - start_address) as usize;
+        let mut merged_chunks = self.data_chunks.drain(first_index..last_index).peekable();
+        // Existing data before the range is kept in place, and the rest of its chunk overwritten
+        let mut data_chunk =
+            match merged_chunks.next_if(|data_chunk| data_chunk.address <= range.start) {
+                Some(mut data_chunk) => {
+                    let offset = (range.start - data_chunk.address) as usize;
+                    let end = min(
+                        data_chunk.data.len(),
+                        (range.end - data_chunk.address) as usize,
+                    );
+                    data_chunk.data[offset..end].fill(value);
+                    data_chunk.data.reserve_exact(len - data_chunk.data.len());
+                    data_chunk
+                }
+                None => DataChunk::with_capacity(range.start, len),
+            };
+        // Gaps and data inside the range are filled, and data after the range is kept
+        for merged_chunk in merged_chunks {
+            let end_address = min(merged_chunk.end_address(), range.end);
+            data_chunk
+                .data
+                .resize((end_address - start_address) as usize, value);
+            if merged_chunk.end_address() > range.end {
+                let offset = (range.end - merged_chunk.address) as usize;
+                data_chunk
+                    .data
+                    .extend_from_slice(&merged_chunk.data[offset..]);
+            }
+        }
+        if data_chunk.end_address() < range.end {
+            data_chunk
+                .data
+                .resize((range.end - start_address) as usize, value);
+        }
+        self.data_chunks.insert(first_index, data_chunk);
+    }
+
+    /// Fills every gap smaller than `max_gap` bytes between data chunks with `fill`, merging the
+    /// data chunks around it, so that the data is stored in fewer and larger data chunks. The
+    /// filled bytes become part of the data. This is the same as a [`ParseOptions::gap_fill`] with
+    /// a [`max_gap`](GapFill::max_gap) one smaller, applied after parsing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file =
+    ///     SRecordFile::from_str("S1051000AABB85\nS1051004AABB81\nS1051100CCDD40").unwrap();
+    /// srecord_file.bridge_gaps(0x10, 0xFF).unwrap();
+    /// assert_eq!(srecord_file.chunk_count(), 2);
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0xAA, 0xBB, 0xFF, 0xFF, 0xAA, 0xBB]);
+    /// ```
+    ///
+    /// The data chunks are [`normalize`](SRecordFile::normalize)d first, so data chunks that are
+    /// only out of order are sorted. Returns the error of [`normalize`](SRecordFile::normalize),
+    /// without changing any data chunk, if any data chunks overlap or extend past `u64::MAX`.
+    pub fn bridge_gaps(&mut self, max_gap: u64, fill: u8) -> Result<(), NormalizeError> {
+        // A GapFill bridges gaps of at most its max_gap bytes
+        let gap_fill = max_gap.checked_sub(1).map(|max_gap| GapFill {
+            max_gap,
+            value: fill,
+        });
+        self.normalize()?;
+        // Cannot fail, as normalized data chunks are sorted and do not overlap
+        self.merge_data_chunks(gap_fill)
+            .expect("Data chunks do not overlap");
+        Ok(())
+    }
+
+    /// Writes the `size` lowest bytes of `value` at `address` with byte order `endianness`,
+    /// allocating data and merging data chunks as by [`set_data`](SRecordFile::set_data).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{Endianness, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.write_int(0x1002, 3, 0xAABBCC, Endianness::Big);
+    /// srecord_file.write_u16_le(0x2000, 0x1234);
+    /// assert_eq!(srecord_file[0x1000..0x1005], [0x00, 0x01, 0xAA, 0xBB, 0xCC]);
+    /// assert_eq!(srecord_file[0x2000..0x2002], [0x34, 0x12]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`write_int`](SRecordFile::write_int) will [`panic!`] if `size` is not between 1 and 8, if
+    /// `value` does not fit in `size` bytes, or if the end address of the integer does not fit in a
+    /// `u64`.
+    pub fn write_int<A: IntoAddress>(
+        &mut self,
+        address: A,
+        size: usize,
+        value: u64,
+        endianness: Endianness,
+    ) {
+        check_int_size(size);
+        if size < 8 && value >> (8 * size) != 0 {
+            panic!("Value {value:#X} does not fit in {size} bytes");
+        }
+        self.set_data(address, &int_to_bytes(value, size, endianness));
+    }
+
+    /// Writes `value` as a little-endian `u16` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u16_le<A: IntoAddress>(&mut self, address: A, value: u16) {
+        self.set_data(address, &value.to_le_bytes());
+    }
+
+    /// Writes `value` as a big-endian `u16` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u16_be<A: IntoAddress>(&mut self, address: A, value: u16) {
+        self.set_data(address, &value.to_be_bytes());
+    }
+
+    /// Writes `value` as a little-endian `u32` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u32_le<A: IntoAddress>(&mut self, address: A, value: u32) {
+        self.set_data(address, &value.to_le_bytes());
+    }
+
+    /// Writes `value` as a big-endian `u32` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u32_be<A: IntoAddress>(&mut self, address: A, value: u32) {
+        self.set_data(address, &value.to_be_bytes());
+    }
+
+    /// Writes `value` as a little-endian `u64` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u64_le<A: IntoAddress>(&mut self, address: A, value: u64) {
+        self.set_data(address, &value.to_le_bytes());
+    }
+
+    /// Writes `value` as a big-endian `u64` at `address`, see
+    /// [`write_int`](SRecordFile::write_int).
+    pub fn write_u64_be<A: IntoAddress>(&mut self, address: A, value: u64) {
+        self.set_data(address, &value.to_be_bytes());
+    }
+
+    /// Returns a mutable reference to the byte at `address`, allocating it with the value 0 if it
+    /// does not exist, see [`range_mut`](SRecordFile::range_mut).
     ///
     /// # Examples
     ///
     /// ```
-    /// use srex::srecord::{DataChunk, SRecordFile};
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
     ///
-    /// let mut srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk{
-    ///         address: 0x1000,
-    ///         data: vec![0x01, 0x02, 0x03, 0x04],
-    ///     }],
-    ///     start_address: None,
-    /// };
-    /// assert!(srecord_file.get_mut(0x1004).is_none());
-    /// assert_eq!(srecord_file.get_mut(0x1001), Some(&mut 0x02u8));
-    /// *srecord_file.get_mut(0x1001).unwrap() = 0xAA;
-    /// assert_eq!(srecord_file.get_mut(0x1001), Some(&mut 0xAAu8));
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// *srecord_file.at_mut(0x1001) = 0xAA;
+    /// *srecord_file.at_mut(0x1004) = 0xBB;
+    /// assert_eq!(srecord_file[0x1000..0x1005], [0x00, 0xAA, 0x02, 0x03, 0xBB]);
+    /// assert_eq!(*srecord_file.at_mut(0x2000), 0x00);
     /// ```
-    pub(crate) fn get_data_chunk_mut(&mut self, address: u64) -> Option<&mut DataChunk> {
-        match self.get_data_chunk_index(address, false) {
-            Ok(data_chunk_index) => Some(&mut self.data_chunks[data_chunk_index]),
-            Err(_) => None,
+    ///
+    /// # Panics
+    ///
+    /// [`at_mut`](SRecordFile::at_mut) will [`panic!`] if `address` is `u64::MAX`, see
+    /// [`set_data`](SRecordFile::set_data).
+    pub fn at_mut(&mut self, address: u64) -> &mut u8 {
+        let end_address = match address.checked_add(1) {
+            Some(end_address) => end_address,
+            None => panic!("End address of data at {address:#08X} does not fit in a u64"),
+        };
+        &mut self.range_mut(address..end_address)[0]
+    }
+
+    /// Returns a mutable slice of the data in `range`, allocating all addresses in `range` that do
+    /// not contain any data with the value 0. Unlike [`get_mut`](SRecordFile::get_mut) and
+    /// indexing, this never fails because of missing data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.range_mut(0x1002..0x1006).copy_from_slice(&[0xAA, 0xBB, 0xCC, 0xDD]);
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0xAA, 0xBB, 0xCC, 0xDD]);
+    /// ```
+    pub fn range_mut(&mut self, range: Range<u64>) -> &mut [u8] {
+        self.range_mut_with_fill(range, 0x00)
+    }
+
+    /// Same as [`range_mut`](SRecordFile::range_mut), but allocates addresses that do not contain
+    /// any data with the value `fill`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// let data = srecord_file.range_mut_with_fill(0x0FFE..0x1002, 0xFF);
+    /// assert_eq!(data, [0xFF, 0xFF, 0x00, 0x01]);
+    /// data[0] = 0xAA;
+    /// assert_eq!(srecord_file[0x0FFE..0x1004], [0xAA, 0xFF, 0x00, 0x01, 0x02, 0x03]);
+    /// ```
+    pub fn range_mut_with_fill(&mut self, range: Range<u64>, fill: u8) -> &mut [u8] {
+        if range.start >= range.end {
+            return &mut [];
+        }
+        let gaps: Vec<Range<u64>> = self.gaps_within(range.clone()).collect();
+        for gap in gaps {
+            self.fill(gap, fill);
+        }
+        range.get_mut(self).unwrap()
+    }
+
+    /// XORs the data in `range` with `key`, which is repeated from the start of `range`. Addresses
+    /// without data are skipped, but still advance the key, so that the result does not depend on
+    /// the gaps in the data. Applying the same key twice restores the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051003AABB82").unwrap();
+    /// srecord_file.xor_range(0x1000..0x1005, &[0xFF, 0x0F]);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0x55, 0xB4]);
+    /// assert_eq!(srecord_file[0x1003..0x1005], [0xA5, 0x44]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`xor_range`](SRecordFile::xor_range) will [`panic!`] if `key` is empty.
+    pub fn xor_range(&mut self, range: Range<u64>, key: &[u8]) {
+        self.apply_key(range, key, |byte, key_byte| byte ^ key_byte);
+    }
+
+    /// ANDs the data in `range` with `mask`, which is repeated from the start of `range`, as by
+    /// [`xor_range`](SRecordFile::xor_range).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S1051000AABB85").unwrap();
+    /// srecord_file.and_range(0x1000..0x1002, &[0x0F]);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0x0A, 0x0B]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`and_range`](SRecordFile::and_range) will [`panic!`] if `mask` is empty.
+    pub fn and_range(&mut self, range: Range<u64>, mask: &[u8]) {
+        self.apply_key(range, mask, |byte, mask_byte| byte & mask_byte);
+    }
+
+    /// ORs the data in `range` with `mask`, which is repeated from the start of `range`, as by
+    /// [`xor_range`](SRecordFile::xor_range).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S1051000AABB85").unwrap();
+    /// srecord_file.or_range(0x1000..0x1002, &[0x0F]);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0xAF, 0xBF]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`or_range`](SRecordFile::or_range) will [`panic!`] if `mask` is empty.
+    pub fn or_range(&mut self, range: Range<u64>, mask: &[u8]) {
+        self.apply_key(range, mask, |byte, mask_byte| byte | mask_byte);
+    }
+
+    /// Inverts every bit of the data in `range`. Addresses without data are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S1051000AABB85").unwrap();
+    /// srecord_file.not_range(0x0F00..0x1001);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0x55, 0xBB]);
+    /// ```
+    pub fn not_range(&mut self, range: Range<u64>) {
+        for (_, data) in self.iter_data_in_range_mut(range) {
+            data.iter_mut().for_each(|byte| *byte = !*byte);
+        }
+    }
+
+    /// Reverses the byte order of every word of `word_size` bytes in `range`, which converts the
+    /// data between big and little endian.
+    ///
+    /// Returns an error, without changing any data, if `word_size` is not 2, 4 or 8, if the start
+    /// or end of `range` is not a multiple of `word_size`, or if any address in `range` has no
+    /// data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::{ByteSwapError, SRecordFile};
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S10B10000001020304050607C8").unwrap();
+    /// srecord_file.byte_swap(0x1000..0x1008, 4).unwrap();
+    /// assert_eq!(
+    ///     srecord_file[0x1000..0x1008],
+    ///     [0x03, 0x02, 0x01, 0x00, 0x07, 0x06, 0x05, 0x04],
+    /// );
+    /// assert_eq!(
+    ///     srecord_file.byte_swap(0x1004..0x100C, 2),
+    ///     Err(ByteSwapError::MissingData(0x1008..0x100C)),
+    /// );
+    /// ```
+    pub fn byte_swap(&mut self, range: Range<u64>, word_size: usize) -> Result<(), ByteSwapError> {
+        if !matches!(word_size, 2 | 4 | 8) {
+            return Err(ByteSwapError::InvalidWordSize);
+        }
+        let word_size_u64 = word_size as u64;
+        if !range.start.is_multiple_of(word_size_u64) || !range.end.is_multiple_of(word_size_u64) {
+            return Err(ByteSwapError::UnalignedRange);
+        }
+        if let Some(gap) = self.gaps_within(range.clone()).next() {
+            return Err(ByteSwapError::MissingData(gap));
+        }
+        if let Some(data) = self.get_mut(range) {
+            data.chunks_exact_mut(word_size).for_each(<[u8]>::reverse);
+        }
+        Ok(())
+    }
+
+    /// Removes all data in `range`. Data chunks that are only partially inside `range` are cut, and
+    /// a data chunk is split in two if `range` is in the middle of it. Nothing is removed if
+    /// `range` is empty. The records of the [`record_layout`](SRecordFile::record_layout) are cut
+    /// the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S10B10000001020304050607C8").unwrap();
+    /// srecord_file.erase(0x1002..0x1004);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1000..0x1002], [0x00, 0x01]);
+    /// assert_eq!(srecord_file[0x1004..0x1008], [0x04, 0x05, 0x06, 0x07]);
+    /// assert!(srecord_file.get(0x1002).is_none());
+    /// ```
+    pub fn erase<A: IntoAddress>(&mut self, range: Range<A>) {
+        let range = address_range(range);
+        if range.start >= range.end {
+            return;
+        }
+        if let Some(record_layout) = self.record_layout.as_mut() {
+            record_layout.data_records = erased_layout_records(&record_layout.data_records, &range);
+        }
+        // Chunks in first_index..last_index overlap the erased range
+        let first_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() <= range.start);
+        let last_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.address < range.end);
+        if first_index >= last_index {
+            return;
+        }
+
+        let mut kept_chunks = Vec::new();
+        for mut data_chunk in self.data_chunks.drain(first_index..last_index) {
+            if data_chunk.end_address() > range.end {
+                let (below, above) = data_chunk.split_at(range.end);
+                kept_chunks.push(above);
+                data_chunk = below;
+            }
+            if data_chunk.address < range.start {
+                data_chunk.truncate(range.start);
+                kept_chunks.push(data_chunk);
+            }
+        }
+        kept_chunks.sort_by_key(|data_chunk| data_chunk.address);
+        self.data_chunks
+            .splice(first_index..first_index, kept_chunks);
+    }
+
+    /// Iterates over the data in `range`, as `(address, data)` pairs in ascending address order.
+    /// Data chunks that are only partially inside `range` are cut to fit.
+    pub(crate) fn iter_data_in_range(
+        &self,
+        range: Range<u64>,
+    ) -> impl Iterator<Item = (u64, &[u8])> + '_ {
+        let first_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() <= range.start);
+        self.data_chunks[first_index..]
+            .iter()
+            .take_while(move |data_chunk| data_chunk.address < range.end)
+            .filter_map(move |data_chunk| {
+                let start_address = max(data_chunk.address, range.start);
+                let end_address = min(data_chunk.end_address(), range.end);
+                (start_address < end_address).then(|| {
+                    let start_index = (start_address - data_chunk.address) as usize;
+                    let end_index = (end_address - data_chunk.address) as usize;
+                    (start_address, &data_chunk.data[start_index..end_index])
+                })
+            })
+    }
+
+    /// Iterates over the data in `range` mutably, as `(address, data)` pairs in ascending address
+    /// order. Data chunks that are only partially inside `range` are cut to fit.
+    pub(crate) fn iter_data_in_range_mut(
+        &mut self,
+        range: Range<u64>,
+    ) -> impl Iterator<Item = (u64, &mut [u8])> + '_ {
+        let first_index = self
+            .data_chunks
+            .partition_point(|data_chunk| data_chunk.end_address() <= range.start);
+        self.data_chunks[first_index..]
+            .iter_mut()
+            .take_while(move |data_chunk| data_chunk.address < range.end)
+            .filter_map(move |data_chunk| {
+                let start_address = max(data_chunk.address, range.start);
+                let end_address = min(data_chunk.end_address(), range.end);
+                (start_address < end_address).then(|| {
+                    let start_index = (start_address - data_chunk.address) as usize;
+                    let end_index = (end_address - data_chunk.address) as usize;
+                    (start_address, &mut data_chunk.data[start_index..end_index])
+                })
+            })
+    }
+
+    /// Replaces every byte in `range` with `operation(byte, key_byte)`, where `key` is repeated
+    /// from `range.start`. Addresses without data are skipped.
+    fn apply_key(&mut self, range: Range<u64>, key: &[u8], operation: impl Fn(u8, u8) -> u8) {
+        assert!(!key.is_empty(), "Key must not be empty");
+        let range_start = range.start;
+        for (address, data) in self.iter_data_in_range_mut(range) {
+            let key_offset = ((address - range_start) % key.len() as u64) as usize;
+            let key_bytes = key[key_offset..].iter().chain(key.iter().cycle());
+            for (byte, key_byte) in data.iter_mut().zip(key_bytes) {
+                *byte = operation(*byte, *key_byte);
+            }
+        }
+    }
+
+    /// Iterates over every address where `pattern` is found in the data, comparing only the bits
+    /// set in `mask` if given.
+    fn find_matches<'a>(
+        &'a self,
+        pattern: &'a [u8],
+        mask: Option<&'a [u8]>,
+    ) -> impl DoubleEndedIterator<Item = u64> + 'a {
+        if let Some(mask) = mask {
+            assert_eq!(
+                mask.len(),
+                pattern.len(),
+                "Mask must have the same length as the pattern"
+            );
         }
+        let matches = move |window: &[u8]| match mask {
+            Some(mask) => window
+                .iter()
+                .zip(pattern)
+                .zip(mask)
+                .all(|((byte, pattern_byte), mask_byte)| (byte ^ pattern_byte) & mask_byte == 0),
+            None => window == pattern,
+        };
+        // Windows of size 0 are not allowed, and an empty pattern is never found
+        let data_chunks = match pattern.is_empty() {
+            true => &[],
+            false => self.data_chunks.as_slice(),
+        };
+        data_chunks.iter().flat_map(move |data_chunk| {
+            data_chunk
+                .data
+                .windows(pattern.len())
+                .enumerate()
+                .filter(move |(_, window)| matches(window))
+                .map(move |(index, _)| data_chunk.address + index as u64)
+        })
     }
 
     // TODO: Tests
     /// Iterates through [`SRecordFile::data_chunks`] and merges them together to form as large
-    /// contiguous chunks of data as possible.
-    fn merge_data_chunks(&mut self) -> Result<(), SRecordParseError> {
-        let mut index = 0;
-        while index < self.data_chunks.len() - 1 {
-            let current_end_address =
-                self.data_chunks[index].address + self.data_chunks[index].data.len() as u64;
-            let next_index = index + 1;
-            let next_start_address = self.data_chunks[next_index].address;
-            match next_start_address.cmp(&current_end_address) {
-                Ordering::Greater => index += 1,
-                Ordering::Equal => {
-                    // Merge
-                    let mut next_data_chunk = self.data_chunks.remove(next_index);
-                    self.data_chunks[index]
-                        .data
-                        .append(&mut next_data_chunk.data);
+    /// contiguous chunks of data as possible. Gaps between chunks are filled as configured by
+    /// `gap_fill`.
+    ///
+    /// The data chunks must be sorted by address. Overlapping data chunks are an error.
+    pub(crate) fn merge_data_chunks(
+        &mut self,
+        gap_fill: Option<GapFill>,
+    ) -> Result<(), SRecordParseError> {
+        let mut merged_chunks: Vec<DataChunk> = Vec::with_capacity(self.data_chunks.len());
+        for mut data_chunk in mem::take(&mut self.data_chunks) {
+            let last_chunk = match merged_chunks.last_mut() {
+                Some(last_chunk) => last_chunk,
+                None => {
+                    merged_chunks.push(data_chunk);
+                    continue;
                 }
-                Ordering::Less => {
-                    return Err(SRecordParseError {
-                        error_type: ErrorType::OverlappingData,
-                    })
+            };
+            let last_end_address = last_chunk.end_address();
+            if data_chunk.address < last_end_address {
+                return Err(SRecordParseError {
+                    error_type: ErrorType::OverlappingData,
+                });
+            }
+            let gap = data_chunk.address - last_end_address;
+            match gap_fill {
+                _ if gap == 0 => last_chunk.data.append(&mut data_chunk.data),
+                Some(gap_fill) if gap <= gap_fill.max_gap => {
+                    let len = last_chunk.data.len() + gap as usize;
+                    last_chunk.data.resize(len, gap_fill.value);
+                    last_chunk.data.append(&mut data_chunk.data);
                 }
+                _ => merged_chunks.push(data_chunk),
             }
         }
+        self.data_chunks = merged_chunks;
         Ok(())
     }
 }
 
+/// Returns the parts of the record layout ranges `data_records` inside the normalized list `ranges`,
+/// in the order of `data_records`. Records that are cut by a gap in `ranges` are split.
+fn cropped_layout_records(data_records: &[Range<u64>], ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+    data_records
+        .iter()
+        .flat_map(|range| intersection(slice::from_ref(range), ranges))
+        .collect()
+}
+
+/// Removes `range` from the address ranges of the data records of a record layout, keeping their
+/// order. Records partially inside `range` are cut, and split in two if `range` is in the middle.
+fn erased_layout_records(data_records: &[Range<u64>], range: &Range<u64>) -> Vec<Range<u64>> {
+    data_records
+        .iter()
+        .flat_map(|data_record| difference(slice::from_ref(data_record), slice::from_ref(range)))
+        .collect()
+}
+
 impl SliceIndex<SRecordFile> for u64 {
     type Output = u8;
 
@@ -292,14 +3358,11 @@ impl SliceIndex<SRecordFile> for u64 {
     /// use srex::srecord::{DataChunk, SRecordFile};
     /// use srex::srecord::slice_index::SliceIndex;
     ///
-    /// let srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk {
-    ///         address: 0x1000,
-    ///         data: vec![0x00, 0x01, 0x02, 0x03],
-    ///     }],
-    ///     start_address: None,
-    /// };
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// });
     /// assert_eq!(*(0x1001 as u64).get(&srecord_file).unwrap(), 0x01);
     /// assert!((0x1004 as u64).get(&srecord_file).is_none());
     /// ```
@@ -310,93 +3373,224 @@ impl SliceIndex<SRecordFile> for u64 {
         }
     }
 
-    /// Returns a mutable reference to a single byte in a [`SRecordFile::data_chunks`], at the
-    /// address that `self` points to, or `None` if out of bounds.
+    /// Returns a mutable reference to a single byte in a [`SRecordFile::data_chunks`], at the
+    /// address that `self` points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SRecordFile};
+    /// use srex::srecord::slice_index::SliceIndex;
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// });
+    /// assert_eq!(*(0x1001 as u64).get_mut(&mut srecord_file).unwrap(), 0x01);
+    /// *(0x1001 as u64).get_mut(&mut srecord_file).unwrap() = 0xFF;
+    /// assert_eq!(*(0x1001 as u64).get_mut(&mut srecord_file).unwrap(), 0xFF);
+    /// assert!((0x1004 as u64).get(&srecord_file).is_none());
+    /// ```
+    fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
+        match srecord_file.get_data_chunk_mut(self) {
+            Some(data_chunk) => data_chunk.get_mut(self),
+            None => None,
+        }
+    }
+}
+
+impl SliceIndex<SRecordFile> for Address {
+    type Output = u8;
+
+    /// Returns a reference to a single byte in a [`SRecordFile::data_chunks`], at the address that
+    /// `self` points to, or `None` if out of bounds. Same as indexing with a `u64`.
+    fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
+        self.value().get(srecord_file)
+    }
+
+    /// Returns a mutable reference to a single byte in a [`SRecordFile::data_chunks`], at the
+    /// address that `self` points to, or `None` if out of bounds. Same as indexing with a `u64`.
+    fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
+        self.value().get_mut(srecord_file)
+    }
+}
+
+impl SliceIndex<SRecordFile> for Range<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to a data slice in an [`SRecordFile`], at the address range that `self`
+    /// points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SRecordFile};
+    /// use srex::srecord::slice_index::SliceIndex;
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// });
+    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get(&srecord_file).unwrap(), [0x01, 0x02]);
+    /// assert!((0x1000 as u64..0x1005 as u64).get(&srecord_file).is_none());
+    /// ```
+    fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
+        match srecord_file.get_data_chunk(self.start) {
+            Some(data_chunk) => data_chunk.get(self),
+            None => None,
+        }
+    }
+
+    /// Returns a reference to a data slice in an [`SRecordFile`], at the address range that `self`
+    /// points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{DataChunk, SRecordFile};
+    /// use srex::srecord::slice_index::SliceIndex;
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.data_chunks.push(DataChunk {
+    ///     address: 0x1000,
+    ///     data: vec![0x00, 0x01, 0x02, 0x03],
+    /// });
+    ///
+    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get_mut(&mut srecord_file).unwrap(), [0x01, 0x02]);
+    /// (0x1001 as u64..0x1003).get_mut(&mut srecord_file).unwrap().fill(0xAA);
+    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get_mut(&mut srecord_file).unwrap(), [0xAA, 0xAA]);
+    /// assert!((0x1000 as u64..0x1005 as u64).get_mut(&mut srecord_file).is_none());
+    /// ```
+    fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
+        match srecord_file.get_data_chunk_mut(self.start) {
+            Some(data_chunk) => data_chunk.get_mut(self),
+            None => None,
+        }
+    }
+}
+
+impl SliceIndex<SRecordFile> for RangeInclusive<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to a data slice in an [`SRecordFile`], at the inclusive address range
+    /// that `self` points to, or `None` if out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// assert_eq!(srecord_file.get(0x1001..=0x1003).unwrap(), [0x01, 0x02, 0x03]);
+    /// assert!(srecord_file.get(0x1000..=0x1004).is_none());
+    /// ```
+    fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
+        exclusive_range(self)?.get(srecord_file)
+    }
+
+    /// Returns a mutable reference to a data slice in an [`SRecordFile`], at the inclusive address
+    /// range that `self` points to, or `None` if out of bounds.
+    fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
+        exclusive_range(self)?.get_mut(srecord_file)
+    }
+}
+
+impl SliceIndex<SRecordFile> for RangeFrom<u64> {
+    type Output = [u8];
+
+    /// Returns a reference to the data in an [`SRecordFile`] from the address that `self` points to
+    /// until the end of the contiguous data, or `None` if there is no data at the address.
     ///
     /// # Examples
     ///
     /// ```
-    /// use srex::srecord::{DataChunk, SRecordFile};
-    /// use srex::srecord::slice_index::SliceIndex;
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
     ///
-    /// let mut srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk {
-    ///         address: 0x1000,
-    ///         data: vec![0x00, 0x01, 0x02, 0x03],
-    ///     }],
-    ///     start_address: None,
-    /// };
-    /// assert_eq!(*(0x1001 as u64).get_mut(&mut srecord_file).unwrap(), 0x01);
-    /// *(0x1001 as u64).get_mut(&mut srecord_file).unwrap() = 0xFF;
-    /// assert_eq!(*(0x1001 as u64).get_mut(&mut srecord_file).unwrap(), 0xFF);
-    /// assert!((0x1004 as u64).get(&srecord_file).is_none());
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.get(0x1002..).unwrap(), [0x02, 0x03]);
+    /// assert!(srecord_file.get(0x1004..).is_none());
     /// ```
+    fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
+        srecord_file.get_data_chunk(self.start)?.get(self)
+    }
+
+    /// Returns a mutable reference to the data in an [`SRecordFile`] from the address that `self`
+    /// points to until the end of the contiguous data, or `None` if there is no data at the
+    /// address.
     fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
-        match srecord_file.get_data_chunk_mut(self) {
-            Some(data_chunk) => data_chunk.get_mut(self),
-            None => None,
-        }
+        srecord_file.get_data_chunk_mut(self.start)?.get_mut(self)
     }
 }
 
-impl SliceIndex<SRecordFile> for Range<u64> {
+impl SliceIndex<SRecordFile> for RangeTo<u64> {
     type Output = [u8];
 
-    /// Returns a reference to a data slice in an [`SRecordFile`], at the address range that `self`
-    /// points to, or `None` if out of bounds.
+    /// Returns a reference to the data in an [`SRecordFile`] from the start of the contiguous data
+    /// until the exclusive end address that `self` points to, or `None` if there is no data right
+    /// before the end address.
     ///
     /// # Examples
     ///
     /// ```
-    /// use srex::srecord::{DataChunk, SRecordFile};
-    /// use srex::srecord::slice_index::SliceIndex;
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
     ///
-    /// let srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk {
-    ///         address: 0x1000,
-    ///         data: vec![0x00, 0x01, 0x02, 0x03],
-    ///     }],
-    ///     start_address: None,
-    /// };
-    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get(&srecord_file).unwrap(), [0x01, 0x02]);
-    /// assert!((0x1000 as u64..0x1005 as u64).get(&srecord_file).is_none());
+    /// let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    /// assert_eq!(srecord_file.get(..0x2001).unwrap(), [0xAA]);
+    /// assert!(srecord_file.get(..0x1005).is_none());
     /// ```
     fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
-        match srecord_file.get_data_chunk(self.start) {
-            Some(data_chunk) => data_chunk.get(self),
-            None => None,
-        }
+        srecord_file
+            .get_data_chunk(self.end.checked_sub(1)?)?
+            .get(self)
     }
 
-    /// Returns a reference to a data slice in an [`SRecordFile`], at the address range that `self`
-    /// points to, or `None` if out of bounds.
+    /// Returns a mutable reference to the data in an [`SRecordFile`] from the start of the
+    /// contiguous data until the exclusive end address that `self` points to, or `None` if there is
+    /// no data right before the end address.
+    fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
+        srecord_file
+            .get_data_chunk_mut(self.end.checked_sub(1)?)?
+            .get_mut(self)
+    }
+}
+
+impl SliceIndex<SRecordFile> for RangeFull {
+    type Output = [u8];
+
+    /// Returns a reference to all data in an [`SRecordFile`], or `None` if the data is not
+    /// contiguous. Returns an empty slice if the file contains no data.
     ///
     /// # Examples
     ///
     /// ```
-    /// use srex::srecord::{DataChunk, SRecordFile};
-    /// use srex::srecord::slice_index::SliceIndex;
-    ///
-    /// let mut srecord_file = SRecordFile{
-    ///     header_data: None,
-    ///     data_chunks: vec![DataChunk {
-    ///         address: 0x1000,
-    ///         data: vec![0x00, 0x01, 0x02, 0x03],
-    ///     }],
-    ///     start_address: None,
-    /// };
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
     ///
-    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get_mut(&mut srecord_file).unwrap(), [0x01, 0x02]);
-    /// (0x1001 as u64..0x1003).get_mut(&mut srecord_file).unwrap().fill(0xAA);
-    /// assert_eq!(*(0x1001 as u64..0x1003 as u64).get_mut(&mut srecord_file).unwrap(), [0xAA, 0xAA]);
-    /// assert!((0x1000 as u64..0x1005 as u64).get_mut(&mut srecord_file).is_none());
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// assert_eq!(srecord_file.get(..).unwrap(), [0x00, 0x01, 0x02, 0x03]);
+    /// srecord_file.set_data(0x2000, &[0xAA]);
+    /// assert!(srecord_file.get(..).is_none());
     /// ```
+    fn get(self, srecord_file: &SRecordFile) -> Option<&Self::Output> {
+        match srecord_file.data_chunks.as_slice() {
+            [] => Some(&[]),
+            [data_chunk] => Some(&data_chunk.data),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to all data in an [`SRecordFile`], or `None` if the data is not
+    /// contiguous. Returns an empty slice if the file contains no data.
     fn get_mut(self, srecord_file: &mut SRecordFile) -> Option<&mut Self::Output> {
-        match srecord_file.get_data_chunk_mut(self.start) {
-            Some(data_chunk) => data_chunk.get_mut(self),
-            None => None,
+        match srecord_file.data_chunks.as_mut_slice() {
+            [] => Some(&mut []),
+            [data_chunk] => Some(&mut data_chunk.data),
+            _ => None,
         }
     }
 }
@@ -438,6 +3632,21 @@ impl Index<u64> for SRecordFile {
     }
 }
 
+impl Index<Address> for SRecordFile {
+    type Output = u8;
+
+    /// Index the data inside the [`SRecordFile`] using an [`Address`], same as indexing with a
+    /// `u64`.
+    ///
+    /// # Panics
+    ///
+    /// [`index`](SRecordFile::index) will [`panic!`] if the input address does not exist in the
+    /// [`SRecordFile`].
+    fn index(&self, address: Address) -> &Self::Output {
+        &self[address.value()]
+    }
+}
+
 impl Index<Range<u64>> for SRecordFile {
     type Output = [u8];
 
@@ -480,6 +3689,78 @@ impl Index<Range<u64>> for SRecordFile {
     }
 }
 
+impl Index<RangeInclusive<u64>> for SRecordFile {
+    type Output = [u8];
+
+    /// Get a slice for the data inside the [`SRecordFile`] using an inclusive address range, with
+    /// the syntax `srecord_file[0x1235..=0x1236]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index`](SRecordFile::index) will [`panic!`] if the input address range does not exist in
+    /// the [`SRecordFile`].
+    fn index(&self, address_range: RangeInclusive<u64>) -> &Self::Output {
+        match address_range.clone().get(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl Index<RangeFrom<u64>> for SRecordFile {
+    type Output = [u8];
+
+    /// Get a slice for the data inside the [`SRecordFile`] using an address range without end, with
+    /// the syntax `srecord_file[0x1235..]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index`](SRecordFile::index) will [`panic!`] if the input address range does not exist in
+    /// the [`SRecordFile`].
+    fn index(&self, address_range: RangeFrom<u64>) -> &Self::Output {
+        match address_range.clone().get(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl Index<RangeTo<u64>> for SRecordFile {
+    type Output = [u8];
+
+    /// Get a slice for the data inside the [`SRecordFile`] using an address range without start,
+    /// with the syntax `srecord_file[..0x1237]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index`](SRecordFile::index) will [`panic!`] if the input address range does not exist in
+    /// the [`SRecordFile`].
+    fn index(&self, address_range: RangeTo<u64>) -> &Self::Output {
+        match address_range.get(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl Index<RangeFull> for SRecordFile {
+    type Output = [u8];
+
+    /// Get a slice for the data inside the [`SRecordFile`] using the full address range, with the
+    /// syntax `srecord_file[..]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index`](SRecordFile::index) will [`panic!`] if the data in the [`SRecordFile`] is not
+    /// contiguous.
+    fn index(&self, address_range: RangeFull) -> &Self::Output {
+        match address_range.get(self) {
+            Some(data) => data,
+            None => panic!("Data in SRecordFile is not contiguous"),
+        }
+    }
+}
+
 impl IndexMut<u64> for SRecordFile {
     /// Performs mutable indexing in [`SRecordFile`], allowing writing using syntax
     /// `srecord_file[0x1234] = 0xFF`.
@@ -506,7 +3787,8 @@ impl IndexMut<u64> for SRecordFile {
     /// # Panics
     ///
     /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address does not exist in
-    /// the [`SRecordFile`].
+    /// the [`SRecordFile`]. Use [`at_mut`](SRecordFile::at_mut) or
+    /// [`set_data`](SRecordFile::set_data) to add new data.
     fn index_mut(&mut self, address: u64) -> &mut Self::Output {
         match address.get_mut(self) {
             Some(data) => data,
@@ -515,6 +3797,19 @@ impl IndexMut<u64> for SRecordFile {
     }
 }
 
+impl IndexMut<Address> for SRecordFile {
+    /// Performs mutable indexing in [`SRecordFile`] using an [`Address`], same as indexing with a
+    /// `u64`.
+    ///
+    /// # Panics
+    ///
+    /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address does not exist in
+    /// the [`SRecordFile`].
+    fn index_mut(&mut self, address: Address) -> &mut Self::Output {
+        &mut self[address.value()]
+    }
+}
+
 impl IndexMut<Range<u64>> for SRecordFile {
     /// Performs mutable indexing in [`SRecordFile`], allowing writing using syntax
     /// `srecord_file[0x1234..0x1236] = [0x1A, 0x1B]`.
@@ -541,7 +3836,8 @@ impl IndexMut<Range<u64>> for SRecordFile {
     /// # Panics
     ///
     /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address does not exist in
-    /// the [`SRecordFile`].
+    /// the [`SRecordFile`]. Use [`range_mut`](SRecordFile::range_mut) or
+    /// [`set_data`](SRecordFile::set_data) to add new data.
     fn index_mut(&mut self, address_range: Range<u64>) -> &mut Self::Output {
         let start_address = address_range.start;
         let end_address = address_range.end;
@@ -552,6 +3848,199 @@ impl IndexMut<Range<u64>> for SRecordFile {
     }
 }
 
+impl IndexMut<RangeInclusive<u64>> for SRecordFile {
+    /// Performs mutable indexing in [`SRecordFile`] using an inclusive address range, with the
+    /// syntax `srecord_file[0x1235..=0x1236]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address range does not
+    /// exist in the [`SRecordFile`].
+    fn index_mut(&mut self, address_range: RangeInclusive<u64>) -> &mut Self::Output {
+        match address_range.clone().get_mut(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl IndexMut<RangeFrom<u64>> for SRecordFile {
+    /// Performs mutable indexing in [`SRecordFile`] using an address range without end, with the
+    /// syntax `srecord_file[0x1235..]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address range does not
+    /// exist in the [`SRecordFile`].
+    fn index_mut(&mut self, address_range: RangeFrom<u64>) -> &mut Self::Output {
+        match address_range.clone().get_mut(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl IndexMut<RangeTo<u64>> for SRecordFile {
+    /// Performs mutable indexing in [`SRecordFile`] using an address range without start, with the
+    /// syntax `srecord_file[..0x1237]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the input address range does not
+    /// exist in the [`SRecordFile`].
+    fn index_mut(&mut self, address_range: RangeTo<u64>) -> &mut Self::Output {
+        match address_range.get_mut(self) {
+            Some(data) => data,
+            None => panic!("Address range {address_range:#08X?} does not exist in SRecordFile"),
+        }
+    }
+}
+
+impl IndexMut<RangeFull> for SRecordFile {
+    /// Performs mutable indexing in [`SRecordFile`] using the full address range, with the syntax
+    /// `srecord_file[..]`. See [`SliceIndex`] for details.
+    ///
+    /// # Panics
+    ///
+    /// [`index_mut`](SRecordFile::index_mut) will [`panic!`] if the data in the [`SRecordFile`] is
+    /// not contiguous.
+    fn index_mut(&mut self, address_range: RangeFull) -> &mut Self::Output {
+        match address_range.get_mut(self) {
+            Some(data) => data,
+            None => panic!("Data in SRecordFile is not contiguous"),
+        }
+    }
+}
+
+impl FromIterator<(u64, u8)> for SRecordFile {
+    /// Builds an [`SRecordFile`] from bytes and their addresses, in any order. Bytes at
+    /// consecutive addresses are grouped into data chunks. If an address occurs more than once,
+    /// the last byte at that address is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let bytes = [(0x1001, 0x01), (0x1002, 0x02), (0x1000, 0x00), (0x2000, 0xAA)];
+    /// let srecord_file: SRecordFile = bytes.into_iter().collect();
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1000..0x1003], [0x00, 0x01, 0x02]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any address is `u64::MAX`, see [`set_data`](SRecordFile::set_data).
+    fn from_iter<T: IntoIterator<Item = (u64, u8)>>(iter: T) -> Self {
+        let mut srecord_file = SRecordFile::new();
+        srecord_file.extend(iter);
+        srecord_file
+    }
+}
+
+impl FromIterator<(u64, Vec<u8>)> for SRecordFile {
+    /// Builds an [`SRecordFile`] from segments of data and their addresses, in any order. Adjacent
+    /// segments are merged into one data chunk. Where segments overlap, the data of the last
+    /// segment is kept. The segments are moved into data chunks without copying, and sorted and
+    /// merged in one pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let segments = vec![(0x1002, vec![0x02, 0x03]), (0x1000, vec![0x00, 0x01])];
+    /// let srecord_file: SRecordFile = segments.into_iter().collect();
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0x03]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end address of any segment does not fit in a `u64`, see
+    /// [`set_data`](SRecordFile::set_data).
+    fn from_iter<T: IntoIterator<Item = (u64, Vec<u8>)>>(iter: T) -> Self {
+        let mut srecord_file = SRecordFile::new();
+        srecord_file.overwrite_data_chunks(
+            iter.into_iter()
+                .map(|(address, data)| DataChunk { address, data })
+                .collect(),
+        );
+        srecord_file
+    }
+}
+
+impl Extend<(u64, u8)> for SRecordFile {
+    /// Writes bytes at their addresses, in any order, overwriting existing data. Bytes at
+    /// consecutive addresses are collected into runs, and all runs are sorted and merged with the
+    /// existing data in one pass. If an address occurs more than once, the last byte at that
+    /// address is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// srecord_file.extend((0x1004..0x1008).map(|address| (address, address as u8)));
+    /// assert_eq!(srecord_file.data_chunks.len(), 1);
+    /// assert_eq!(srecord_file[0x1002..0x1008], [0x02, 0x03, 0x04, 0x05, 0x06, 0x07]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if any address is `u64::MAX`, see [`set_data`](SRecordFile::set_data).
+    fn extend<T: IntoIterator<Item = (u64, u8)>>(&mut self, iter: T) {
+        // Runs of bytes at consecutive addresses are collected into data chunks
+        let mut runs: Vec<DataChunk> = Vec::new();
+        for (address, byte) in iter {
+            match runs.last_mut() {
+                Some(run) if run.end_address() == address => run.data.push(byte),
+                _ => runs.push(DataChunk {
+                    address,
+                    data: vec![byte],
+                }),
+            }
+        }
+        self.overwrite_data_chunks(runs);
+    }
+}
+
+impl<'a> Extend<(u64, &'a [u8])> for SRecordFile {
+    /// Writes segments of data at their addresses, in any order, overwriting existing data. All
+    /// segments are sorted and merged with the existing data in one pass. Where segments overlap,
+    /// the data of the last segment is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    /// let segments: [(u64, &[u8]); 2] = [(0x1004, &[0x04, 0x05]), (0x2000, &[0xAA])];
+    /// srecord_file.extend(segments);
+    /// assert_eq!(srecord_file.data_chunks.len(), 2);
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0x04, 0x05]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end address of any segment does not fit in a `u64`, see
+    /// [`set_data`](SRecordFile::set_data).
+    fn extend<T: IntoIterator<Item = (u64, &'a [u8])>>(&mut self, iter: T) {
+        self.overwrite_data_chunks(
+            iter.into_iter()
+                .map(|(address, data)| DataChunk {
+                    address,
+                    data: data.to_vec(),
+                })
+                .collect(),
+        );
+    }
+}
+
 impl FromStr for SRecordFile {
     type Err = SRecordParseError;
 
@@ -574,84 +4063,30 @@ impl FromStr for SRecordFile {
     /// let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
     /// ```
     fn from_str(srecord_str: &str) -> Result<Self, Self::Err> {
-        let mut srecord_file = SRecordFile::new();
-
-        let mut num_data_records: usize = 0;
-        let mut data_buffer = [0u8; 256];
-
-        for line in srecord_str.lines() {
-            let record = Record::from_str(line, &mut data_buffer)?;
-            match record {
-                Record::S0Record(header_record) => match srecord_file.header_data {
-                    Some(_) => {
-                        return Err(SRecordParseError {
-                            error_type: ErrorType::MultipleHeaderRecords,
-                        })
-                    }
-                    None => srecord_file.header_data = Some(Vec::<u8>::from(header_record.data)),
-                },
-                Record::S1Record(data_record)
-                | Record::S2Record(data_record)
-                | Record::S3Record(data_record) => {
-                    // TODO: Validate record type (no mixes?)
-                    match srecord_file.get_data_chunk_index(data_record.address, true) {
-                        Ok(data_chunk_index) => {
-                            // Error if writing to the same address twice
-                            let data_chunk = &mut srecord_file.data_chunks[data_chunk_index];
-                            if data_chunk.address as usize + data_chunk.data.len()
-                                != data_record.address as usize
-                            {
-                                return Err(SRecordParseError {
-                                    error_type: ErrorType::OverlappingData,
-                                });
-                            }
-                            data_chunk.data.extend_from_slice(data_record.data);
-                        }
-                        Err(data_chunk_index) => {
-                            // TODO: Move out to allocation function?
-                            srecord_file.data_chunks.insert(
-                                data_chunk_index,
-                                DataChunk {
-                                    address: data_record.address,
-                                    data: Vec::<u8>::from(data_record.data),
-                                },
-                            );
-                        }
-                    }
-                    num_data_records += 1;
-                }
-                Record::S5Record(count_record) | Record::S6Record(count_record) => {
-                    // TODO: Validate record count
-                    // * Only last in file
-                    // * Only once
-                    // * Ensure it matches number of encountered data records
-                    let file_num_records = count_record.record_count;
-                    if num_data_records != file_num_records {
-                        return Err(SRecordParseError {
-                            error_type: ErrorType::CalculatedNumRecordsNotMatchingParsedNumRecords,
-                        });
-                    }
-                }
-                Record::S7Record(start_address_record)
-                | Record::S8Record(start_address_record)
-                | Record::S9Record(start_address_record) => {
-                    if srecord_file.start_address.is_some() {
-                        return Err(SRecordParseError {
-                            error_type: ErrorType::MultipleStartAddresses,
-                        });
-                    }
-                    srecord_file.start_address = Some(start_address_record.start_address);
-                }
-            }
-        }
-
-        // Merge data chunks
-        srecord_file.merge_data_chunks()?;
-
+        let (srecord_file, _) =
+            SRecordFile::from_str_with_options(srecord_str, &ParseOptions::default())?;
         Ok(srecord_file)
     }
 }
 
+/// Splits `data_record` in two if it crosses the boundary between two 4 GiB banks, see
+/// [`SerializationOptions::bank_records`].
+fn split_at_bank(data_record: DataRecord) -> impl Iterator<Item = DataRecord> {
+    // Number of addresses from the start of the data record to the end of its bank
+    let remaining_bank_size = (data_record.address | 0xFFFF_FFFF) - data_record.address + 1;
+    let split_index = min(remaining_bank_size, data_record.data.len() as u64) as usize;
+    let (data, next_data) = data_record.data.split_at(split_index);
+    let next_data_record = (!next_data.is_empty()).then(|| DataRecord {
+        address: data_record.address + split_index as u64,
+        data: next_data,
+    });
+    iter::once(DataRecord {
+        address: data_record.address,
+        data,
+    })
+    .chain(next_data_record)
+}
+
 /// Used to keep track of which stage of the records the iterator is at.
 enum SRecordFileIteratorStage {
     /// Next record should be a [`HeaderRecord`].
@@ -673,15 +4108,23 @@ pub struct SRecordFileIterator<'a> {
     srecord_file: &'a SRecordFile,
     /// Which type of [`Record`] the iterator will return next (if exists).
     stage: SRecordFileIteratorStage,
-    /// Which element in [`SRecordFile::data_chunks`] is currently iterated over.
-    data_chunk_index: usize,
-    /// Iterator inside current [`DataChunk`].
-    data_chunk_iterator: Option<DataChunkIterator<'a>>,
-    /// Number of data bytes found in each [`DataRecord`].
-    data_record_size: usize,
+    /// Iterator over the [`DataRecord`]s to return.
+    data_records: Box<dyn Iterator<Item = DataRecord<'a>> + 'a>,
+    /// Which type of data [`Record`] to return, S1, S2 or S3.
+    data_record_type: RecordType,
+    /// Which type of count [`Record`] to return, if any.
+    count_record_type: CountRecordType,
+    /// Which type of start address [`Record`] to return, S7, S8 or S9.
+    start_address_record_type: RecordType,
     /// Number of data records found in the [`SRecordFile`] so far. Used to generate a
     /// [`CountRecord`].
     num_data_records: usize,
+    /// Whether to return [`BankRecord`]s, see [`SerializationOptions::bank_records`].
+    bank_records: bool,
+    /// Bank of the last [`BankRecord`] returned, initially 0.
+    bank: u32,
+    /// Data record to return after the [`BankRecord`] that was just returned.
+    pending_data_record: Option<DataRecord<'a>>,
 }
 
 impl<'a> Iterator for SRecordFileIterator<'a> {
@@ -698,49 +4141,54 @@ impl<'a> Iterator for SRecordFileIterator<'a> {
                     None => self.next(),
                 }
             }
-            SRecordFileIteratorStage::Data => match self.data_chunk_iterator.as_mut() {
-                Some(iterator) => match iterator.next() {
-                    Some(record) => {
-                        self.num_data_records += 1;
-                        Some(Record::S3Record(record))
+            SRecordFileIteratorStage::Data => match self
+                .pending_data_record
+                .take()
+                .or_else(|| self.data_records.next())
+            {
+                Some(mut record) => {
+                    if self.bank_records {
+                        let bank = (record.address >> 32) as u32;
+                        if bank != self.bank {
+                            self.bank = bank;
+                            self.pending_data_record = Some(record);
+                            return Some(Record::S4Record(BankRecord { bank }));
+                        }
+                        record.address &= 0xFFFF_FFFF;
                     }
-                    None => {
-                        self.data_chunk_index += 1;
-                        self.data_chunk_iterator = None;
-                        self.next()
+                    self.num_data_records += 1;
+                    match self.data_record_type {
+                        RecordType::S1 => Some(Record::S1Record(record)),
+                        RecordType::S2 => Some(Record::S2Record(record)),
+                        _ => Some(Record::S3Record(record)),
                     }
-                },
+                }
                 None => {
-                    match self.srecord_file.data_chunks.get(self.data_chunk_index) {
-                        Some(data_chunk) => {
-                            self.data_chunk_iterator =
-                                Some(data_chunk.iter_records(self.data_record_size));
-                        }
-                        None => {
-                            self.stage = SRecordFileIteratorStage::Count;
-                        }
-                    }
+                    self.stage = SRecordFileIteratorStage::Count;
                     self.next()
                 }
             },
             SRecordFileIteratorStage::Count => {
                 self.stage = SRecordFileIteratorStage::StartAddress;
-                if self.num_data_records < 1 << 16 {
-                    Some(Record::S5Record(CountRecord {
-                        record_count: self.num_data_records,
-                    }))
-                } else if self.num_data_records < 1 << 24 {
-                    Some(Record::S6Record(CountRecord {
-                        record_count: self.num_data_records,
-                    }))
-                } else {
-                    self.next()
+                // The number of data records was validated when creating the iterator
+                match self
+                    .count_record_type
+                    .count_record(self.num_data_records)
+                    .unwrap()
+                {
+                    Some(count_record) => Some(count_record),
+                    None => self.next(),
                 }
             }
             SRecordFileIteratorStage::StartAddress => match self.srecord_file.start_address {
                 Some(start_address) => {
                     self.stage = SRecordFileIteratorStage::Finished;
-                    Some(Record::S7Record(StartAddressRecord { start_address }))
+                    let start_address_record = StartAddressRecord { start_address };
+                    match self.start_address_record_type {
+                        RecordType::S9 => Some(Record::S9Record(start_address_record)),
+                        RecordType::S8 => Some(Record::S8Record(start_address_record)),
+                        _ => Some(Record::S7Record(start_address_record)),
+                    }
                 }
                 None => {
                     self.stage = SRecordFileIteratorStage::Finished;
@@ -751,3 +4199,86 @@ impl<'a> Iterator for SRecordFileIterator<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn srecord_file(data_chunks: &[(u64, usize)]) -> SRecordFile {
+        let mut srecord_file = SRecordFile::new();
+        srecord_file.data_chunks = data_chunks
+            .iter()
+            .map(|&(address, len)| DataChunk {
+                address,
+                data: vec![0; len],
+            })
+            .collect();
+        srecord_file
+    }
+
+    #[test]
+    fn test_get_data_chunk_index() {
+        let srecord_file = srecord_file(&[(0x1000, 0x10), (0x2000, 0x10), (0x3000, 0x10)]);
+        for inclusive_end in [false, true] {
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x0000, inclusive_end),
+                Err(0)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x0FFF, inclusive_end),
+                Err(0)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x1000, inclusive_end),
+                Ok(0)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x100F, inclusive_end),
+                Ok(0)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x1011, inclusive_end),
+                Err(1)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x2008, inclusive_end),
+                Ok(1)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x3000, inclusive_end),
+                Ok(2)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(0x3011, inclusive_end),
+                Err(3)
+            );
+            assert_eq!(
+                srecord_file.get_data_chunk_index(u64::MAX, inclusive_end),
+                Err(3)
+            );
+        }
+        // The end address is only in bounds if `inclusive_end` is set
+        assert_eq!(srecord_file.get_data_chunk_index(0x1010, false), Err(1));
+        assert_eq!(srecord_file.get_data_chunk_index(0x1010, true), Ok(0));
+        assert_eq!(srecord_file.get_data_chunk_index(0x3010, false), Err(3));
+        assert_eq!(srecord_file.get_data_chunk_index(0x3010, true), Ok(2));
+    }
+
+    #[test]
+    fn test_get_data_chunk_index_empty() {
+        let srecord_file = SRecordFile::new();
+        assert_eq!(srecord_file.get_data_chunk_index(0, false), Err(0));
+        assert_eq!(srecord_file.get_data_chunk_index(u64::MAX, true), Err(0));
+    }
+
+    #[test]
+    fn test_get_data_chunk_index_end_of_address_space() {
+        let srecord_file = srecord_file(&[(u64::MAX - 0x10, 0x10)]);
+        assert_eq!(
+            srecord_file.get_data_chunk_index(u64::MAX - 1, false),
+            Ok(0)
+        );
+        assert_eq!(srecord_file.get_data_chunk_index(u64::MAX, false), Err(1));
+        assert_eq!(srecord_file.get_data_chunk_index(u64::MAX, true), Ok(0));
+    }
+}
@@ -0,0 +1,442 @@
+use std::cmp::max;
+use std::mem;
+
+use crate::srecord::error::{ErrorType, SRecordParseError, SerializationError};
+use crate::srecord::utils::{data_within, normalized};
+use crate::srecord::{
+    BankRecord, DataChunk, ParseOptions, ParseStats, ParseWarning, Record, RecordLayout,
+    SRecordFile, SerializationOptions,
+};
+
+/// Incremental parser that builds an [`SRecordFile`] from input that arrives piece by piece, e.g.
+/// over a serial link or a socket.
+///
+/// Records are parsed and validated as soon as a complete line has been pushed, in the same way as
+/// [`SRecordFile::from_str_with_options`]. Data chunks are merged and the file is returned by
+/// [`finish`](SRecordParser::finish).
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::SRecordParser;
+///
+/// let mut parser = SRecordParser::new();
+/// parser.push_bytes(b"S10710000001").unwrap();
+/// parser.push_bytes(b"0203E2\r\nS1051004AABB81\r\n").unwrap();
+/// parser.push_line("S9031000EC").unwrap();
+/// assert_eq!(parser.stats().num_data_records, 2);
+///
+/// let srecord_file = parser.finish().unwrap();
+/// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB]);
+/// assert_eq!(srecord_file.start_address, Some(0x1000));
+/// ```
+#[derive(Debug)]
+pub struct SRecordParser {
+    /// Options controlling the validation of the records.
+    options: ParseOptions,
+    /// File containing the data of the records pushed so far.
+    srecord_file: SRecordFile,
+    /// Statistics about the records pushed so far.
+    stats: ParseStats,
+    /// Whether a count record (S5/S6) has been pushed.
+    count_record_found: bool,
+    /// Whether a start address record (S7/S8/S9) has been pushed.
+    terminator_found: bool,
+    /// Whether the data chunks of the file are sorted by address. Records pushed out of address
+    /// order are only sorted into place when the parser is finished.
+    data_chunks_sorted: bool,
+    /// Bytes pushed with [`push_bytes`](SRecordParser::push_bytes) after the last line ending.
+    partial_line: Vec<u8>,
+    /// Number of bytes to reserve at least when the data of a chunk has to grow, see
+    /// [`reserve`](SRecordParser::reserve).
+    chunk_capacity: usize,
+    /// Input pushed so far, kept for the record layout if [`ParseOptions::record_layout`] is set.
+    /// `None` until input is pushed as text.
+    source: Option<Vec<u8>>,
+    /// Base address of the data records, set by the last bank record (S4) if
+    /// [`ParseOptions::bank_records`] is set.
+    bank_address: u64,
+}
+
+impl Default for SRecordParser {
+    /// Generates a new [`SRecordParser`] with default [`ParseOptions`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SRecordParser {
+    /// Creates a new [`SRecordParser`] with default [`ParseOptions`] and no records pushed.
+    pub fn new() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+
+    /// Creates a new [`SRecordParser`] with no records pushed, validating the records as
+    /// configured by `options`. If [`ParseOptions::record_layout`] is set, the pushed text is
+    /// kept as [`RecordLayout::source`].
+    pub fn with_options(mut options: ParseOptions) -> Self {
+        options.address_filter = options.address_filter.map(normalized);
+        let mut srecord_file = SRecordFile::new();
+        if options.record_layout {
+            srecord_file.record_layout = Some(RecordLayout::new(Vec::new()));
+        }
+        SRecordParser {
+            options,
+            srecord_file,
+            stats: ParseStats::default(),
+            count_record_found: false,
+            terminator_found: false,
+            data_chunks_sorted: true,
+            partial_line: Vec::new(),
+            chunk_capacity: 0,
+            source: None,
+            bank_address: 0,
+        }
+    }
+
+    /// Reserves room for at least `num_chunks` more data chunks, and for `bytes_per_chunk` bytes
+    /// in each data chunk that is continued by a following record, to avoid repeated reallocation
+    /// when the size of the input is known in advance.
+    ///
+    /// Chunks that are not continued are not reserved for, and capacity that a chunk does not use
+    /// is released when the next chunk starts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordParser;
+    ///
+    /// let mut parser = SRecordParser::new();
+    /// parser.reserve(1, 0x1000);
+    /// parser.push_line("S107100000010203E2").unwrap();
+    /// parser.push_line("S1051004AABB81").unwrap();
+    /// let srecord_file = parser.finish().unwrap();
+    /// assert_eq!(srecord_file[0x1000..0x1006], [0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB]);
+    /// ```
+    pub fn reserve(&mut self, num_chunks: usize, bytes_per_chunk: usize) {
+        self.srecord_file.data_chunks.reserve(num_chunks);
+        self.chunk_capacity = bytes_per_chunk;
+    }
+
+    /// Returns statistics about the records pushed so far.
+    pub fn stats(&self) -> &ParseStats {
+        &self.stats
+    }
+
+    /// Returns the count record (S5/S6) of the type selected by
+    /// [`SerializationOptions::count_record_type`] for the data records pushed so far, or `None`
+    /// if no count record is emitted for that many records.
+    ///
+    /// Returns an error if the number of data records does not fit in the selected type of count
+    /// record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::{CountRecord, Record, SRecordParser, SerializationOptions};
+    ///
+    /// let mut parser = SRecordParser::new();
+    /// parser.push_line("S107100000010203E2").unwrap();
+    /// parser.push_line("S1051004AABB81").unwrap();
+    ///
+    /// let count_record = parser
+    ///     .expected_count_record_value(&SerializationOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(count_record, Some(Record::S5Record(CountRecord { record_count: 2 })));
+    /// assert!(parser.verify_count(2).is_ok());
+    /// assert!(parser.verify_count(3).is_err());
+    /// ```
+    pub fn expected_count_record_value(
+        &self,
+        options: &SerializationOptions,
+    ) -> Result<Option<Record<'static>>, SerializationError> {
+        options
+            .count_record_type
+            .count_record(self.stats.num_data_records)
+    }
+
+    /// Validates `parsed_count`, the record count of a count record (S5/S6), against the number of
+    /// data records pushed so far.
+    pub fn verify_count(&self, parsed_count: usize) -> Result<(), SRecordParseError> {
+        match parsed_count == self.stats.num_data_records {
+            true => Ok(()),
+            false => Err(SRecordParseError {
+                error_type: ErrorType::CalculatedNumRecordsNotMatchingParsedNumRecords,
+            }),
+        }
+    }
+
+    /// Parses a single, complete line without line ending, and validates the record against the
+    /// records pushed so far.
+    ///
+    /// Returns [`ErrorType::IncompleteLinePending`], without parsing `line`, if
+    /// [`push_bytes`](SRecordParser::push_bytes) holds an incomplete line, as the lines would
+    /// otherwise be parsed out of order. The incomplete line must be completed first.
+    pub fn push_line(&mut self, line: &str) -> Result<(), SRecordParseError> {
+        if !self.partial_line.is_empty() {
+            return Err(SRecordParseError {
+                error_type: ErrorType::IncompleteLinePending,
+            });
+        }
+        self.push_source(line.as_bytes());
+        self.push_source(b"\n");
+        self.parse_line(line.as_bytes())
+    }
+
+    /// Appends `bytes` to the input, and parses every line that is completed by it. Lines end with
+    /// `\n` or `\r\n`, and the last incomplete line is kept until more bytes are pushed or the
+    /// parser is finished.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), SRecordParseError> {
+        let mut remaining_bytes = bytes;
+        while let Some(line_end) = remaining_bytes.iter().position(|&byte| byte == b'\n') {
+            let line_bytes = &remaining_bytes[..=line_end];
+            remaining_bytes = &remaining_bytes[line_end + 1..];
+            if self.partial_line.is_empty() {
+                self.parse_line_bytes(line_bytes)?;
+            } else {
+                let mut line = mem::take(&mut self.partial_line);
+                line.extend_from_slice(line_bytes);
+                self.parse_line_bytes(&line)?;
+            }
+        }
+        self.partial_line.extend_from_slice(remaining_bytes);
+        Ok(())
+    }
+
+    /// Parses the last line if it was not terminated by a line ending, merges the data chunks of
+    /// the file, and returns the file.
+    pub fn finish(self) -> Result<SRecordFile, SRecordParseError> {
+        self.finish_with_stats()
+            .map(|(srecord_file, _)| srecord_file)
+    }
+
+    /// Same as [`finish`](SRecordParser::finish), but also returns [`ParseStats`] about the pushed
+    /// records.
+    pub fn finish_with_stats(mut self) -> Result<(SRecordFile, ParseStats), SRecordParseError> {
+        if !self.partial_line.is_empty() {
+            let line = mem::take(&mut self.partial_line);
+            self.parse_line_bytes(&line)?;
+        }
+        if let Some(last_chunk) = self.srecord_file.data_chunks.last_mut() {
+            shrink_unused(last_chunk);
+        }
+        if !self.data_chunks_sorted {
+            self.srecord_file
+                .data_chunks
+                .sort_by_key(|data_chunk| data_chunk.address);
+        }
+        self.srecord_file.merge_data_chunks(self.options.gap_fill)?;
+        if let Some(source) = self.source {
+            self.srecord_file.attach_source(source);
+        }
+        Ok((self.srecord_file, self.stats))
+    }
+
+    /// Parses a line pushed with [`push_bytes`](SRecordParser::push_bytes), including its line
+    /// ending if present.
+    fn parse_line_bytes(&mut self, line_bytes: &[u8]) -> Result<(), SRecordParseError> {
+        self.push_source(line_bytes);
+        let line = line_bytes.strip_suffix(b"\n").unwrap_or(line_bytes);
+        self.parse_line(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+
+    /// Appends `bytes` to the input kept for the record layout, if any.
+    fn push_source(&mut self, bytes: &[u8]) {
+        if self.options.record_layout {
+            self.source
+                .get_or_insert_with(Vec::new)
+                .extend_from_slice(bytes);
+        }
+    }
+
+    /// Counts a line that is not parsed as a record, and reports it as `error_type` as configured
+    /// by [`ParseOptions::strict`].
+    pub(crate) fn skip_line(&mut self, error_type: ErrorType) -> Result<(), SRecordParseError> {
+        if self.options.strict {
+            return Err(SRecordParseError { error_type });
+        }
+        self.stats.warnings.push(ParseWarning {
+            line_index: self.stats.num_records,
+            error_type,
+        });
+        self.stats.num_records += 1;
+        Ok(())
+    }
+
+    /// Parses a single line without line ending, see [`push_line`](SRecordParser::push_line).
+    pub(crate) fn parse_line(&mut self, line: &[u8]) -> Result<(), SRecordParseError> {
+        if self.options.bank_records && line.starts_with(b"S4") {
+            return self.push_record(Record::S4Record(BankRecord::from_bytes(line)?));
+        }
+        let mut data_buffer = [0u8; 256];
+        self.push_record(Record::from_bytes(line, &mut data_buffer)?)
+    }
+
+    /// Validates `record` against the records pushed so far, and adds its contents to the file.
+    pub(crate) fn push_record(&mut self, record: Record) -> Result<(), SRecordParseError> {
+        let line_index = self.stats.num_records;
+        let stats = &mut self.stats;
+        let srecord_file = &mut self.srecord_file;
+        stats.num_records += 1;
+
+        let is_data_record = matches!(
+            record,
+            Record::S1Record(_) | Record::S2Record(_) | Record::S3Record(_)
+        );
+        for (out_of_place, error_type) in [
+            (self.terminator_found, ErrorType::RecordAfterTerminator),
+            (
+                self.count_record_found && is_data_record,
+                ErrorType::DataAfterCountRecord,
+            ),
+        ] {
+            if !out_of_place {
+                continue;
+            }
+            if self.options.strict {
+                return Err(SRecordParseError { error_type });
+            }
+            stats.warnings.push(ParseWarning {
+                line_index,
+                error_type,
+            });
+        }
+        match record {
+            Record::S5Record(_) | Record::S6Record(_) => self.count_record_found = true,
+            Record::S7Record(_) | Record::S8Record(_) | Record::S9Record(_) => {
+                self.terminator_found = true
+            }
+            _ => {}
+        }
+
+        match record {
+            Record::S0Record(header_record) => match srecord_file.header_data {
+                Some(_) => {
+                    return Err(SRecordParseError {
+                        error_type: ErrorType::MultipleHeaderRecords,
+                    })
+                }
+                None => srecord_file.header_data = Some(Vec::<u8>::from(header_record.data)),
+            },
+            Record::S1Record(data_record)
+            | Record::S2Record(data_record)
+            | Record::S3Record(data_record) => {
+                // TODO: Validate record type (no mixes?)
+                let address = self.bank_address + data_record.address;
+                match self.options.address_filter.as_deref() {
+                    Some(address_filter) => {
+                        for (address, data) in
+                            data_within(address, data_record.data, address_filter)
+                        {
+                            push_data(
+                                srecord_file,
+                                &mut self.data_chunks_sorted,
+                                self.chunk_capacity,
+                                address,
+                                data,
+                            )?;
+                        }
+                    }
+                    None => push_data(
+                        srecord_file,
+                        &mut self.data_chunks_sorted,
+                        self.chunk_capacity,
+                        address,
+                        data_record.data,
+                    )?,
+                }
+                stats.num_data_records += 1;
+            }
+            Record::S4Record(bank_record) => match self.options.bank_records {
+                true => self.bank_address = (bank_record.bank as u64) << 32,
+                false => {
+                    return Err(SRecordParseError {
+                        error_type: ErrorType::S4Reserved,
+                    })
+                }
+            },
+            Record::S5Record(count_record) | Record::S6Record(count_record) => {
+                self.verify_count(count_record.record_count)?
+            }
+            Record::S7Record(start_address_record)
+            | Record::S8Record(start_address_record)
+            | Record::S9Record(start_address_record) => {
+                if srecord_file.start_address.is_some() {
+                    return Err(SRecordParseError {
+                        error_type: ErrorType::MultipleStartAddresses,
+                    });
+                }
+                srecord_file.start_address = Some(start_address_record.start_address);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Adds `data` at `address` to the data chunks of `srecord_file`, and its address range to the
+/// record layout if there is one.
+fn push_data(
+    srecord_file: &mut SRecordFile,
+    data_chunks_sorted: &mut bool,
+    chunk_capacity: usize,
+    address: u64,
+    data: &[u8],
+) -> Result<(), SRecordParseError> {
+    let last_end_address = srecord_file
+        .data_chunks
+        .last()
+        .map(|data_chunk| data_chunk.end_address());
+    match last_end_address {
+        // Records in ascending address order are added to the end without searching
+        Some(last_end_address) if address == last_end_address => {
+            let last_chunk = srecord_file.data_chunks.last_mut().unwrap();
+            // Grow in large steps, instead of doubling from the size of one record
+            if last_chunk.data.capacity() - last_chunk.data.len() < data.len() {
+                let additional = max(chunk_capacity, last_chunk.data.len());
+                last_chunk.data.reserve(additional);
+            }
+            last_chunk.data.extend_from_slice(data);
+        }
+        Some(last_end_address) if address > last_end_address => {
+            shrink_unused(srecord_file.data_chunks.last_mut().unwrap());
+            srecord_file.data_chunks.push(DataChunk {
+                address,
+                data: Vec::<u8>::from(data),
+            })
+        }
+        None => srecord_file.data_chunks.push(DataChunk {
+            address,
+            data: Vec::<u8>::from(data),
+        }),
+        Some(_) => {
+            // Error early if writing inside existing data, as long as the data chunks can still be
+            // searched. Other overlaps are found when merging the chunks.
+            if *data_chunks_sorted && srecord_file.get_data_chunk_index(address, false).is_ok() {
+                return Err(SRecordParseError {
+                    error_type: ErrorType::OverlappingData,
+                });
+            }
+            // Out of order records are sorted when finishing, instead of inserting every record at
+            // its position
+            shrink_unused(srecord_file.data_chunks.last_mut().unwrap());
+            srecord_file.data_chunks.push(DataChunk {
+                address,
+                data: Vec::<u8>::from(data),
+            });
+            *data_chunks_sorted = false;
+        }
+    }
+    if let Some(record_layout) = srecord_file.record_layout.as_mut() {
+        let end_address = address + data.len() as u64;
+        record_layout.data_records.push(address..end_address);
+    }
+    Ok(())
+}
+
+/// Releases the unused capacity of `data_chunk` if it is more than its length, i.e. more than what
+/// would be unused when growing by doubling.
+fn shrink_unused(data_chunk: &mut DataChunk) {
+    if data_chunk.data.capacity() - data_chunk.data.len() > data_chunk.data.len() {
+        data_chunk.data.shrink_to_fit();
+    }
+}
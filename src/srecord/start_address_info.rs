@@ -0,0 +1,24 @@
+use crate::srecord::{Address, RecordType};
+
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// Start address of an [`SRecordFile`] together with how it relates to the file, returned by
+/// [`SRecordFile::start_address_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartAddressInfo {
+    /// The start address.
+    pub address: Address,
+    /// Narrowest start address record type (S9, S8 or S7) that can hold the address, or `None` if
+    /// the address does not fit in any of them.
+    pub record_type: Option<RecordType>,
+    /// Whether there is data at the start address.
+    pub points_to_data: bool,
+}
+
+impl StartAddressInfo {
+    /// Returns whether the start address can be written to a record and points to data.
+    pub fn is_valid(&self) -> bool {
+        self.record_type.is_some() && self.points_to_data
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(doc)]
+use crate::srecord::{Pipeline, SRecordFile};
+
+/// Decides which start address is kept when a [`Pipeline`] merges [`SRecordFile`]s that define
+/// start addresses.
+///
+/// The inputs of a [`Pipeline`] are numbered in the order they are added: input 0 is the file
+/// passed to [`Pipeline::load`], and input `n` is the file of the `n`th
+/// [`merge`](Pipeline::merge) step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StartAddressPolicy {
+    /// Different start addresses are an error.
+    Error,
+    /// The existing start address is kept, and a merged start address is only used if there is
+    /// no existing start address.
+    #[default]
+    KeepExisting,
+    /// The existing start address is overwritten by a merged start address.
+    Overwrite,
+    /// The start address of the input with the given index is used, and all other start
+    /// addresses are ignored. It is an error if that input does not exist or has no start
+    /// address.
+    RequireFromInput(usize),
+}
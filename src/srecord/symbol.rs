@@ -0,0 +1,23 @@
+use std::ops::Range;
+
+#[cfg(doc)]
+use crate::srecord::SRecordFile;
+
+/// A named address range in an [`SRecordFile`], e.g. taken from the symbol table of the linked
+/// ELF file or from a linker map file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    /// Name of the symbol.
+    pub name: String,
+    /// Start address of the symbol.
+    pub address: u64,
+    /// Size of the symbol in bytes.
+    pub size: u64,
+}
+
+impl Symbol {
+    /// Returns the address range covered by the symbol, or `None` if it does not fit in a `u64`.
+    pub fn range(&self) -> Option<Range<u64>> {
+        Some(self.address..self.address.checked_add(self.size)?)
+    }
+}
@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::srecord::error::{ErrorType, SRecordParseError};
+use crate::srecord::{DataChunk, SRecordFile};
+
+/// Maximum number of data bytes in each Extended Tektronix data record generated from an
+/// [`SRecordFile`].
+const TEKTRONIX_DATA_RECORD_SIZE: usize = 16;
+
+/// Record type character of a data record.
+const DATA_RECORD: u8 = b'6';
+/// Record type character of a termination record, containing the start address.
+const TERMINATION_RECORD: u8 = b'8';
+/// Record type character of a symbol record.
+const SYMBOL_RECORD: u8 = b'3';
+
+impl SRecordFile {
+    /// Parses the data and start address of an Extended Tektronix hex file into an
+    /// [`SRecordFile`].
+    ///
+    /// Every line is a record starting with `%`, followed by the number of characters after the
+    /// `%`, the record type, an 8-bit checksum of the record characters and an address field. Data
+    /// records (type 6) are collected into the data, and the address of the termination record
+    /// (type 8) becomes the [`start_address`](SRecordFile::start_address). Symbol records (type 3)
+    /// are validated but otherwise ignored. Empty lines are skipped.
+    ///
+    /// Returns an error if any record is invalid, if any data overlaps, if there is more than one
+    /// termination record, or if any record comes after the termination record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let srecord_file =
+    ///     SRecordFile::from_tektronix_str("%1A626810000000202020202020\n%0781010").unwrap();
+    /// assert_eq!(srecord_file[0x10000000..0x10000006], [0x20; 6]);
+    /// assert_eq!(srecord_file.start_address, Some(0));
+    /// ```
+    pub fn from_tektronix_str(tektronix_str: &str) -> Result<Self, SRecordParseError> {
+        let mut srecord_file = SRecordFile::new();
+        let mut termination_found = false;
+        for line in tektronix_str.lines() {
+            let line = line.trim_end().as_bytes();
+            if line.is_empty() {
+                continue;
+            }
+            if termination_found {
+                return Err(parse_error(ErrorType::RecordAfterTerminator));
+            }
+            let (record_type, address, data) = parse_record(line)?;
+            match record_type {
+                DATA_RECORD => match srecord_file.data_chunks.last_mut() {
+                    Some(last_chunk) if last_chunk.end_address() == address => {
+                        last_chunk.data.extend_from_slice(&data)
+                    }
+                    _ => srecord_file.data_chunks.push(DataChunk { address, data }),
+                },
+                TERMINATION_RECORD => {
+                    srecord_file.start_address = Some(address);
+                    termination_found = true;
+                }
+                _ => {}
+            }
+        }
+        srecord_file
+            .data_chunks
+            .sort_by_key(|data_chunk| data_chunk.address);
+        srecord_file.merge_data_chunks(None)?;
+        Ok(srecord_file)
+    }
+
+    /// Serializes the data and start address into an Extended Tektronix hex file, with up to 16
+    /// bytes in each data record and a termination record at the end.
+    ///
+    /// Addresses are written with 8 hexadecimal digits, or 16 digits for data above 32 bits. The
+    /// termination record contains the [`start_address`](SRecordFile::start_address), or 0 if
+    /// there is none. The header data has no equivalent in Extended Tektronix hex, and is not
+    /// included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use srex::srecord::SRecordFile;
+    ///
+    /// let mut srecord_file = SRecordFile::new();
+    /// srecord_file.set_data(0x10000000, &[0x20; 6]);
+    /// assert_eq!(
+    ///     srecord_file.to_tektronix_string(),
+    ///     "%1A626810000000202020202020\n%0E81E800000000\n",
+    /// );
+    /// ```
+    pub fn to_tektronix_string(&self) -> String {
+        let mut tektronix_string = String::new();
+        for data_chunk in self.data_chunks.iter() {
+            for data_record in data_chunk.iter_records(TEKTRONIX_DATA_RECORD_SIZE) {
+                push_record(
+                    &mut tektronix_string,
+                    DATA_RECORD,
+                    data_record.address,
+                    data_record.data,
+                );
+            }
+        }
+        push_record(
+            &mut tektronix_string,
+            TERMINATION_RECORD,
+            self.start_address.unwrap_or(0),
+            &[],
+        );
+        tektronix_string
+    }
+}
+
+/// Returns an [`SRecordParseError`] of type `error_type`.
+fn parse_error(error_type: ErrorType) -> SRecordParseError {
+    SRecordParseError { error_type }
+}
+
+/// Returns the value of `character` in the checksum of a record, or `None` if the character is not
+/// allowed in a record.
+fn character_value(character: u8) -> Option<u8> {
+    match character {
+        b'0'..=b'9' => Some(character - b'0'),
+        b'A'..=b'Z' => Some(character - b'A' + 10),
+        b'$' => Some(36),
+        b'%' => Some(37),
+        b'.' => Some(38),
+        b'_' => Some(39),
+        b'a'..=b'z' => Some(character - b'a' + 40),
+        _ => None,
+    }
+}
+
+/// Parses the hexadecimal digits in `digits`, or returns `None` if any character is not a
+/// hexadecimal digit or the value does not fit in a `u64`.
+fn parse_hex(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u64::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+/// Parses and validates a single record without line ending, and returns its record type, address
+/// and data. The data of symbol records is not decoded.
+fn parse_record(line: &[u8]) -> Result<(u8, u64, Vec<u8>), SRecordParseError> {
+    if line[0] != b'%' {
+        return Err(parse_error(ErrorType::InvalidFirstCharacter));
+    }
+    let length = line
+        .get(1..3)
+        .ok_or(parse_error(ErrorType::EolWhileParsingByteCount))?;
+    let length = parse_hex(length).ok_or(parse_error(ErrorType::InvalidByteCount))? as usize;
+    if length < 6 {
+        return Err(parse_error(ErrorType::ByteCountTooLowForRecordType));
+    }
+    match (line.len() - 1).cmp(&length) {
+        Ordering::Less => return Err(parse_error(ErrorType::EolWhileParsingData)),
+        Ordering::Greater => return Err(parse_error(ErrorType::LineNotTerminatedAfterChecksum)),
+        Ordering::Equal => {}
+    }
+    let record_type = line[3];
+    if !matches!(
+        record_type,
+        DATA_RECORD | TERMINATION_RECORD | SYMBOL_RECORD
+    ) {
+        return Err(parse_error(ErrorType::InvalidRecordType));
+    }
+    let checksum = parse_hex(&line[4..6]).ok_or(parse_error(ErrorType::InvalidChecksum))?;
+    let mut calculated_checksum = 0u8;
+    for &character in line[1..4].iter().chain(line[6..].iter()) {
+        let value = character_value(character).ok_or(parse_error(ErrorType::InvalidData))?;
+        calculated_checksum = calculated_checksum.wrapping_add(value);
+    }
+    if calculated_checksum as u64 != checksum {
+        return Err(parse_error(
+            ErrorType::CalculatedChecksumNotMatchingParsedChecksum,
+        ));
+    }
+    if record_type == SYMBOL_RECORD {
+        return Ok((record_type, 0, Vec::new()));
+    }
+
+    let num_address_digits = match line.get(6) {
+        Some(b'0') => 16,
+        Some(&digit @ b'1'..=b'9') => (digit - b'0') as usize,
+        Some(_) => return Err(parse_error(ErrorType::InvalidAddress)),
+        None => return Err(parse_error(ErrorType::EolWhileParsingAddress)),
+    };
+    let address = line
+        .get(7..7 + num_address_digits)
+        .ok_or(parse_error(ErrorType::EolWhileParsingAddress))?;
+    let address = parse_hex(address).ok_or(parse_error(ErrorType::InvalidAddress))?;
+    let data_digits = &line[7 + num_address_digits..];
+    if record_type == TERMINATION_RECORD && !data_digits.is_empty() {
+        return Err(parse_error(ErrorType::LineNotTerminatedAfterChecksum));
+    }
+    if !data_digits.len().is_multiple_of(2) {
+        return Err(parse_error(ErrorType::EolWhileParsingData));
+    }
+    let data = data_digits
+        .chunks(2)
+        .map(|digits| parse_hex(digits).map(|byte| byte as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(parse_error(ErrorType::InvalidData))?;
+    if address.checked_add(data.len() as u64).is_none() {
+        return Err(parse_error(ErrorType::AddressOverflowForRecordType));
+    }
+    Ok((record_type, address, data))
+}
+
+/// Appends a record of `record_type` with `address` and `data` to `tektronix_string`.
+fn push_record(tektronix_string: &mut String, record_type: u8, address: u64, data: &[u8]) {
+    let (address_length, address_digits) = match address > u32::MAX as u64 {
+        true => ('0', format!("{address:016X}")),
+        false => ('8', format!("{address:08X}")),
+    };
+    let mut fields = String::with_capacity(address_digits.len() + 2 * data.len() + 1);
+    fields.push(address_length);
+    fields.push_str(&address_digits);
+    for byte in data {
+        write!(fields, "{byte:02X}").unwrap();
+    }
+    let length = fields.len() + 5;
+    let header = format!("{length:02X}{}", record_type as char);
+    let checksum = header
+        .bytes()
+        .chain(fields.bytes())
+        .filter_map(character_value)
+        .fold(0u8, |checksum, value| checksum.wrapping_add(value));
+    writeln!(tektronix_string, "%{header}{checksum:02X}{fields}").unwrap();
+}
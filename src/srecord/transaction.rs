@@ -0,0 +1,143 @@
+use std::ops::Range;
+use std::slice;
+
+use crate::srecord::address::address_range;
+use crate::srecord::error::TransactionError;
+use crate::srecord::utils::{difference, intersection, normalized, union};
+use crate::srecord::{IntoAddress, SRecordFile};
+
+/// A single edit in a [`Transaction`].
+#[derive(Debug)]
+enum Edit {
+    /// Write data starting at an address, allocating it where needed.
+    Set { address: u64, data: Vec<u8> },
+    /// Write data starting at an address where the file has no data.
+    Insert { address: u64, data: Vec<u8> },
+    /// Remove all data in an address range.
+    Erase(Range<u64>),
+}
+
+/// Edits staged by the closure passed to [`SRecordFile::transaction`].
+///
+/// Edits are only recorded when they are added. They are validated and applied in the order they
+/// were added once the closure returns successfully, see [`SRecordFile::transaction`].
+#[derive(Debug, Default)]
+pub struct Transaction {
+    /// Edits to apply, in order.
+    edits: Vec<Edit>,
+    /// Address ranges that no edit may write or erase.
+    protected_ranges: Vec<Range<u64>>,
+    /// Address ranges that all written data must be inside, or `None` to allow writes anywhere.
+    memory_layout: Option<Vec<Range<u64>>>,
+}
+
+impl Transaction {
+    /// Writes `data` starting at `address`, overwriting existing data and allocating new data
+    /// where needed.
+    pub fn set<A: IntoAddress>(&mut self, address: A, data: &[u8]) -> &mut Self {
+        self.edits.push(Edit::Set {
+            address: address.into_address().value(),
+            data: data.to_vec(),
+        });
+        self
+    }
+
+    /// Writes `data` starting at `address`, where the file must not have any data after the edits
+    /// before it. Fails with [`TransactionError::OverlappingData`] otherwise.
+    pub fn insert<A: IntoAddress>(&mut self, address: A, data: &[u8]) -> &mut Self {
+        self.edits.push(Edit::Insert {
+            address: address.into_address().value(),
+            data: data.to_vec(),
+        });
+        self
+    }
+
+    /// Removes all data in `range`. Data chunks that are only partially inside `range` are cut.
+    /// Nothing is removed if `range` is empty, as by [`SRecordFile::erase`].
+    pub fn erase<A: IntoAddress>(&mut self, range: Range<A>) -> &mut Self {
+        self.edits.push(Edit::Erase(address_range(range)));
+        self
+    }
+
+    /// Protects `range`, e.g. a bootloader or a calibration sector. The transaction fails with
+    /// [`TransactionError::Protected`] if any edit, added before or after, writes or erases an
+    /// address in `range`.
+    pub fn protect(&mut self, range: Range<u64>) -> &mut Self {
+        self.protected_ranges.push(range);
+        self
+    }
+
+    /// Restricts written data to `ranges`, e.g. the flash regions of the target. The transaction
+    /// fails with [`TransactionError::OutsideMemoryLayout`] if any edit writes an address outside
+    /// of `ranges`. Calling this again adds to the allowed ranges.
+    pub fn memory_layout(&mut self, ranges: &[Range<u64>]) -> &mut Self {
+        self.memory_layout
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(ranges);
+        self
+    }
+
+    /// Validates every edit against `srecord_file` as modified by the edits before it, without
+    /// applying any.
+    pub(crate) fn validate<E>(
+        &self,
+        srecord_file: &SRecordFile,
+    ) -> Result<(), TransactionError<E>> {
+        let protected_ranges = normalized(self.protected_ranges.clone());
+        let memory_layout = self.memory_layout.clone().map(normalized);
+        let mut memory_map = normalized(
+            srecord_file
+                .data_chunks
+                .iter()
+                .map(|data_chunk| data_chunk.address..data_chunk.end_address())
+                .collect(),
+        );
+        for (edit_index, edit) in self.edits.iter().enumerate() {
+            let range = match edit {
+                Edit::Set { address, data } | Edit::Insert { address, data } => {
+                    match address.checked_add(data.len() as u64) {
+                        Some(end_address) => *address..end_address,
+                        None => return Err(TransactionError::AddressOverflow(edit_index)),
+                    }
+                }
+                Edit::Erase(range) => range.clone(),
+            };
+            if range.is_empty() {
+                continue;
+            }
+            let range = slice::from_ref(&range);
+            if !intersection(range, &protected_ranges).is_empty() {
+                return Err(TransactionError::Protected(edit_index));
+            }
+            match edit {
+                Edit::Erase(_) => memory_map = difference(&memory_map, range),
+                _ => {
+                    if matches!(edit, Edit::Insert { .. })
+                        && !intersection(range, &memory_map).is_empty()
+                    {
+                        return Err(TransactionError::OverlappingData(edit_index));
+                    }
+                    if let Some(memory_layout) = memory_layout.as_deref() {
+                        if !difference(range, memory_layout).is_empty() {
+                            return Err(TransactionError::OutsideMemoryLayout(edit_index));
+                        }
+                    }
+                    memory_map = union(&memory_map, range);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies every edit to `srecord_file`. The edits must have been validated.
+    pub(crate) fn apply(self, srecord_file: &mut SRecordFile) {
+        for edit in self.edits {
+            match edit {
+                Edit::Set { address, data } | Edit::Insert { address, data } => {
+                    srecord_file.set_data(address, &data)
+                }
+                Edit::Erase(range) => srecord_file.erase(range),
+            }
+        }
+    }
+}
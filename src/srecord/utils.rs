@@ -1,80 +1,58 @@
+use std::cmp::{max, min};
 use std::num::Wrapping;
+use std::ops::Range;
 
 use crate::srecord::error::{ErrorType, SRecordParseError};
 use crate::srecord::record_type::RecordType;
+#[cfg(doc)]
+use crate::srecord::Record;
+use crate::srecord::RecordInfo;
 
-/// Parses a record type from `record_str` and returns it, or error message
+/// Parses a record type from `record_bytes` and returns it, or error message
 #[inline]
-pub(crate) fn parse_record_type(record_str: &str) -> Result<RecordType, SRecordParseError> {
-    let mut chars = record_str.chars();
-    match chars.next() {
-        Some('S') => match chars.next() {
-            Some('0') => Ok(RecordType::S0),
-            Some('1') => Ok(RecordType::S1),
-            Some('2') => Ok(RecordType::S2),
-            Some('3') => Ok(RecordType::S3),
-            Some('4') => Err(SRecordParseError {
-                error_type: ErrorType::S4Reserved,
-            }),
-            Some('5') => Ok(RecordType::S5),
-            Some('6') => Ok(RecordType::S6),
-            Some('7') => Ok(RecordType::S7),
-            Some('8') => Ok(RecordType::S8),
-            Some('9') => Ok(RecordType::S9),
-            Some(_) => Err(SRecordParseError {
-                error_type: ErrorType::InvalidRecordType,
-            }),
-            None => Err(SRecordParseError {
-                error_type: ErrorType::EolWhileParsingRecordType,
-            }),
-        },
-        Some(_) => Err(SRecordParseError {
-            error_type: ErrorType::InvalidFirstCharacter,
-        }),
-        None => Err(SRecordParseError {
-            error_type: ErrorType::EolWhileParsingRecordType,
-        }),
-    }
+pub(crate) fn parse_record_type(record_bytes: &[u8]) -> Result<RecordType, SRecordParseError> {
+    decode_record_type(record_bytes).map_err(|error_type| SRecordParseError { error_type })
 }
 
-/// Parses byte count from `record_str` and returns it, or error message
+/// Parses byte count from `record_bytes` and returns it, or error message
 #[inline]
-pub(crate) fn parse_byte_count(record_str: &str) -> Result<u8, SRecordParseError> {
-    match record_str.get(2..4) {
-        Some(byte_count_str) => match u8::from_str_radix(byte_count_str, 16) {
-            Ok(i) => Ok(i),
-            Err(_) => Err(SRecordParseError {
-                error_type: ErrorType::InvalidByteCount,
-            }),
-        },
-        None => Err(SRecordParseError {
-            error_type: ErrorType::EolWhileParsingByteCount,
-        }),
-    }
+pub(crate) fn parse_byte_count(record_bytes: &[u8]) -> Result<u8, SRecordParseError> {
+    decode_hex_byte(
+        record_bytes,
+        2,
+        ErrorType::EolWhileParsingByteCount,
+        ErrorType::InvalidByteCount,
+    )
+    .map_err(|error_type| SRecordParseError { error_type })
 }
 
-/// Parses address from `record_str` and returns it, or error message
+/// Parses address from `record_bytes` and returns it, or error message
 #[inline]
 pub(crate) fn parse_address(
-    record_str: &str,
+    record_bytes: &[u8],
     record_type: &RecordType,
 ) -> Result<u64, SRecordParseError> {
     let num_address_bytes = record_type.num_address_bytes();
-    let num_address_chars = num_address_bytes * 2;
     let address_start_index = 4;
-    let address_end_index = address_start_index + num_address_chars;
-
-    match record_str.get(address_start_index..address_end_index) {
-        Some(address_str) => match u64::from_str_radix(address_str, 16) {
-            Ok(i) => Ok(i),
-            Err(_) => Err(SRecordParseError {
-                error_type: ErrorType::InvalidAddress,
-            }),
-        },
-        None => Err(SRecordParseError {
+    let address_end_index = address_start_index + num_address_bytes * 2;
+    if record_bytes.len() < address_end_index {
+        return Err(SRecordParseError {
             error_type: ErrorType::EolWhileParsingAddress,
-        }),
+        });
+    }
+
+    let mut address = 0u64;
+    for i in 0..num_address_bytes {
+        let byte = decode_hex_byte(
+            record_bytes,
+            address_start_index + 2 * i,
+            ErrorType::EolWhileParsingAddress,
+            ErrorType::InvalidAddress,
+        )
+        .map_err(|error_type| SRecordParseError { error_type })?;
+        address = (address << 8) | byte as u64;
     }
+    Ok(address)
 }
 
 /// Parses data and sets slice inside record
@@ -82,7 +60,7 @@ pub(crate) fn parse_address(
 /// Data is written to `data`.
 #[inline]
 pub(crate) fn parse_data_and_checksum(
-    record_str: &str,
+    record_bytes: &[u8],
     record_type: &RecordType,
     byte_count: &u8,
     address: &u64,
@@ -104,15 +82,14 @@ pub(crate) fn parse_data_and_checksum(
     // Parse data
     let data_start_index = 2 + 2 + 2 * num_address_bytes; // S* + byte count + address
     let data_end_index = data_start_index + num_data_bytes * 2;
-    match record_str.get(data_start_index..data_end_index) {
-        Some(data_str) => match hex::decode_to_slice(data_str, data) {
-            Ok(_) => {}
-            Err(_) => {
+    match record_bytes.get(data_start_index..data_end_index) {
+        Some(data_bytes) => {
+            if !decode_hex(data_bytes, data) {
                 return Err(SRecordParseError {
                     error_type: ErrorType::InvalidData,
-                })
+                });
             }
-        },
+        }
         None => {
             return Err(SRecordParseError {
                 error_type: ErrorType::EolWhileParsingData,
@@ -123,21 +100,13 @@ pub(crate) fn parse_data_and_checksum(
     // Next, parse and validate checksum
     let checksum_start_index = data_end_index;
     let checksum_end_index = checksum_start_index + 2;
-    let checksum: u8 = match record_str.get(checksum_start_index..checksum_end_index) {
-        Some(checksum_str) => match u8::from_str_radix(checksum_str, 16) {
-            Ok(i) => i,
-            Err(_) => {
-                return Err(SRecordParseError {
-                    error_type: ErrorType::InvalidChecksum,
-                });
-            }
-        },
-        None => {
-            return Err(SRecordParseError {
-                error_type: ErrorType::EolWhileParsingChecksum,
-            });
-        }
-    };
+    let checksum = decode_hex_byte(
+        record_bytes,
+        checksum_start_index,
+        ErrorType::EolWhileParsingChecksum,
+        ErrorType::InvalidChecksum,
+    )
+    .map_err(|error_type| SRecordParseError { error_type })?;
     let expected_checksum = calculate_checksum(byte_count, address, data);
     if checksum != expected_checksum {
         return Err(SRecordParseError {
@@ -145,8 +114,8 @@ pub(crate) fn parse_data_and_checksum(
         });
     }
 
-    // Finally, validate that we are at the end of the record str
-    if record_str.len() != checksum_end_index {
+    // Finally, validate that we are at the end of the record
+    if record_bytes.len() != checksum_end_index {
         return Err(SRecordParseError {
             error_type: ErrorType::LineNotTerminatedAfterChecksum,
         });
@@ -155,6 +124,17 @@ pub(crate) fn parse_data_and_checksum(
     Ok(())
 }
 
+/// Iterates over the lines in `bytes`, in the same way as [`str::lines`]. Lines end with `\n` or
+/// `\r\n`, which is not included in the returned lines.
+pub(crate) fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes
+        .split_inclusive(|&byte| byte == b'\n')
+        .map(|line| match line.strip_suffix(b"\n") {
+            Some(line) => line.strip_suffix(b"\r").unwrap_or(line),
+            None => line,
+        })
+}
+
 /// Calculate the checksum for a single record (line).
 ///
 /// The checksum is calculated from the sum of all the individual bytes, from `byte_count`,
@@ -183,9 +163,353 @@ pub fn calculate_checksum(byte_count: &u8, address: &u64, data: &[u8]) -> u8 {
     0xFF - checksum.0
 }
 
+/// Validates a single record (line) given as raw ASCII bytes, and returns a summary of it.
+///
+/// Performs the same checks as [`Record::from_str`], but does not decode the data anywhere and
+/// never allocates, which makes it usable e.g. in a bootloader that validates incoming lines before
+/// buffering them.
+///
+/// # Examples
+///
+/// ```
+/// use srex::srecord::utils::validate_record_bytes;
+/// use srex::srecord::{ErrorType, RecordType};
+///
+/// let record_info = validate_record_bytes(b"S107123401020304A8").unwrap();
+/// assert_eq!(record_info.record_type, RecordType::S1);
+/// assert_eq!(record_info.address, 0x1234);
+/// assert_eq!(record_info.num_data_bytes, 4);
+/// assert_eq!(
+///     validate_record_bytes(b"S107123401020304A9").unwrap_err(),
+///     ErrorType::CalculatedChecksumNotMatchingParsedChecksum,
+/// );
+/// ```
+pub fn validate_record_bytes(record_bytes: &[u8]) -> Result<RecordInfo, ErrorType> {
+    let record_type = decode_record_type(record_bytes)?;
+
+    let byte_count = decode_hex_byte(
+        record_bytes,
+        2,
+        ErrorType::EolWhileParsingByteCount,
+        ErrorType::InvalidByteCount,
+    )?;
+    let mut checksum = Wrapping(byte_count);
+
+    let num_address_bytes = record_type.num_address_bytes();
+    let mut address = 0u64;
+    for i in 0..num_address_bytes {
+        let byte = decode_hex_byte(
+            record_bytes,
+            4 + 2 * i,
+            ErrorType::EolWhileParsingAddress,
+            ErrorType::InvalidAddress,
+        )?;
+        address = (address << 8) | byte as u64;
+        checksum += byte;
+    }
+
+    let num_data_bytes = match (byte_count as usize).checked_sub(num_address_bytes + 1) {
+        Some(i) => i,
+        None => return Err(ErrorType::ByteCountTooLowForRecordType),
+    };
+    let data_start_index = 4 + 2 * num_address_bytes;
+    for i in 0..num_data_bytes {
+        checksum += decode_hex_byte(
+            record_bytes,
+            data_start_index + 2 * i,
+            ErrorType::EolWhileParsingData,
+            ErrorType::InvalidData,
+        )?;
+    }
+
+    let checksum_index = data_start_index + 2 * num_data_bytes;
+    let parsed_checksum = decode_hex_byte(
+        record_bytes,
+        checksum_index,
+        ErrorType::EolWhileParsingChecksum,
+        ErrorType::InvalidChecksum,
+    )?;
+    if parsed_checksum != 0xFF - checksum.0 {
+        return Err(ErrorType::CalculatedChecksumNotMatchingParsedChecksum);
+    }
+    if record_bytes.len() != checksum_index + 2 {
+        return Err(ErrorType::LineNotTerminatedAfterChecksum);
+    }
+    check_data_end_address(&record_type, address, num_data_bytes)?;
+
+    Ok(RecordInfo {
+        record_type,
+        address,
+        num_data_bytes,
+    })
+}
+
+/// Decodes the record type (S*) at the start of `record_bytes`.
+#[inline]
+fn decode_record_type(record_bytes: &[u8]) -> Result<RecordType, ErrorType> {
+    match record_bytes.first() {
+        Some(b'S') => match record_bytes.get(1) {
+            Some(b'0') => Ok(RecordType::S0),
+            Some(b'1') => Ok(RecordType::S1),
+            Some(b'2') => Ok(RecordType::S2),
+            Some(b'3') => Ok(RecordType::S3),
+            Some(b'4') => Err(ErrorType::S4Reserved),
+            Some(b'5') => Ok(RecordType::S5),
+            Some(b'6') => Ok(RecordType::S6),
+            Some(b'7') => Ok(RecordType::S7),
+            Some(b'8') => Ok(RecordType::S8),
+            Some(b'9') => Ok(RecordType::S9),
+            Some(_) => Err(ErrorType::InvalidRecordType),
+            None => Err(ErrorType::EolWhileParsingRecordType),
+        },
+        Some(_) => Err(ErrorType::InvalidFirstCharacter),
+        None => Err(ErrorType::EolWhileParsingRecordType),
+    }
+}
+
+/// Checks that `num_data_bytes` bytes of data at `address` do not extend past the highest address
+/// of `record_type`, e.g. 0xFFFF for S1. Only data records (S1/S2/S3) are checked.
+#[inline]
+pub(crate) fn check_data_end_address(
+    record_type: &RecordType,
+    address: u64,
+    num_data_bytes: usize,
+) -> Result<(), ErrorType> {
+    match matches!(
+        record_type,
+        RecordType::S1 | RecordType::S2 | RecordType::S3
+    ) && address + num_data_bytes as u64 > 1 << (8 * record_type.num_address_bytes())
+    {
+        true => Err(ErrorType::AddressOverflowForRecordType),
+        false => Ok(()),
+    }
+}
+
+/// Decodes the two hex characters at `index` in `record_bytes` into a byte. Returns `eol_error` if
+/// `record_bytes` ends first, or `invalid_error` if the characters are not hex digits.
+#[inline]
+fn decode_hex_byte(
+    record_bytes: &[u8],
+    index: usize,
+    eol_error: ErrorType,
+    invalid_error: ErrorType,
+) -> Result<u8, ErrorType> {
+    match record_bytes.get(index..index + 2) {
+        Some(&[high, low]) => {
+            let (high, low) = (
+                HEX_DECODE_TABLE[high as usize],
+                HEX_DECODE_TABLE[low as usize],
+            );
+            match (high | low) & INVALID_HEX_DIGIT {
+                0 => Ok((high << 4) | low),
+                _ => Err(invalid_error),
+            }
+        }
+        _ => Err(eol_error),
+    }
+}
+
+/// Upper case hex digits, by value.
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Marks characters in [`HEX_DECODE_TABLE`] that are not hex digits. Does not overlap the bits of
+/// any digit value.
+const INVALID_HEX_DIGIT: u8 = 0x80;
+
+/// Value of every hex digit (upper or lower case) by ASCII character, or [`INVALID_HEX_DIGIT`].
+const HEX_DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID_HEX_DIGIT; 256];
+    let mut i = 0;
+    while i < 16 {
+        table[HEX_DIGITS[i] as usize] = i as u8;
+        table[HEX_DIGITS[i].to_ascii_lowercase() as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Decodes pairs of hex digits in `hex_bytes` into `data`, which must be half as long. Returns
+/// `false` if any character is not a hex digit.
+#[cfg(not(feature = "hex"))]
+#[inline]
+pub(crate) fn decode_hex(hex_bytes: &[u8], data: &mut [u8]) -> bool {
+    // Invalid characters are accumulated and checked once, to keep the loop free of branches
+    let mut invalid = 0;
+    for (byte, digits) in data.iter_mut().zip(hex_bytes.chunks_exact(2)) {
+        let high = HEX_DECODE_TABLE[digits[0] as usize];
+        let low = HEX_DECODE_TABLE[digits[1] as usize];
+        invalid |= high | low;
+        *byte = (high << 4) | low;
+    }
+    invalid & INVALID_HEX_DIGIT == 0
+}
+
+/// Decodes pairs of hex digits in `hex_bytes` into `data`, which must be half as long. Returns
+/// `false` if any character is not a hex digit.
+#[cfg(feature = "hex")]
+#[inline]
+pub(crate) fn decode_hex(hex_bytes: &[u8], data: &mut [u8]) -> bool {
+    hex::decode_to_slice(hex_bytes, data).is_ok()
+}
+
+/// Encodes `data` as upper case hex digits.
+#[cfg(not(feature = "hex"))]
+#[inline]
+pub(crate) fn encode_hex_upper(data: &[u8]) -> String {
+    let mut hex_str = String::with_capacity(2 * data.len());
+    for byte in data.iter() {
+        hex_str.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        hex_str.push(HEX_DIGITS[(byte & 0x0F) as usize] as char);
+    }
+    hex_str
+}
+
+/// Encodes `data` as upper case hex digits.
+#[cfg(feature = "hex")]
+#[inline]
+pub(crate) fn encode_hex_upper(data: &[u8]) -> String {
+    hex::encode_upper(data)
+}
+
+/// Calculates the CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320) of `data`.
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data
+        .iter()
+        .fold(0xFFFFFFFF, |crc, &byte| crc32_update(crc, byte))
+}
+
+/// Updates the running CRC-32 `crc` with `byte`. The initial value is `0xFFFFFFFF`, and the final
+/// CRC is the inverted running CRC.
+#[inline]
+pub(crate) fn crc32_update(mut crc: u32, byte: u8) -> u32 {
+    crc ^= byte as u32;
+    for _ in 0..8 {
+        crc = if crc & 1 != 0 {
+            (crc >> 1) ^ 0xEDB88320
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
+
+/// Sorts `ranges`, drops empty ranges and merges overlapping or adjacent ranges.
+pub(crate) fn normalized(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.retain(|range| range.start < range.end);
+    ranges.sort_by_key(|range| range.start);
+    let mut normalized_ranges: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match normalized_ranges.last_mut() {
+            Some(last_range) if range.start <= last_range.end => {
+                last_range.end = max(last_range.end, range.end);
+            }
+            _ => normalized_ranges.push(range),
+        }
+    }
+    normalized_ranges
+}
+
+/// Returns the parts of `data`, starting at `address`, that are inside the normalized list
+/// `ranges`, together with their addresses.
+pub(crate) fn data_within<'a>(
+    address: u64,
+    data: &'a [u8],
+    ranges: &'a [Range<u64>],
+) -> impl Iterator<Item = (u64, &'a [u8])> + 'a {
+    let end_address = address + data.len() as u64;
+    let first_index = ranges.partition_point(|range| range.end <= address);
+    ranges[first_index..]
+        .iter()
+        .take_while(move |range| range.start < end_address)
+        .map(move |range| {
+            let start = max(range.start, address);
+            let end = min(range.end, end_address);
+            (
+                start,
+                &data[(start - address) as usize..(end - address) as usize],
+            )
+        })
+}
+
+/// Returns the normalized union of the sorted, non-overlapping lists `a` and `b`. Empty ranges are
+/// dropped.
+pub(crate) fn union(a: &[Range<u64>], b: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut ranges: Vec<Range<u64>> = Vec::with_capacity(a.len() + b.len());
+    let (mut index_a, mut index_b) = (0, 0);
+    while index_a < a.len() || index_b < b.len() {
+        // Take the range that starts first, merging it into the last range if they touch
+        let range =
+            if index_b == b.len() || (index_a < a.len() && a[index_a].start <= b[index_b].start) {
+                index_a += 1;
+                a[index_a - 1].clone()
+            } else {
+                index_b += 1;
+                b[index_b - 1].clone()
+            };
+        match ranges.last_mut() {
+            _ if range.is_empty() => {}
+            Some(last_range) if range.start <= last_range.end => {
+                last_range.end = max(last_range.end, range.end);
+            }
+            _ => ranges.push(range),
+        }
+    }
+    ranges
+}
+
+/// Returns the address ranges contained in both of the normalized lists `a` and `b`.
+pub(crate) fn intersection(a: &[Range<u64>], b: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut ranges = Vec::new();
+    let (mut index_a, mut index_b) = (0, 0);
+    while index_a < a.len() && index_b < b.len() {
+        let (range_a, range_b) = (&a[index_a], &b[index_b]);
+        let start_address = max(range_a.start, range_b.start);
+        let end_address = min(range_a.end, range_b.end);
+        if start_address < end_address {
+            ranges.push(start_address..end_address);
+        }
+        // Advance past the range that ends first, as it cannot intersect any later range
+        if range_a.end <= range_b.end {
+            index_a += 1;
+        } else {
+            index_b += 1;
+        }
+    }
+    ranges
+}
+
+/// Returns the address ranges of the normalized list `a` that are not in the normalized list `b`.
+pub(crate) fn difference(a: &[Range<u64>], b: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut ranges = Vec::new();
+    let mut index_b = 0;
+    for range_a in a.iter() {
+        let mut address = range_a.start;
+        // Skip the ranges that end first, as they cannot overlap this or any later range of `a`
+        while index_b < b.len() && b[index_b].end <= address {
+            index_b += 1;
+        }
+        for range_b in b[index_b..]
+            .iter()
+            .take_while(|range_b| range_b.start < range_a.end)
+        {
+            if range_b.start > address {
+                ranges.push(address..range_b.start);
+            }
+            address = max(address, range_b.end);
+        }
+        if address < range_a.end {
+            ranges.push(address..range_a.end);
+        }
+    }
+    ranges
+}
+
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use super::*;
+    use crate::srecord::Record;
 
     #[test]
     fn test_calculate_checksum() {
@@ -243,4 +567,126 @@ mod tests {
         assert_eq!(calculate_checksum(&0x03, &0x0003, &[]), 0xF9);
         assert_eq!(calculate_checksum(&0x03, &0x0000, &[]), 0xFC);
     }
+
+    #[test]
+    fn test_validate_record_bytes() {
+        let mut data_buffer = [0u8; 256];
+        for record_str in [
+            "S00F000068656C6C6F202020202000003C",
+            "S107123401020304A8",
+            "S107123401020304a8",
+            "S20A12345601020304050644",
+            "S30B12345678010203040506CB",
+            "S5031234B6",
+            "S6041234565F",
+            "S70512345678E6",
+            "S8041234565F",
+            "S9031234B6",
+            "",
+            "X107123401020304A8",
+            "S",
+            "S4031234B6",
+            "SA031234B6",
+            "S1",
+            "S1G7123401020304A8",
+            "S107",
+            "S10712G401020304A8",
+            "S10712340102",
+            "S1071234010203G4A8",
+            "S10712340102030",
+            "S107123401020304",
+            "S107123401020304G8",
+            "S107123401020304A9",
+            "S107123401020304A8F",
+            "S1021234B6",
+            "S105FFFFAABB97",
+            "S206FFFFFFAABB97",
+            "S307FFFFFFFFAABB97",
+            "S105FFFEAABB98",
+        ] {
+            let expected = Record::from_str(record_str, &mut data_buffer);
+            let record_info = validate_record_bytes(record_str.as_bytes());
+            match expected {
+                Ok(record) => {
+                    let record_info = record_info.unwrap();
+                    let expected_type = match record {
+                        Record::S0Record(_) => RecordType::S0,
+                        Record::S1Record(_) => RecordType::S1,
+                        Record::S2Record(_) => RecordType::S2,
+                        Record::S3Record(_) => RecordType::S3,
+                        Record::S4Record(_) => unreachable!("S4 records are reserved"),
+                        Record::S5Record(_) => RecordType::S5,
+                        Record::S6Record(_) => RecordType::S6,
+                        Record::S7Record(_) => RecordType::S7,
+                        Record::S8Record(_) => RecordType::S8,
+                        Record::S9Record(_) => RecordType::S9,
+                    };
+                    assert_eq!(record_info.record_type, expected_type, "{record_str}");
+                }
+                Err(error) => assert_eq!(record_info, Err(error.error_type), "{record_str}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(&[]), 0x00000000);
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414FA339
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(normalized(vec![]), []);
+        assert_eq!(
+            normalized(vec![8..10, 0..2, 2..4, 3..5, 6..6]),
+            [0..5, 8..10]
+        );
+    }
+
+    #[test]
+    fn test_union() {
+        assert_eq!(union(&[0..2, 8..10], &[2..4, 5..5, 6..9]), [0..4, 6..10]);
+        assert_eq!(union(&[], &[2..4]), [2..4]);
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(intersection(&[0..10], &[2..4, 8..12]), [2..4, 8..10]);
+        assert_eq!(intersection(&[0..2], &[2..4]), []);
+        assert_eq!(intersection(&[0..4, 6..10], &[2..8]), [2..4, 6..8]);
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(difference(&[0..10], &[2..4, 8..12]), [0..2, 4..8]);
+        assert_eq!(difference(&[0..10], &[]), [0..10]);
+        assert_eq!(difference(&[2..4], &[0..10]), []);
+        assert_eq!(
+            difference(&[0..4, 6..10, 12..14], &[2..7, 9..13]),
+            [0..2, 7..9, 13..14]
+        );
+        assert_eq!(difference(&[0..2, 4..6], &[2..4, 6..8]), [0..2, 4..6]);
+        assert_eq!(difference(&[], &[0..10]), []);
+    }
+
+    #[test]
+    fn test_hex_codec() {
+        let data: Vec<u8> = (0..=255).collect();
+        let hex_str = encode_hex_upper(&data);
+        assert_eq!(hex_str, hex::encode_upper(&data));
+
+        let mut decoded = [0u8; 256];
+        assert!(decode_hex(hex_str.as_bytes(), &mut decoded));
+        assert_eq!(decoded, data.as_slice());
+        assert!(decode_hex(hex_str.to_lowercase().as_bytes(), &mut decoded));
+        assert_eq!(decoded, data.as_slice());
+
+        let mut decoded = [0u8; 2];
+        assert!(!decode_hex(b"0G12", &mut decoded));
+        assert!(!decode_hex(b"12\xB0\x30", &mut decoded));
+    }
 }
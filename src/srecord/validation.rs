@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+use crate::srecord::error::{SerializationError, StartAddressError};
+use crate::srecord::SerializationOptions;
+
+#[cfg(doc)]
+use crate::srecord::{DataChunk, SRecordFile};
+
+/// Options controlling which checks [`SRecordFile::validate`] does.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// Options the file is checked to be serializable with, e.g. whether all data addresses fit
+    /// in the selected [`data_record_type`](SerializationOptions::data_record_type) and whether
+    /// the number of data records fits in the selected
+    /// [`count_record_type`](SerializationOptions::count_record_type).
+    pub serialization_options: SerializationOptions,
+    /// If `true`, a file without header data is reported as [`ValidationIssue::MissingHeader`].
+    pub require_header: bool,
+    /// If `true`, a file without start address is reported as
+    /// [`ValidationIssue::MissingStartAddress`].
+    pub require_start_address: bool,
+}
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The file can be serialized, but is likely not what was intended.
+    Warning,
+    /// The file cannot be serialized as is, or is broken.
+    Error,
+}
+
+/// A problem found by [`SRecordFile::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Data of a [`DataChunk`] extends past address `u64::MAX`. Contains the index of the data
+    /// chunk.
+    DataChunkAddressOverflow(usize),
+    /// A [`DataChunk`] starts before the data chunk before it. Contains the index of the data
+    /// chunk.
+    UnsortedDataChunks(usize),
+    /// Two [`DataChunk`]s overlap. Contains the overlapping address range.
+    OverlappingDataChunks(Range<u64>),
+    /// A [`DataChunk`] contains no data. Contains the index of the data chunk.
+    EmptyDataChunk(usize),
+    /// The file contains no data.
+    NoData,
+    /// The file has no header data, and [`ValidationOptions::require_header`] is `true`.
+    MissingHeader,
+    /// The file has no start address, and [`ValidationOptions::require_start_address`] is `true`.
+    MissingStartAddress,
+    /// The start address is invalid, see [`SRecordFile::validate_start_address`].
+    StartAddress(StartAddressError),
+    /// The file cannot be serialized with [`ValidationOptions::serialization_options`].
+    Serialization(SerializationError),
+}
+
+impl ValidationIssue {
+    /// Returns how serious the issue is.
+    ///
+    /// A start address without data is a [`Severity::Warning`], since some toolchains write a
+    /// placeholder start address, as are empty data chunks and files without data. All other
+    /// issues are [`Severity::Error`]s.
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::EmptyDataChunk(_)
+            | ValidationIssue::NoData
+            | ValidationIssue::StartAddress(StartAddressError::NotInData(_)) => Severity::Warning,
+            ValidationIssue::DataChunkAddressOverflow(_)
+            | ValidationIssue::UnsortedDataChunks(_)
+            | ValidationIssue::OverlappingDataChunks(_)
+            | ValidationIssue::MissingHeader
+            | ValidationIssue::MissingStartAddress
+            | ValidationIssue::StartAddress(StartAddressError::TooLarge(_))
+            | ValidationIssue::Serialization(_) => Severity::Error,
+        }
+    }
+}
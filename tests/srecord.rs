@@ -1,3 +1,5 @@
+#![allow(clippy::single_range_in_vec_init)]
+
 use std::{fs, str::FromStr};
 
 use srex::srecord::*;
@@ -11,6 +13,8 @@ fn test_srecord_file_new() {
 }
 
 #[test]
+// Exercises `Default` through a generic caller, as it was originally written
+#[allow(clippy::unnecessary_literal_unwrap)]
 fn test_srecord_file_default() {
     let srecord_file = Option::<SRecordFile>::None.unwrap_or_default();
     assert_eq!(srecord_file.header_data, None);
@@ -18,6 +22,33 @@ fn test_srecord_file_default() {
     assert_eq!(srecord_file.start_address, None);
 }
 
+#[test]
+fn test_srecord_file_with_capacity() {
+    let mut srecord_file = SRecordFile::with_capacity(4, 0x40);
+    assert_eq!(srecord_file, SRecordFile::new());
+    assert!(srecord_file.data_chunks.capacity() >= 4);
+
+    // Data written in address order fills the reserved bytes of its chunk
+    srecord_file.write_u32_le(0x3000, 0x03020100);
+    let data_ptr = srecord_file.data_chunks[0].data.as_ptr();
+    for address in (0x3004..0x3040).step_by(4) {
+        srecord_file.write_u32_le(address, 0);
+    }
+    assert_eq!(srecord_file.data_chunks[0].len(), 0x40);
+    assert_eq!(srecord_file.data_chunks[0].data.as_ptr(), data_ptr);
+    srecord_file.data_chunks.clear();
+
+    for address in [0x1000, 0x2000] {
+        let mut data_chunk = DataChunk::with_capacity(address, 64);
+        assert!(data_chunk.data.capacity() >= 64);
+        data_chunk.data.extend(0..64);
+        srecord_file.data_chunks.push(data_chunk);
+    }
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.get(0x203F), Some(&63));
+    assert_eq!(srecord_file.get(0x2040), None);
+}
+
 #[test]
 fn test_srecord_file_from_str() {
     let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
@@ -43,6 +74,83 @@ fn test_srecord_file_from_str() {
     assert_eq!(srecord_file.start_address, Some(0));
 }
 
+#[test]
+fn test_srecord_file_from_bytes() {
+    let srecord_bytes = fs::read("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let from_bytes = SRecordFile::from_bytes(&srecord_bytes).unwrap();
+    let from_str = SRecordFile::from_str(&srecord_str).unwrap();
+    assert_eq!(from_bytes.header_data, from_str.header_data);
+    assert_eq!(from_bytes.data_chunks, from_str.data_chunks);
+    assert_eq!(from_bytes.start_address, from_str.start_address);
+
+    // Bytes that are not ASCII are invalid records, not UTF-8 errors
+    let error = SRecordFile::from_bytes(b"S107100000010203E2\n\xFF9031000EC").unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidFirstCharacter);
+    let error = SRecordFile::from_bytes(b"S1071000\xC3\xA9010203E2").unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidData);
+}
+
+#[test]
+fn test_srecord_file_from_bytes_lossy() {
+    let mut srecord_bytes = fs::read("tests/srec_files/wikipedia.s19").unwrap();
+    srecord_bytes.splice(0..0, b"\x00\xF0\x9F\xA6\x80 connected\r\n".iter().copied());
+    srecord_bytes.extend_from_slice(b"\xC3\xA9\n");
+    let (srecord_file, stats) =
+        SRecordFile::from_bytes_lossy(&srecord_bytes, &ParseOptions::default()).unwrap();
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    assert_eq!(
+        srecord_file.data_chunks,
+        SRecordFile::from_str(&srecord_str).unwrap().data_chunks
+    );
+    assert_eq!(stats.num_records, 8);
+    assert_eq!(
+        stats.warnings,
+        [
+            ParseWarning {
+                line_index: 0,
+                error_type: ErrorType::NonAsciiLine,
+            },
+            ParseWarning {
+                line_index: 7,
+                error_type: ErrorType::NonAsciiLine,
+            },
+        ]
+    );
+
+    let mut strict = ParseOptions::default();
+    strict.strict = true;
+    assert_eq!(
+        SRecordFile::from_bytes_lossy(&srecord_bytes, &strict)
+            .unwrap_err()
+            .error_type,
+        ErrorType::NonAsciiLine
+    );
+    // Lines that are ASCII must still be valid records
+    assert_eq!(
+        SRecordFile::from_bytes_lossy(b"S107100000010203E2\nnoise", &ParseOptions::default())
+            .unwrap_err()
+            .error_type,
+        ErrorType::InvalidFirstCharacter
+    );
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_srecord_file_from_mmap() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let from_mmap = SRecordFile::from_mmap("tests/srec_files/wikipedia.s19").unwrap();
+    let from_str = SRecordFile::from_str(&srecord_str).unwrap();
+    assert_eq!(from_mmap.header_data, from_str.header_data);
+    assert_eq!(from_mmap.data_chunks, from_str.data_chunks);
+    assert_eq!(from_mmap.start_address, from_str.start_address);
+
+    assert!(matches!(
+        SRecordFile::from_mmap("tests/srec_files/missing.s19"),
+        Err(ReadFileError::Io(_))
+    ));
+}
+
 #[test]
 fn test_parse_srecord_unsorted_data() {
     let srecord_str = fs::read_to_string("tests/srec_files/unsorted.s28").unwrap();
@@ -77,11 +185,335 @@ fn test_parse_srecord_multiple_data_chunks() {
         assert_eq!(data_chunk.end_address(), expected_end_address);
     }
 }
+#[test]
+fn test_parse_srecord_empty() {
+    let srecord_file = SRecordFile::from_str("").unwrap();
+    assert!(srecord_file.data_chunks.is_empty());
+}
+
+#[test]
+fn test_parse_srecord_out_of_order() {
+    let srecord_str = "S1041006AA3B\nS1041002AA3F\nS1041003AA3E\nS1041000AA41\nS1041001AA40";
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data, [0xAA; 4]);
+    assert_eq!(srecord_file.data_chunks[1].address, 0x1006);
+
+    // Overlaps between out of order records are found when merging
+    let srecord_str = "S1041003AA3E\nS1041000AA41\nS1041001AA40\nS1041000AA41";
+    assert_eq!(
+        SRecordFile::from_str(srecord_str).unwrap_err().error_type,
+        ErrorType::OverlappingData
+    );
+}
+
+#[test]
+fn test_parse_srecord_gap_fill() {
+    let srecord_str = "S1041006AA3B\nS1041000AA41\nS1041002AA3F\nS1041010AA31";
+    let mut options = ParseOptions::default();
+    options.gap_fill = Some(GapFill {
+        max_gap: 3,
+        value: 0xFF,
+    });
+    let (srecord_file, stats) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert_eq!(stats.num_data_records, 4);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(
+        srecord_file.data_chunks[0].data,
+        [0xAA, 0xFF, 0xAA, 0xFF, 0xFF, 0xFF, 0xAA]
+    );
+    assert_eq!(srecord_file.data_chunks[1].address, 0x1010);
+
+    // Gaps may be filled by later records
+    let srecord_str = "S1041000BB30\nS1041004BB2C\nS1041002BB2E\nS1041006BB2A";
+    let (srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(
+        srecord_file.data_chunks[0].data,
+        [0xBB, 0xFF, 0xBB, 0xFF, 0xBB, 0xFF, 0xBB]
+    );
+}
+
+#[test]
+fn test_parse_srecord_address_filter() {
+    let srecord_str = "S107100000010203E2\nS1051004AABB81\nS1052000CCDD31\nS5030003F9\nS9031000EC";
+    let mut options = ParseOptions::default();
+    options.address_filter = Some(vec![0x1005..0x1010, 0x0000..0x1002, 0x1001..0x1003]);
+    options.record_layout = true;
+    let (srecord_file, stats) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert_eq!(stats.num_data_records, 3);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file[0x1000..0x1003], [0x00, 0x01, 0x02]);
+    assert_eq!(srecord_file[0x1005..0x1006], [0xBB]);
+    assert!(srecord_file.get(0x1003).is_none());
+    assert!(srecord_file.get(0x2000).is_none());
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+    assert_eq!(
+        srecord_file.record_layout().unwrap().data_records,
+        [0x1000..0x1003, 0x1005..0x1006]
+    );
+
+    // Records outside the filter are still validated
+    let srecord_str = "S107100000010203E2\nS1052000CCDD00";
+    assert_eq!(
+        SRecordFile::from_str_with_options(srecord_str, &options)
+            .unwrap_err()
+            .error_type,
+        ErrorType::CalculatedChecksumNotMatchingParsedChecksum
+    );
+
+    // Large enough to be parsed in several batches when parsing in parallel
+    let mut srecord_str = String::new();
+    for i in 0..100000u64 {
+        let address = i * 16;
+        let data = [i as u8; 16];
+        let checksum = utils::calculate_checksum(&0x15, &address, &data);
+        srecord_str.push_str(&format!(
+            "S315{address:08X}{}{checksum:02X}\n",
+            hex::encode_upper(data)
+        ));
+    }
+    let address_filter = vec![0x1008..0x1F00, 0x80000..0x80001, 0x180000..0x200000];
+    let mut options = ParseOptions::default();
+    options.address_filter = Some(address_filter.clone());
+    let (srecord_file, stats) = SRecordFile::from_str_with_options(&srecord_str, &options).unwrap();
+    assert_eq!(stats.num_data_records, 100000);
+    let expected = SRecordFile::from_str(&srecord_str)
+        .unwrap()
+        .cropped(&address_filter);
+    assert_eq!(srecord_file.data_chunks, expected.data_chunks);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+}
+
+#[test]
+fn test_parse_srecord_large() {
+    // Large enough to be parsed in several batches when parsing in parallel
+    let mut srecord_str = String::from("S00600004844521B\n");
+    let num_rows: u64 = 100000;
+    for i in 0..num_rows {
+        // Every 1000th record is out of order
+        let address = match i % 1000 {
+            999 => (i - 999) * 16 + 0x1000000,
+            _ => i * 16,
+        };
+        let data = [i as u8; 16];
+        let checksum = utils::calculate_checksum(&0x15, &address, &data);
+        srecord_str.push_str(&format!(
+            "S315{address:08X}{}{checksum:02X}\n",
+            hex::encode_upper(data)
+        ));
+    }
+    srecord_str.push_str("S70500000000FA\n");
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (srecord_file, stats) = SRecordFile::from_str_with_options(&srecord_str, &options).unwrap();
+    assert_eq!(stats.num_records, num_rows as usize + 2);
+    assert_eq!(stats.num_data_records, num_rows as usize);
+    assert_eq!(srecord_file.header_data, Some(b"HDR".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0));
+    assert_eq!(srecord_file.data_chunks.len(), 100 + 100);
+    assert_eq!(srecord_file[0x10..0x11], [1]);
+    assert_eq!(srecord_file[0x1000000..0x1000001], [231]);
+    assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+
+    // Problems are reported for the correct line
+    let srecord_str = srecord_str.replace("S70500000000FA\n", "S70500000000FA\nS30602000000AA4D\n");
+    let (_, stats) = SRecordFile::from_str_with_options(&srecord_str, &options).unwrap();
+    assert_eq!(
+        stats.warnings,
+        [ParseWarning {
+            line_index: num_rows as usize + 2,
+            error_type: ErrorType::RecordAfterTerminator,
+        }]
+    );
+}
+
+#[test]
+fn test_srecord_parser() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_str = srecord_str.replace('\n', "\r\n");
+    let expected = SRecordFile::from_str(&srecord_str).unwrap();
+    for fragment_size in [1, 7, 64, srecord_str.len()] {
+        let mut options = ParseOptions::default();
+        options.record_layout = true;
+        let mut parser = SRecordParser::with_options(options);
+        for fragment in srecord_str.as_bytes().chunks(fragment_size) {
+            parser.push_bytes(fragment).unwrap();
+        }
+        let (srecord_file, stats) = parser.finish_with_stats().unwrap();
+        assert_eq!(srecord_file.data_chunks, expected.data_chunks);
+        assert_eq!(srecord_file.header_data, expected.header_data);
+        assert_eq!(srecord_file.start_address, expected.start_address);
+        assert_eq!(stats.num_records, 6);
+        assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+    }
+
+    // The last line does not need a line ending
+    let mut parser = SRecordParser::new();
+    parser.push_bytes(b"S107100000010203E2\nS9031").unwrap();
+    parser.push_bytes(b"000EC").unwrap();
+    assert_eq!(parser.stats().num_records, 1);
+    assert_eq!(parser.finish().unwrap().start_address, Some(0x1000));
+
+    // Whole lines cannot be pushed while an incomplete line is pending
+    let mut parser = SRecordParser::new();
+    parser.push_bytes(b"S10710000001").unwrap();
+    assert_eq!(
+        parser.push_line("S1051004AABB81").unwrap_err().error_type,
+        ErrorType::IncompleteLinePending
+    );
+    parser.push_bytes(b"0203E2\n").unwrap();
+    parser.push_line("S1051004AABB81").unwrap();
+    let srecord_file = parser.finish().unwrap();
+    assert_eq!(
+        srecord_file[0x1000..0x1006],
+        [0x00, 0x01, 0x02, 0x03, 0xAA, 0xBB]
+    );
+
+    // Errors are returned as soon as the line is complete
+    let mut options = ParseOptions::default();
+    options.strict = true;
+    let mut parser = SRecordParser::with_options(options);
+    parser.push_line("S9031000EC").unwrap();
+    assert_eq!(
+        parser.push_bytes(b"S107100000010203E2\n").unwrap_err(),
+        SRecordParseError {
+            error_type: ErrorType::RecordAfterTerminator
+        }
+    );
+    let mut parser = SRecordParser::new();
+    parser.push_bytes(b"S107100000010203E3").unwrap();
+    assert_eq!(
+        parser.finish().unwrap_err().error_type,
+        ErrorType::CalculatedChecksumNotMatchingParsedChecksum
+    );
+}
+
+#[test]
+fn test_srecord_parser_count_record() {
+    let mut parser = SRecordParser::new();
+    let options = |count_record_type| {
+        let mut options = SerializationOptions::default();
+        options.count_record_type = count_record_type;
+        options
+    };
+    assert_eq!(
+        parser.expected_count_record_value(&options(CountRecordType::Omit)),
+        Ok(None)
+    );
+
+    for i in 0..0x10000u64 {
+        let address = i * 2;
+        let checksum = utils::calculate_checksum(&0x06, &address, &[0xAA]);
+        parser
+            .push_line(&format!("S306{address:08X}AA{checksum:02X}"))
+            .unwrap();
+    }
+    let count_record = CountRecord {
+        record_count: 0x10000,
+    };
+    assert_eq!(
+        parser.expected_count_record_value(&options(CountRecordType::Auto)),
+        Ok(Some(Record::S6Record(count_record)))
+    );
+    assert_eq!(
+        parser.expected_count_record_value(&options(CountRecordType::S6)),
+        Ok(Some(Record::S6Record(count_record)))
+    );
+    assert_eq!(
+        parser.expected_count_record_value(&options(CountRecordType::S5)),
+        Err(SerializationError::RecordCountTooLarge(0x10000))
+    );
+    assert_eq!(parser.verify_count(0x10000), Ok(()));
+    assert_eq!(
+        parser.verify_count(0xFFFF).unwrap_err().error_type,
+        ErrorType::CalculatedNumRecordsNotMatchingParsedNumRecords
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_srecord_document() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let document = SRecordDocument::from_str(&srecord_str).unwrap();
+    assert_eq!(document.records.len(), 6);
+    assert!(matches!(document.records[0], OwnedRecord::S0Record(_)));
+    assert_eq!(
+        document.records[5],
+        OwnedRecord::S9Record(StartAddressRecord { start_address: 0 })
+    );
+    assert_eq!(document.serialize(), srecord_str);
+
+    let (srecord_file, stats) = document.to_srecord_file(&ParseOptions::default()).unwrap();
+    assert_eq!(
+        srecord_file.data_chunks,
+        SRecordFile::from_str(&srecord_str).unwrap().data_chunks
+    );
+    assert_eq!(stats.num_data_records, 3);
+
+    let mut options = SerializationOptions::default();
+    options.data_record_size = 0x1C;
+    options.data_record_type = DataRecordType::S1;
+    assert_eq!(
+        SRecordDocument::from_srecord_file(&srecord_file, &options).unwrap(),
+        document
+    );
+
+    // Unchanged records are written exactly as parsed
+    let srecord_str = srecord_str.to_lowercase().replace("s", "S");
+    let mut lowercase_document = SRecordDocument::from_str(&srecord_str).unwrap();
+    assert_eq!(lowercase_document, document);
+    assert_eq!(lowercase_document.serialize(), srecord_str);
+    lowercase_document.records.remove(2);
+    let mut lines: Vec<&str> = srecord_str.lines().collect();
+    lines.remove(2);
+    let serialized = lowercase_document.serialize();
+    let serialized_lines: Vec<&str> = serialized.lines().collect();
+    assert_eq!(serialized_lines[..2], lines[..2]);
+    assert_eq!(
+        serialized_lines[2..],
+        [
+            lines[2].to_uppercase(),
+            lines[3].to_uppercase(),
+            lines[4].to_uppercase()
+        ]
+    );
+
+    // Records are validated against each other when converted to an SRecordFile
+    let mut document = document;
+    document.records.swap(3, 4);
+    assert_eq!(
+        document
+            .to_srecord_file(&ParseOptions::default())
+            .unwrap_err()
+            .error_type,
+        ErrorType::CalculatedNumRecordsNotMatchingParsedNumRecords
+    );
+}
+
 #[test]
 fn test_parse_srecord_error() {
     assert!(SRecordFile::from_str("S").is_err());
 }
 
+#[test]
+fn test_parse_srecord_address_overflow() {
+    let mut data_buffer = [0u8; 256];
+    for record_str in ["S105FFFEAABB98", "S206FFFFFEAABB98", "S307FFFFFFFEAABB98"] {
+        assert!(Record::from_str(record_str, &mut data_buffer).is_ok());
+    }
+    for record_str in ["S105FFFFAABB97", "S206FFFFFFAABB97", "S307FFFFFFFFAABB97"] {
+        assert_eq!(
+            Record::from_str(record_str, &mut data_buffer),
+            Err(SRecordParseError {
+                error_type: ErrorType::AddressOverflowForRecordType
+            })
+        );
+    }
+}
+
 #[test]
 fn test_parse_srecord_multiple_start_addresses() {
     let srecord_str = fs::read_to_string("tests/srec_files/multiple_start_addresses.s19").unwrap();
@@ -167,9 +599,3603 @@ fn test_serialize_from_str() {
     let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s37").unwrap();
     let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
     let mut serialized_str = String::new();
-    for record in srecord_file.iter_records(0x1C) {
+    for record in srecord_file.iter_records(0x1C).unwrap() {
         serialized_str.push_str(record.serialize().as_str());
         serialized_str.push('\n');
     }
     assert_eq!(serialized_str, srecord_str);
 }
+
+#[test]
+fn test_serialize_from_str_s19() {
+    // S19 files are reproduced with a matching S9 start address record
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut options = SerializationOptions::default();
+    options.data_record_size = 0x1C;
+    options.data_record_type = DataRecordType::S1;
+    let mut serialized_str = String::new();
+    for record in srecord_file.iter_records_with_options(&options).unwrap() {
+        serialized_str.push_str(record.serialize().as_str());
+        serialized_str.push('\n');
+    }
+    assert_eq!(serialized_str, srecord_str);
+}
+
+#[test]
+fn test_serialize_preserve_layout() {
+    let srecord_str = "S107100000010203E2\n\
+                       S10B0FF0F0F1F2F3F4F5F6F759\n\
+                       S1091004040506070809BB\n\
+                       S5030003F9\n";
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert_eq!(
+        srecord_file.record_layout().unwrap().data_records,
+        [0x1000..0x1004, 0x0FF0..0x0FF8, 0x1004..0x100A]
+    );
+    let serialize = |srecord_file: &SRecordFile, preserve_layout| {
+        let mut options = SerializationOptions::default();
+        options.data_record_type = DataRecordType::S1;
+        options.preserve_layout = preserve_layout;
+        let mut serialized_str = String::new();
+        for record in srecord_file.iter_records_with_options(&options).unwrap() {
+            serialized_str.push_str(&record.serialize());
+            serialized_str.push('\n');
+        }
+        serialized_str
+    };
+    assert_eq!(serialize(&srecord_file, true), srecord_str);
+    assert_ne!(serialize(&srecord_file, false), srecord_str);
+
+    // Data outside of the layout is emitted after the layout records
+    srecord_file[0x1000] = 0xAA;
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0x2000,
+        data: vec![0xBB; 0x12],
+    });
+    assert_eq!(
+        serialize(&srecord_file, true),
+        "S1071000AA01020338\n\
+         S10B0FF0F0F1F2F3F4F5F6F759\n\
+         S1091004040506070809BB\n\
+         S1132000BBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB1C\n\
+         S1052010BBBB54\n\
+         S5030005F7\n"
+    );
+
+    // Layout records must fit the data record type
+    srecord_file.set_record_layout(Some(RecordLayout::new(vec![0x1000..0x1100])));
+    let mut options = SerializationOptions::default();
+    options.preserve_layout = true;
+    assert_eq!(
+        srecord_file
+            .iter_records_with_options(&options)
+            .err()
+            .unwrap(),
+        SerializationError::DataRecordSizeTooLarge(250)
+    );
+}
+
+#[test]
+fn test_serialize_original() {
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    for path in [
+        "tests/srec_files/wikipedia.s19",
+        "tests/srec_files/wikipedia.s37",
+        "tests/srec_files/unsorted.s28",
+        "tests/srec_files/data_chunks.s19",
+    ] {
+        let srecord_str = fs::read_to_string(path).unwrap();
+        let (srecord_file, _) = SRecordFile::from_str_with_options(&srecord_str, &options).unwrap();
+        assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+    }
+
+    // Formatting that is not reproduced by serialization is kept if the file is not edited
+    let srecord_str = "S00600004844521b\nS107100000010203e2\nS9031000EC";
+    let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+    srecord_file[0x1000] = 0xAA;
+    assert_eq!(
+        srecord_file.serialize_original().unwrap(),
+        "S00600004844521B\nS1071000AA01020338\nS9031000EC"
+    );
+    // Undoing the edit reproduces the parsed text again
+    srecord_file[0x1000] = 0x00;
+    assert_eq!(srecord_file.serialize_original().unwrap(), srecord_str);
+    // Moving the layout records is an edit, even if the data is unchanged
+    let mut moved = srecord_file.clone();
+    moved.set_record_layout(Some(RecordLayout::new(vec![
+        0x1002..0x1004,
+        0x1000..0x1002,
+    ])));
+    assert_eq!(
+        moved.serialize_original().unwrap(),
+        "S00600004844521B\nS10510020203E3\nS10510000001E9\nS9031000EC"
+    );
+
+    // Lines that are not valid UTF-8 are kept byte-for-byte
+    let srecord_bytes = b"S107100000010203E2\r\n\xFF\xFE junk\r\nS9031000EC\r\n";
+    let (srecord_file, stats) = SRecordFile::from_bytes_lossy(srecord_bytes, &options).unwrap();
+    assert_eq!(stats.warnings.len(), 1);
+    assert_eq!(
+        srecord_file.serialize_original_bytes().unwrap(),
+        srecord_bytes
+    );
+    assert_eq!(
+        srecord_file.serialize_original().unwrap(),
+        String::from_utf8_lossy(srecord_bytes)
+    );
+
+    // Pushed input is kept as it was pushed
+    let srecord_bytes = b"S107100000010203e2\r\nS9031000EC";
+    let mut parser = SRecordParser::with_options(options.clone());
+    parser.push_bytes(&srecord_bytes[..10]).unwrap();
+    parser.push_bytes(&srecord_bytes[10..]).unwrap();
+    let srecord_file = parser.finish().unwrap();
+    assert_eq!(
+        srecord_file.serialize_original_bytes().unwrap(),
+        srecord_bytes
+    );
+
+    // Without a record layout, the default serialization is used
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    assert_eq!(
+        srecord_file.serialize_original().unwrap(),
+        "S00600004844521B\nS3090000100000010203E0\nS5030001FB\nS70500001000EA\n"
+    );
+}
+
+#[test]
+fn test_iter_records_start_address_record_type() {
+    let mut data_buffer = [0u8; 256];
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    for (start_address, data_record_type, expected) in [
+        (0x1234, DataRecordType::S1, "S9031234B6"),
+        (0x1234, DataRecordType::S2, "S804001234B5"),
+        (0x1234, DataRecordType::S3, "S70500001234B4"),
+        (0x123456, DataRecordType::Auto, "S8041234565F"),
+    ] {
+        srecord_file.start_address = Some(start_address);
+        let mut options = SerializationOptions::default();
+        options.data_record_type = data_record_type;
+        let record = srecord_file
+            .iter_records_with_options(&options)
+            .unwrap()
+            .last()
+            .unwrap()
+            .serialize();
+        assert_eq!(record, expected);
+        let parsed_record = Record::from_str(&record, &mut data_buffer).unwrap();
+        assert_eq!(parsed_record.serialize(), expected);
+    }
+
+    srecord_file.start_address = Some(0x10000);
+    let mut options = SerializationOptions::default();
+    options.data_record_type = DataRecordType::S1;
+    assert_eq!(
+        srecord_file
+            .iter_records_with_options(&options)
+            .err()
+            .unwrap(),
+        SerializationError::StartAddressTooLarge(0x10000)
+    );
+
+    srecord_file.start_address = None;
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0xFFFF,
+        data: vec![0xAA, 0xBB],
+    });
+    assert_eq!(
+        srecord_file
+            .iter_records_with_options(&options)
+            .err()
+            .unwrap(),
+        SerializationError::AddressTooLargeForFormat(0x10000)
+    );
+}
+
+#[test]
+fn test_srecord_file_get_multiple_data_chunks() {
+    let srecord_str = fs::read_to_string("tests/srec_files/data_chunks.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    for i in 0..16 {
+        let address = 0x1000 * i;
+        assert_eq!(srecord_file.get(address), Some(&0xFF));
+        assert_eq!(srecord_file.get(address + 0x0F), Some(&0xFF));
+        assert_eq!(srecord_file.get(address + 0x10), None);
+    }
+}
+
+#[test]
+fn test_srecord_file_transplant() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let src = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut dst = SRecordFile::new();
+    dst.transplant(&src, 0x38..0x46, 0x8000, OverlapPolicy::Error)
+        .unwrap();
+    assert_eq!(
+        dst.data_chunks,
+        [DataChunk {
+            address: 0x8000,
+            data: src[0x38..0x46].to_vec(),
+        }]
+    );
+    assert_eq!(dst.header_data, None);
+    assert_eq!(dst.start_address, None);
+}
+
+#[test]
+fn test_srecord_file_transplant_gaps() {
+    let srecord_str = fs::read_to_string("tests/srec_files/unsorted.s28").unwrap();
+    let src = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut dst = SRecordFile::new();
+    dst.transplant(&src, 0x00..0x10, 0x100, OverlapPolicy::Error)
+        .unwrap();
+    assert_eq!(
+        dst.data_chunks,
+        [
+            DataChunk {
+                address: 0x101,
+                data: vec![0x01, 0x02, 0x03],
+            },
+            DataChunk {
+                address: 0x105,
+                data: vec![0x05],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_srecord_file_transplant_overlap_policy() {
+    let src = SRecordFile::from_str("S107100000010203E2").unwrap();
+    let base = SRecordFile::from_str("S1051002AABB83").unwrap();
+
+    let mut dst = SRecordFile::from_str("S1051002AABB83").unwrap();
+    assert_eq!(
+        dst.transplant(&src, 0x1000..0x1004, 0x1000, OverlapPolicy::Error),
+        Err(TransplantError::OverlappingData(0x1002..0x1004))
+    );
+    assert_eq!(dst.data_chunks, base.data_chunks);
+
+    dst.transplant(&src, 0x1000..0x1004, 0x1000, OverlapPolicy::KeepExisting)
+        .unwrap();
+    assert_eq!(dst.data_chunks.len(), 1);
+    assert_eq!(dst[0x1000..0x1004], [0x00, 0x01, 0xAA, 0xBB]);
+
+    let mut dst = SRecordFile::from_str("S1051002AABB83").unwrap();
+    dst.transplant(&src, 0x1001..0x1005, 0x1001, OverlapPolicy::Overwrite)
+        .unwrap();
+    assert_eq!(dst.data_chunks.len(), 1);
+    assert_eq!(dst[0x1001..0x1004], [0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_srecord_file_transplant_address_overflow() {
+    let src = SRecordFile::from_str("S107100000010203E2").unwrap();
+    let mut dst = SRecordFile::new();
+    assert_eq!(
+        dst.transplant(&src, 0x1000..0x1004, u64::MAX - 2, OverlapPolicy::Error),
+        Err(TransplantError::AddressOverflow)
+    );
+    assert!(dst.data_chunks.is_empty());
+}
+
+#[test]
+fn test_srecord_file_append_trailer() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.append_trailer(&[0x01, 0x02], 4, 2), Ok(0));
+    assert_eq!(srecord_file.append_trailer(&[0x03], 1, 3), Ok(2));
+    assert_eq!(srecord_file.append_trailer(&[0x04], 4, 5), Ok(4));
+    assert_eq!(
+        srecord_file.data_chunks,
+        [
+            DataChunk {
+                address: 0,
+                data: vec![0x01, 0x02, 0x03],
+            },
+            DataChunk {
+                address: 4,
+                data: vec![0x04],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_srecord_file_append_trailer_error() {
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    assert_eq!(
+        srecord_file.append_trailer(&[0xAA], 0, u64::MAX),
+        Err(AppendTrailerError::InvalidAlignment)
+    );
+    assert_eq!(
+        srecord_file.append_trailer(&[0xAA; 4], 0x100, 0x1103),
+        Err(AppendTrailerError::ExceedsMaxEndAddress(0x1104))
+    );
+    assert_eq!(
+        srecord_file.append_trailer(&[0xAA], u64::MAX, u64::MAX),
+        Err(AppendTrailerError::AddressOverflow)
+    );
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file.data_chunks[0].end_address(), 0x1004);
+}
+
+#[test]
+fn test_srecord_file_extract_symbol() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let symbols = [
+        Symbol {
+            name: String::from("message"),
+            address: 0x38,
+            size: 0x0E,
+        },
+        Symbol {
+            name: String::from("unmapped"),
+            address: 0x1000,
+            size: 0x10,
+        },
+        Symbol {
+            name: String::from("overflow"),
+            address: u64::MAX,
+            size: 0x10,
+        },
+    ];
+
+    let message = srecord_file.extract_symbol(&symbols, "message", 1).unwrap();
+    assert_eq!(message.data_chunks.len(), 1);
+    assert_eq!(message[0x38..0x44], *b"Hello world.");
+    assert_eq!(message.header_data, None);
+    assert_eq!(message.start_address, None);
+
+    // The aligned range is cut to the existing data
+    let message = srecord_file
+        .extract_symbol(&symbols, "message", 0x20)
+        .unwrap();
+    assert_eq!(message.data_chunks[0].address, 0x20);
+    assert_eq!(message.data_chunks[0].end_address(), 0x46);
+
+    let unmapped = srecord_file
+        .extract_symbol(&symbols, "unmapped", 1)
+        .unwrap();
+    assert!(unmapped.data_chunks.is_empty());
+
+    assert_eq!(
+        srecord_file
+            .extract_symbol(&symbols, "missing", 1)
+            .unwrap_err(),
+        ExtractSymbolError::SymbolNotFound
+    );
+    assert_eq!(
+        srecord_file
+            .extract_symbol(&symbols, "message", 0)
+            .unwrap_err(),
+        ExtractSymbolError::InvalidAlignment
+    );
+    assert_eq!(
+        srecord_file
+            .extract_symbol(&symbols, "overflow", 1)
+            .unwrap_err(),
+        ExtractSymbolError::AddressOverflow
+    );
+}
+
+#[test]
+fn test_iter_records_data_record_type() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    for (data_record_type, expected) in [
+        (DataRecordType::S1, "S1"),
+        (DataRecordType::S2, "S2"),
+        (DataRecordType::S3, "S3"),
+        (DataRecordType::Auto, "S1"),
+    ] {
+        let mut options = SerializationOptions::default();
+        options.data_record_size = 0x1C;
+        options.data_record_type = data_record_type;
+        let data_records: Vec<String> = srecord_file
+            .iter_records_with_options(&options)
+            .unwrap()
+            .filter(|record| {
+                matches!(
+                    record,
+                    Record::S1Record(_) | Record::S2Record(_) | Record::S3Record(_)
+                )
+            })
+            .map(|record| record.serialize())
+            .collect();
+        assert_eq!(data_records.len(), 3);
+        assert!(data_records
+            .iter()
+            .all(|record| record.starts_with(expected)));
+    }
+}
+
+#[test]
+fn test_iter_records_auto_data_record_type() {
+    for (record_str, expected) in [
+        ("S104FFFF0AF3", "S1"),
+        ("S205010000AA4F", "S2"),
+        ("S205FFFFFF0AF3", "S2"),
+        ("S30601000000AA4E", "S3"),
+    ] {
+        let srecord_file = SRecordFile::from_str(record_str).unwrap();
+        let mut options = SerializationOptions::default();
+        options.data_record_type = DataRecordType::Auto;
+        let record = srecord_file
+            .iter_records_with_options(&options)
+            .unwrap()
+            .next()
+            .unwrap()
+            .serialize();
+        assert!(record.starts_with(expected));
+    }
+}
+
+#[test]
+fn test_iter_records_data_record_size() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    for (data_record_type, max_data_record_size) in [
+        (DataRecordType::S1, 252),
+        (DataRecordType::S2, 251),
+        (DataRecordType::S3, 250),
+        (DataRecordType::Auto, 252),
+    ] {
+        let options = |data_record_size| {
+            let mut options = SerializationOptions::default();
+            options.data_record_size = data_record_size;
+            options.data_record_type = data_record_type;
+            options
+        };
+        assert_eq!(
+            srecord_file
+                .iter_records_with_options(&options(0))
+                .err()
+                .unwrap(),
+            SerializationError::ZeroDataRecordSize
+        );
+        assert!(srecord_file.iter_records_with_options(&options(1)).is_ok());
+        assert!(srecord_file
+            .iter_records_with_options(&options(max_data_record_size))
+            .is_ok());
+        assert_eq!(
+            srecord_file
+                .iter_records_with_options(&options(max_data_record_size + 1))
+                .err()
+                .unwrap(),
+            SerializationError::DataRecordSizeTooLarge(max_data_record_size)
+        );
+    }
+
+    // A full S1 record has a byte count of 0xFF
+    let srecord_file = SRecordFile::from_binary(0, &[0; 300]);
+    let mut options = SerializationOptions::default();
+    options.data_record_size = 252;
+    options.data_record_type = DataRecordType::S1;
+    let records: Vec<String> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .map(|record| record.serialize())
+        .collect();
+    assert!(records[0].starts_with("S1FF0000"));
+    assert!(records[1].starts_with("S13300FC"));
+}
+
+#[test]
+fn test_iter_records_count_record_type() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2\nS9031000EC").unwrap();
+    for (count_record_type, expected) in [
+        (CountRecordType::S5, Some("S5030001FB")),
+        (CountRecordType::S6, Some("S604000001FA")),
+        (CountRecordType::Auto, Some("S5030001FB")),
+        (CountRecordType::Omit, None),
+    ] {
+        let mut options = SerializationOptions::default();
+        options.count_record_type = count_record_type;
+        let count_records: Vec<String> = srecord_file
+            .iter_records_with_options(&options)
+            .unwrap()
+            .filter(|record| matches!(record, Record::S5Record(_) | Record::S6Record(_)))
+            .map(|record| record.serialize())
+            .collect();
+        assert_eq!(count_records.first().map(String::as_str), expected);
+        assert!(count_records.len() <= 1);
+    }
+
+    // Forced count record types must fit the number of data records
+    let srecord_file = SRecordFile::from_binary(0, &[0; 0x10000]);
+    let options = |count_record_type| {
+        let mut options = SerializationOptions::default();
+        options.data_record_size = 1;
+        options.count_record_type = count_record_type;
+        options
+    };
+    assert_eq!(
+        srecord_file
+            .iter_records_with_options(&options(CountRecordType::S5))
+            .err()
+            .unwrap(),
+        SerializationError::RecordCountTooLarge(0x10000)
+    );
+    assert!(srecord_file
+        .iter_records_with_options(&options(CountRecordType::S6))
+        .is_ok());
+}
+
+#[test]
+fn test_srecord_file_minimal_record_type() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.minimal_record_type(), RecordType::S1);
+    for (address, expected) in [
+        (0xFFFF, RecordType::S1),
+        (0x10000, RecordType::S2),
+        (0xFFFFFF, RecordType::S2),
+        (0x1000000, RecordType::S3),
+    ] {
+        srecord_file.data_chunks = vec![DataChunk {
+            address,
+            data: vec![0x00],
+        }];
+        assert_eq!(srecord_file.minimal_record_type(), expected);
+    }
+
+    // The start address is also taken into account
+    srecord_file.data_chunks.clear();
+    srecord_file.start_address = Some(0x10000);
+    assert_eq!(srecord_file.minimal_record_type(), RecordType::S2);
+}
+
+#[test]
+fn test_data_chunk_slices() {
+    let mut data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    assert_eq!(data_chunk.len(), 4);
+    assert!(!data_chunk.is_empty());
+    assert_eq!(data_chunk.as_slice(), [0x00, 0x01, 0x02, 0x03]);
+    data_chunk.as_mut_slice()[1] = 0xAA;
+    data_chunk.as_mut()[2] = 0xBB;
+    let data: &[u8] = data_chunk.as_ref();
+    assert_eq!(data, [0x00, 0xAA, 0xBB, 0x03]);
+
+    let bytes = data_chunk.iter_bytes();
+    assert_eq!(bytes.len(), 4);
+    assert_eq!(
+        bytes.collect::<Vec<_>>(),
+        [
+            (0x1000, 0x00),
+            (0x1001, 0xAA),
+            (0x1002, 0xBB),
+            (0x1003, 0x03)
+        ]
+    );
+
+    let data_chunk = DataChunk {
+        address: 0x1000,
+        data: Vec::new(),
+    };
+    assert_eq!(data_chunk.len(), 0);
+    assert!(data_chunk.is_empty());
+    assert_eq!(data_chunk.iter_bytes().count(), 0);
+}
+
+#[test]
+fn test_data_chunk_split_at() {
+    let data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    let (below, above) = data_chunk.split_at(0x1003);
+    assert_eq!(below.address, 0x1000);
+    assert_eq!(below.data, [0x00, 0x01, 0x02]);
+    assert_eq!(above.address, 0x1003);
+    assert_eq!(above.data, [0x03]);
+    let (below, above) = below.split_at(0x1000);
+    assert!(below.data.is_empty());
+    assert_eq!(above.data, [0x00, 0x01, 0x02]);
+    let (below, above) = above.split_at(0x1003);
+    assert_eq!(below.data, [0x00, 0x01, 0x02]);
+    assert_eq!(above.address, 0x1003);
+    assert!(above.data.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_data_chunk_split_at_error() {
+    let data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    data_chunk.split_at(0x1005);
+}
+
+#[test]
+fn test_data_chunk_truncate() {
+    let mut data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    data_chunk.truncate(0x1004);
+    assert_eq!(data_chunk.data, [0x00, 0x01, 0x02, 0x03]);
+    data_chunk.truncate(0x1003);
+    assert_eq!(data_chunk.data, [0x00, 0x01, 0x02]);
+    data_chunk.truncate(0x0FFF);
+    assert_eq!(data_chunk.address, 0x1000);
+    assert!(data_chunk.data.is_empty());
+}
+
+#[test]
+fn test_data_chunk_drain() {
+    let mut data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05],
+    };
+    assert_eq!(
+        data_chunk.drain(0x1004..0x1006).collect::<Vec<_>>(),
+        [0x04, 0x05]
+    );
+    assert_eq!(data_chunk.address, 0x1000);
+    assert_eq!(data_chunk.data, [0x00, 0x01, 0x02, 0x03]);
+    assert_eq!(data_chunk.drain(0x1000..0x1001).collect::<Vec<_>>(), [0x00]);
+    assert_eq!(data_chunk.address, 0x1001);
+    assert_eq!(data_chunk.data, [0x01, 0x02, 0x03]);
+    assert_eq!(data_chunk.drain(0x1002..0x1002).count(), 0);
+    assert_eq!(data_chunk.data, [0x01, 0x02, 0x03]);
+    assert_eq!(data_chunk.drain(0x1001..0x1004).count(), 3);
+    assert_eq!(data_chunk.address, 0x1001);
+    assert!(data_chunk.data.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_data_chunk_drain_middle_error() {
+    let mut data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    data_chunk.drain(0x1001..0x1003);
+}
+
+#[test]
+#[should_panic]
+fn test_data_chunk_drain_outside_error() {
+    let mut data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    };
+    data_chunk.drain(0x1002..0x1005);
+}
+
+#[test]
+fn test_data_chunk_iter_records_aligned() {
+    let data_chunk = DataChunk {
+        address: 0x05,
+        data: (0x05..0x35).collect(),
+    };
+    let records: Vec<(u64, usize)> = data_chunk
+        .iter_records_aligned(16, 16)
+        .map(|record| (record.address, record.data.len()))
+        .collect();
+    assert_eq!(records, [(0x05, 11), (0x10, 16), (0x20, 16), (0x30, 5)]);
+
+    // Aligned chunks are not affected
+    let data_chunk = DataChunk {
+        address: 0x20,
+        data: vec![0; 0x20],
+    };
+    let records: Vec<(u64, usize)> = data_chunk
+        .iter_records_aligned(16, 16)
+        .map(|record| (record.address, record.data.len()))
+        .collect();
+    assert_eq!(records, [(0x20, 16), (0x30, 16)]);
+
+    // Records never cross an alignment boundary from an unaligned start address
+    let records: Vec<(u64, usize)> = DataChunk {
+        address: 0x0E,
+        data: vec![0; 0x0C],
+    }
+    .iter_records_aligned(6, 4)
+    .map(|record| (record.address, record.data.len()))
+    .collect();
+    assert_eq!(records, [(0x0E, 2), (0x10, 6), (0x16, 2), (0x18, 2)]);
+}
+
+#[test]
+fn test_parse_srecord_out_of_place_records() {
+    // Data record after count record
+    let srecord_str = "S107100000010203E2\nS5030001FB\nS107200000010203D2";
+    let lenient = ParseOptions::default();
+    let (srecord_file, stats) = SRecordFile::from_str_with_options(srecord_str, &lenient).unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(stats.num_records, 3);
+    assert_eq!(stats.num_data_records, 2);
+    assert_eq!(
+        stats.warnings,
+        [ParseWarning {
+            line_index: 2,
+            error_type: ErrorType::DataAfterCountRecord,
+        }]
+    );
+    let mut strict = ParseOptions::default();
+    strict.strict = true;
+    assert_eq!(
+        SRecordFile::from_str_with_options(srecord_str, &strict).unwrap_err(),
+        SRecordParseError {
+            error_type: ErrorType::DataAfterCountRecord,
+        }
+    );
+
+    // Records after terminator
+    let srecord_str = "S107100000010203E2\nS9030000FC\nS5030001FB";
+    let (_, stats) = SRecordFile::from_str_with_options(srecord_str, &lenient).unwrap();
+    assert_eq!(
+        stats.warnings,
+        [ParseWarning {
+            line_index: 2,
+            error_type: ErrorType::RecordAfterTerminator,
+        }]
+    );
+    assert_eq!(
+        SRecordFile::from_str_with_options(srecord_str, &strict).unwrap_err(),
+        SRecordParseError {
+            error_type: ErrorType::RecordAfterTerminator,
+        }
+    );
+
+    // Well-formed files are accepted in strict mode
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let (_, stats) = SRecordFile::from_str_with_options(&srecord_str, &strict).unwrap();
+    assert_eq!(stats.num_records, 6);
+    assert_eq!(stats.num_data_records, 3);
+    assert!(stats.warnings.is_empty());
+}
+
+#[test]
+fn test_align_regions_to() {
+    let mut srecord_file =
+        SRecordFile::from_str("S107100200010203E0\nS1051008AABB7D\nS1052001CCDD30").unwrap();
+    srecord_file.align_regions_to(0x10, 0xFF).unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 0x10);
+    assert_eq!(srecord_file[0x1008..0x100A], [0xAA, 0xBB]);
+    assert_eq!(srecord_file.data_chunks[1].address, 0x2000);
+    assert_eq!(srecord_file[0x2000..0x2004], [0xFF, 0xCC, 0xDD, 0xFF]);
+
+    // Aligning already aligned regions does nothing
+    srecord_file.align_regions_to(0x08, 0x00).unwrap();
+    assert_eq!(
+        srecord_file.data_chunks[1].data,
+        [&[0xFF, 0xCC, 0xDD][..], &[0xFF; 13]].concat()
+    );
+
+    assert_eq!(
+        srecord_file.align_regions_to(0, 0xFF),
+        Err(AlignRegionsError::InvalidSectorSize)
+    );
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.data_chunks.push(DataChunk {
+        address: u64::MAX - 2,
+        data: vec![0x00],
+    });
+    assert_eq!(
+        srecord_file.align_regions_to(0x10, 0xFF),
+        Err(AlignRegionsError::AddressOverflow)
+    );
+    assert_eq!(srecord_file.data_chunks[0].data, [0x00]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_align_chunks() {
+    let mut srecord_file =
+        SRecordFile::from_str("S1051003AABB82\nS1051006CCDD3B\nS1052101EEFFEB").unwrap();
+    let mut aligned = srecord_file.clone();
+    aligned.align_regions_to(0x100, 0xFF).unwrap();
+    srecord_file.align_chunks(0x100, 0xFF).unwrap();
+    assert_eq!(srecord_file, aligned);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 0x100);
+    assert_eq!(
+        srecord_file[0x1002..0x1009],
+        [0xFF, 0xAA, 0xBB, 0xFF, 0xCC, 0xDD, 0xFF]
+    );
+    assert_eq!(srecord_file.data_chunks[1].address, 0x2100);
+    assert_eq!(srecord_file[0x2100..0x2104], [0xFF, 0xEE, 0xFF, 0xFF]);
+
+    assert_eq!(
+        srecord_file.align_chunks(0, 0xFF),
+        Err(AlignRegionsError::InvalidSectorSize)
+    );
+}
+
+#[test]
+fn test_truncate_to() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.truncate_to(0x38..0x3D);
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file[0x38..0x3D], *b"Hello");
+    assert!(srecord_file.header_data.is_some());
+
+    srecord_file.truncate_to(0x100..0x200);
+    assert!(srecord_file.data_chunks.is_empty());
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_run() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let srecord_file = Pipeline::load(srecord_file)
+        .crop(&[0x38..0x46, 0x00..0x04])
+        .offset(0x1000)
+        .fill(0x1000..0x1050, 0x00)
+        .crc32_le(0x1000..0x1050, 0x1050)
+        .run()
+        .unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 0x54);
+    assert_eq!(srecord_file[0x1000..0x1004], [0x7C, 0x08, 0x02, 0xA6]);
+    assert_eq!(srecord_file[0x1004..0x1008], [0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(srecord_file[0x1038..0x103D], *b"Hello");
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+    assert!(srecord_file.header_data.is_some());
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_errors() {
+    let srecord_file = || SRecordFile::from_str("S107100000010203E2").unwrap();
+    assert_eq!(
+        Pipeline::load(srecord_file())
+            .offset(-0x1001)
+            .dry_run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 0,
+            error_type: PipelineErrorType::AddressOverflow,
+        }
+    );
+    assert_eq!(
+        Pipeline::load(srecord_file())
+            .crop(&[0x1000..0x1002])
+            .crc32_le(0x1000..0x1004, 0x2000)
+            .run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::IncompleteCrcRange(0x1002..0x1004),
+        }
+    );
+    assert_eq!(
+        Pipeline::load(srecord_file())
+            .crc32_le(0x1000..0x1004, 0x1002)
+            .run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 0,
+            error_type: PipelineErrorType::CrcInsideRange,
+        }
+    );
+    assert_eq!(
+        Pipeline::load(srecord_file())
+            .merge(srecord_file(), OverlapPolicy::KeepExisting)
+            .merge(srecord_file(), OverlapPolicy::Error)
+            .run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::OverlappingData(0x1000..0x1004),
+        }
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_offset_to_end_of_address_space() {
+    let srecord_file = || SRecordFile::from_binary(1 << 63, &[0x00, 0x01, 0x02, 0x03]);
+    // Data ending exactly at u64::MAX fits, as by SRecordFile::offset
+    let delta = (u64::MAX - (1 << 63) - 4) as i64;
+    let offset_file = Pipeline::load(srecord_file()).offset(delta).run().unwrap();
+    assert_eq!(offset_file.data_chunks[0].address, u64::MAX - 4);
+    assert_eq!(offset_file.data_chunks[0].end_address(), u64::MAX);
+    let mut expected = srecord_file();
+    expected.offset(delta).unwrap();
+    assert_eq!(offset_file, expected);
+
+    for delta in [delta + 1, i64::MAX] {
+        assert_eq!(
+            Pipeline::load(srecord_file())
+                .offset(delta)
+                .dry_run()
+                .unwrap_err(),
+            PipelineError {
+                step_index: 0,
+                error_type: PipelineErrorType::AddressOverflow,
+            }
+        );
+    }
+    assert_eq!(
+        Pipeline::load(srecord_file())
+            .offset(delta)
+            .offset(1)
+            .run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::AddressOverflow,
+        }
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_merge_many() {
+    let srecord_file = |data_chunks: &[(u64, &[u8])]| {
+        let mut srecord_file = SRecordFile::new();
+        for (address, data) in data_chunks {
+            srecord_file.data_chunks.push(DataChunk {
+                address: *address,
+                data: data.to_vec(),
+            });
+        }
+        srecord_file
+    };
+    let srecord_file = Pipeline::load(srecord_file(&[(0, &[0x00]), (3, &[0x00])]))
+        .merge(
+            srecord_file(&[(1, &[0x11]), (3, &[0x11])]),
+            OverlapPolicy::KeepExisting,
+        )
+        .merge(
+            srecord_file(&[(1, &[0x22, 0x22])]),
+            OverlapPolicy::KeepExisting,
+        )
+        .merge(
+            srecord_file(&[(0, &[0x33]), (4, &[0x33])]),
+            OverlapPolicy::Overwrite,
+        )
+        .merge(
+            srecord_file(&[(3, &[0x44, 0x44])]),
+            OverlapPolicy::Overwrite,
+        )
+        .merge(srecord_file(&[(5, &[0x55])]), OverlapPolicy::Error)
+        .run()
+        .unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(
+        srecord_file[0x00..0x06],
+        [0x33, 0x11, 0x22, 0x44, 0x44, 0x55]
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_start_address_policy() {
+    let application = || SRecordFile::from_str("S107100000010203E2\nS9031000EC").unwrap();
+    let bootloader = || SRecordFile::from_str("S1050000AABB95\nS9030000FC").unwrap();
+    let run = |policy| {
+        Pipeline::load(application())
+            .merge(bootloader(), OverlapPolicy::Error)
+            .start_address_policy(policy)
+            .run()
+            .map(|srecord_file| srecord_file.start_address)
+    };
+    assert_eq!(run(StartAddressPolicy::KeepExisting), Ok(Some(0x1000)));
+    assert_eq!(run(StartAddressPolicy::Overwrite), Ok(Some(0x0000)));
+    assert_eq!(
+        run(StartAddressPolicy::RequireFromInput(0)),
+        Ok(Some(0x1000))
+    );
+    assert_eq!(
+        run(StartAddressPolicy::RequireFromInput(1)),
+        Ok(Some(0x0000))
+    );
+    assert_eq!(
+        run(StartAddressPolicy::Error),
+        Err(PipelineError {
+            step_index: 0,
+            error_type: PipelineErrorType::ConflictingStartAddresses(0x1000, 0x0000),
+        })
+    );
+    assert_eq!(
+        run(StartAddressPolicy::RequireFromInput(2)),
+        Err(PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::MissingStartAddress(2),
+        })
+    );
+
+    // Equal start addresses do not conflict
+    assert_eq!(
+        Pipeline::load(application())
+            .merge(application(), OverlapPolicy::Overwrite)
+            .start_address_policy(StartAddressPolicy::Error)
+            .run()
+            .unwrap()
+            .start_address,
+        Some(0x1000)
+    );
+
+    // The required input must have a start address, and later offsets are applied to it
+    let mut no_start_address = bootloader();
+    no_start_address.start_address = None;
+    assert_eq!(
+        Pipeline::load(application())
+            .merge(no_start_address, OverlapPolicy::Error)
+            .start_address_policy(StartAddressPolicy::RequireFromInput(1))
+            .dry_run()
+            .unwrap_err(),
+        PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::MissingStartAddress(1),
+        }
+    );
+    assert_eq!(
+        Pipeline::load(application())
+            .merge(bootloader(), OverlapPolicy::Error)
+            .offset(0x100)
+            .start_address_policy(StartAddressPolicy::RequireFromInput(1))
+            .run()
+            .unwrap()
+            .start_address,
+        Some(0x0100)
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_write() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    let mut options = SerializationOptions::default();
+    options.data_record_type = DataRecordType::S1;
+    assert_eq!(
+        Pipeline::load(srecord_file)
+            .offset(-0x1000)
+            .write(&options)
+            .unwrap(),
+        "S107000000010203F2\nS5030001FB\n"
+    );
+
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    let mut options = SerializationOptions::default();
+    options.data_record_size = 300;
+    assert_eq!(
+        Pipeline::load(srecord_file)
+            .offset(-0x1000)
+            .write(&options)
+            .unwrap_err(),
+        PipelineError {
+            step_index: 1,
+            error_type: PipelineErrorType::Serialization(
+                SerializationError::DataRecordSizeTooLarge(250)
+            ),
+        }
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary_round_trip() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    // Pseudo-random input bytes from a fixed seed, so that the test is deterministic
+    let mut state = 0x2545F4914F6CDD1Du64;
+    let mut bytes = vec![0u8; 0x10000];
+    for byte in bytes.iter_mut() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        *byte = state as u8;
+    }
+
+    for offset in (0..0x8000).step_by(0x100) {
+        let mut u = Unstructured::new(&bytes[offset..offset + 0x1000]);
+        let srecord_file = SRecordFile::arbitrary(&mut u).unwrap();
+        assert!(srecord_file
+            .data_chunks
+            .windows(2)
+            .all(|data_chunks| data_chunks[0].end_address() < data_chunks[1].address));
+        let srecord_str = srecord_file.serialize_original().unwrap();
+        let parsed = SRecordFile::from_str(&srecord_str).unwrap();
+        assert_eq!(parsed.data_chunks, srecord_file.data_chunks);
+        assert_eq!(parsed.start_address, srecord_file.start_address);
+        if srecord_file.header_data.is_some() {
+            assert_eq!(parsed.header_data, srecord_file.header_data);
+        }
+
+        let data_chunk = DataChunk::arbitrary(&mut u).unwrap();
+        assert!(!data_chunk.data.is_empty());
+        assert!(data_chunk.end_address() <= 1 << 32);
+
+        for _ in 0..16 {
+            let record = OwnedRecord::arbitrary(&mut u).unwrap();
+            assert_eq!(OwnedRecord::from_str(&record.serialize()).unwrap(), record);
+        }
+    }
+}
+
+#[cfg(feature = "ihex")]
+#[test]
+fn test_ihex_conversion() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s37").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.header_data = None;
+    // Data crossing a 64 KiB boundary is split at the boundary
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0x1FFF8,
+        data: (0..16).collect(),
+    });
+    let records = Vec::<ihex::Record>::try_from(&srecord_file).unwrap();
+    assert_eq!(
+        records[records.len() - 6..],
+        [
+            ihex::Record::ExtendedLinearAddress(0x0001),
+            ihex::Record::Data {
+                offset: 0xFFF8,
+                value: (0..8).collect()
+            },
+            ihex::Record::ExtendedLinearAddress(0x0002),
+            ihex::Record::Data {
+                offset: 0x0000,
+                value: (8..16).collect()
+            },
+            ihex::Record::StartLinearAddress(0x00000000),
+            ihex::Record::EndOfFile,
+        ]
+    );
+    let ihex_str = ihex::create_object_file_representation(&records).unwrap();
+    let records = ihex::Reader::new(&ihex_str)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let converted = SRecordFile::try_from(records.as_slice()).unwrap();
+    assert_eq!(converted.data_chunks, srecord_file.data_chunks);
+    assert_eq!(converted.start_address, srecord_file.start_address);
+
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0xFFFFFFFF,
+        data: vec![0x00, 0x01],
+    });
+    assert_eq!(
+        Vec::<ihex::Record>::try_from(&srecord_file),
+        Err(SerializationError::AddressTooLargeForFormat(0x100000000))
+    );
+
+    let records = [
+        ihex::Record::Data {
+            offset: 0x1000,
+            value: vec![0x00, 0x01],
+        },
+        ihex::Record::Data {
+            offset: 0x1001,
+            value: vec![0x02],
+        },
+    ];
+    assert_eq!(
+        SRecordFile::try_from(records.as_slice())
+            .unwrap_err()
+            .error_type,
+        ErrorType::OverlappingData
+    );
+}
+
+#[cfg(feature = "object")]
+#[test]
+fn test_object_conversion() {
+    let elf_data = fs::read("tests/object_files/minimal.elf").unwrap();
+    let elf_file = object::File::parse(elf_data.as_slice()).unwrap();
+    let srecord_file = SRecordFile::try_from(&elf_file).unwrap();
+    assert_eq!(
+        srecord_file.data_chunks,
+        [
+            DataChunk {
+                address: 0x1000,
+                data: vec![0x00, 0x01, 0x02, 0x03],
+            },
+            DataChunk {
+                address: 0x2000,
+                data: vec![0xAA, 0xBB],
+            },
+        ]
+    );
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+}
+
+#[test]
+fn test_srecord_file_transaction() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file
+        .transaction(|tx| {
+            tx.erase(0x10..0x20).erase(0x40..0x100);
+            tx.set(0x18, &[0xAA; 4]).set(0x1000, &[0xBB]);
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+    let ranges: Vec<_> = srecord_file
+        .data_chunks
+        .iter()
+        .map(|data_chunk| data_chunk.address..data_chunk.end_address())
+        .collect();
+    assert_eq!(ranges, [0x00..0x10, 0x18..0x1C, 0x20..0x40, 0x1000..0x1001]);
+    assert_eq!(srecord_file[0x18..0x1C], [0xAA; 4]);
+
+    // Nothing is applied if the closure fails
+    let result = srecord_file.transaction(|tx| {
+        tx.erase(0x00..0x10);
+        Err("aborted")
+    });
+    assert_eq!(result, Err(TransactionError::Aborted("aborted")));
+    assert_eq!(srecord_file[0x00], 0x7C);
+
+    // Inverted ranges erase nothing, as by SRecordFile::erase
+    let (start_address, end_address) = (0x20, 0x10);
+    let result = srecord_file.transaction(|tx| {
+        tx.erase(0x00..0x10);
+        tx.erase(start_address..end_address);
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(srecord_file.data_chunks[0].address, 0x18);
+}
+
+#[test]
+fn test_srecord_file_transaction_checks() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let original = srecord_file.clone();
+
+    // Protected ranges apply to edits added before and after protecting
+    let result = srecord_file.transaction(|tx| {
+        tx.set(0x1000, &[0xAA]).erase(0x08..0x10);
+        tx.protect(0x00..0x09);
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Err(TransactionError::Protected(1)));
+    assert_eq!(srecord_file, original);
+
+    // Written data must be inside the memory layout, erasing outside of it is fine
+    let result = srecord_file.transaction(|tx| {
+        tx.memory_layout(&[0x00..0x80]).erase(0x1000..0x2000);
+        tx.set(0x10, &[0xAA]).set(0x7F, &[0xAA, 0xBB]);
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Err(TransactionError::OutsideMemoryLayout(2)));
+    assert_eq!(srecord_file, original);
+
+    // Inserted data must not overlap data of the file or of earlier edits
+    let result = srecord_file.transaction(|tx| {
+        tx.insert(0x1000, &[0xAA; 2]).insert(0x1001, &[0xBB]);
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Err(TransactionError::OverlappingData(1)));
+    let result = srecord_file.transaction(|tx| {
+        tx.insert(0x3F, &[0xAA]);
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Err(TransactionError::OverlappingData(0)));
+    assert_eq!(srecord_file, original);
+
+    // Erasing first makes room for inserted data, and empty ranges are never protected
+    srecord_file
+        .transaction(|tx| {
+            tx.protect(0x00..0x10).erase(0x10..0x14).erase(0x08..0x08);
+            tx.insert(0x10, &[0xAA; 4]);
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+    assert_eq!(srecord_file[0x10..0x14], [0xAA; 4]);
+}
+
+#[test]
+fn test_srecord_file_split_banks() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.header_data = Some(b"HDR".to_vec());
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0xFFFF_FFFE,
+        data: vec![0x00, 0x01, 0x02, 0x03],
+    });
+    srecord_file.start_address = Some(0x3_0000_0010);
+    assert_eq!(
+        srecord_file.iter_records(16).err().unwrap(),
+        SerializationError::AddressTooLargeForFormat(0x1_0000_0001)
+    );
+
+    let banks = srecord_file.split_banks();
+    assert_eq!(banks.len(), 3);
+    assert_eq!(banks[0].0, 0x0);
+    assert_eq!(banks[0].1.data_chunks.len(), 1);
+    assert_eq!(banks[0].1.data_chunks[0].address, 0xFFFF_FFFE);
+    assert_eq!(banks[0].1.data_chunks[0].data, [0x00, 0x01]);
+    assert_eq!(banks[0].1.start_address, None);
+    assert_eq!(banks[1].0, 0x1_0000_0000);
+    assert_eq!(banks[1].1.data_chunks.len(), 1);
+    assert_eq!(banks[1].1.data_chunks[0].address, 0x0);
+    assert_eq!(banks[1].1.data_chunks[0].data, [0x02, 0x03]);
+    assert_eq!(banks[2].0, 0x3_0000_0000);
+    assert!(banks[2].1.data_chunks.is_empty());
+    assert_eq!(banks[2].1.start_address, Some(0x10));
+    for (_, bank) in banks.iter() {
+        assert_eq!(bank.header_data.as_deref(), Some(b"HDR".as_slice()));
+        assert!(bank.iter_records(16).is_ok());
+    }
+
+    assert!(SRecordFile::new().split_banks().is_empty());
+}
+
+#[test]
+fn test_srecord_file_bank_records() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.header_data = Some(b"HDR".to_vec());
+    srecord_file.set_data(0x1000, &[0xAA, 0xBB]);
+    srecord_file.set_data(0xFFFF_FFFE, &[0x00, 0x01, 0x02, 0x03]);
+    srecord_file.set_data(0x3_0000_1000, &[0xCC]);
+    srecord_file.start_address = Some(0x1000);
+    let mut options = SerializationOptions::default();
+    options.bank_records = true;
+    let records: Vec<String> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .map(|record| record.serialize())
+        .collect();
+    assert_eq!(
+        records,
+        [
+            "S00600004844521B",
+            "S30700001000AABB83",
+            "S307FFFFFFFE0001FC",
+            "S40500000001F9",
+            "S307000000000203F3",
+            "S40500000003F7",
+            "S30600001000CC1D",
+            "S5030004F8",
+            "S70500001000EA",
+        ]
+    );
+
+    // Bank records are only parsed when enabled
+    let srecord_str = records.join("\n");
+    let mut parse_options = ParseOptions::default();
+    parse_options.bank_records = true;
+    let (parsed, stats) = SRecordFile::from_str_with_options(&srecord_str, &parse_options).unwrap();
+    assert_eq!(parsed, srecord_file);
+    assert_eq!(stats.num_data_records, 4);
+    assert_eq!(
+        SRecordFile::from_str(&srecord_str).unwrap_err().error_type,
+        ErrorType::S4Reserved
+    );
+
+    // Addresses relative to the bank must still fit the data records
+    let mut options = SerializationOptions::default();
+    options.data_record_type = DataRecordType::S1;
+    options.bank_records = true;
+    assert_eq!(
+        srecord_file.iter_records_with_options(&options).err(),
+        Some(SerializationError::AddressTooLargeForFormat(0x1_0000_0001))
+    );
+    srecord_file.erase(0x1_0000..0x2_0000_0000);
+    let records: Vec<String> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .map(|record| record.serialize())
+        .collect();
+    assert_eq!(records[2], "S40500000003F7");
+
+    // Files without data above 4 GiB are serialized without bank records
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    let records: Vec<String> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .map(|record| record.serialize())
+        .collect();
+    assert_eq!(records, ["S107100000010203E2", "S5030001FB"]);
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_pipeline_remap_table() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let pipeline = Pipeline::load(srecord_file)
+        .crop(&[0x0008..0x0010, 0x0020..0x0024])
+        .offset(0x1000)
+        .fill(0x1000..0x1100, 0xFF);
+    let remap_table = pipeline.remap_table().unwrap();
+    assert_eq!(
+        remap_table.entries,
+        [
+            RemapEntry {
+                source: 0x0008..0x0010,
+                destination: 0x1008,
+            },
+            RemapEntry {
+                source: 0x0020..0x0024,
+                destination: 0x1020,
+            },
+        ]
+    );
+    assert_eq!(remap_table.translate(0x0009), Some(0x1009));
+    assert_eq!(remap_table.translate(0x0010), None);
+
+    let serialized = remap_table.serialize();
+    assert_eq!(serialized, "0x8 0x10 0x1008\n0x20 0x24 0x1020\n");
+    assert_eq!(RemapTable::from_str(&serialized), Ok(remap_table.clone()));
+
+    let srecord_file = pipeline.run().unwrap();
+    let other_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let remapped_file = remap_table.apply(&other_file).unwrap();
+    assert_eq!(remapped_file.data_chunks.len(), 2);
+    assert_eq!(remapped_file[0x1008..0x1010], srecord_file[0x1008..0x1010]);
+    assert_eq!(remapped_file[0x1020..0x1024], srecord_file[0x1020..0x1024]);
+    assert!(remapped_file.get(0x1010).is_none());
+
+    assert_eq!(
+        RemapTable::from_str("0x0 0x10 0x100\n\n0x20 0x10 0x200"),
+        Err(RemapTableParseError { line_index: 2 })
+    );
+    assert_eq!(
+        RemapTable::from_str("0x0 0x10"),
+        Err(RemapTableParseError { line_index: 0 })
+    );
+    assert_eq!(
+        RemapTable::from_str("10 20 30"),
+        Err(RemapTableParseError { line_index: 0 })
+    );
+    let overlapping = RemapTable::from_str("0x0 0x10 0x100\n0x8 0x10 0x108").unwrap();
+    assert!(overlapping.apply(&other_file).is_err());
+}
+
+#[test]
+fn test_address() {
+    assert!(Address::for_record_type(0xFFFF, &RecordType::S1).is_some());
+    assert!(Address::for_record_type(0x10000, &RecordType::S1).is_none());
+    assert!(Address::for_record_type(0xFF_FFFF, &RecordType::S8).is_some());
+    assert!(Address::for_record_type(0x100_0000, &RecordType::S8).is_none());
+    assert!(Address::for_record_type(0xFFFF_FFFF, &RecordType::S3).is_some());
+    assert!(Address::for_record_type(0x1_0000_0000, &RecordType::S3).is_none());
+
+    let address = Address::from(0x1234u16);
+    assert_eq!(u64::from(address), 0x1234);
+    assert_eq!(u16::try_from(address), Ok(0x1234));
+    assert!(u32::try_from(Address::new(0x1_0000_0000)).is_err());
+    assert_eq!(address.to_string(), "0x1234");
+
+    let mut srecord_file = SRecordFile::from_str("S107123401020304A8").unwrap();
+    assert_eq!(srecord_file[address], 0x01);
+    srecord_file[address] = 0xFF;
+    assert_eq!(srecord_file.get(address), Some(&0xFF));
+    assert!(srecord_file.get(Address::new(0x1238)).is_none());
+
+    // Addresses are accepted by the methods that write, erase and read data
+    let end = Address::from(0x1238u32);
+    srecord_file.set_data(end, &[0xAA, 0xBB]);
+    srecord_file.write_u16_be(Address::new(0x1240), 0xCCDD);
+    srecord_file.fill(end..Address::new(0x1239), 0xEE);
+    assert_eq!(srecord_file.read_u16_le(end), Some(0xBBEE));
+    assert_eq!(
+        srecord_file.read_int(Address::new(0x1240), 2, Endianness::Big),
+        Some(0xCCDD)
+    );
+    srecord_file.erase(address..end);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1238);
+    srecord_file
+        .transaction(|tx| {
+            tx.erase(end..Address::new(0x1240)).set(address, &[0x01]);
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1234);
+    srecord_file.set_start_address(address);
+    assert_eq!(srecord_file.start_address, Some(0x1234));
+
+    let data_record = DataRecord::new(address, &[0x01]);
+    assert_eq!(data_record.address, 0x1234);
+    assert_eq!(
+        OwnedDataRecord::new(address, vec![0x01]).as_data_record(),
+        data_record
+    );
+    assert_eq!(StartAddressRecord::new(address).start_address, 0x1234);
+}
+
+#[test]
+fn test_srecord_file_merge() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut other = SRecordFile::new();
+    other.header_data = Some(b"OTHER".to_vec());
+    other.start_address = Some(0x8000);
+    other.data_chunks.push(DataChunk {
+        address: 0x0000,
+        data: vec![0xAA, 0xBB],
+    });
+    other.data_chunks.push(DataChunk {
+        address: 0x8000,
+        data: vec![0xCC, 0xDD],
+    });
+    let clone = |srecord_file: &SRecordFile| {
+        let mut clone = SRecordFile::new();
+        clone.header_data = srecord_file.header_data.clone();
+        clone.data_chunks = srecord_file
+            .data_chunks
+            .iter()
+            .map(|data_chunk| DataChunk {
+                address: data_chunk.address,
+                data: data_chunk.data.clone(),
+            })
+            .collect();
+        clone.start_address = srecord_file.start_address;
+        clone
+    };
+
+    let mut merged = clone(&srecord_file);
+    assert_eq!(
+        merged.merge(clone(&other), OverlapPolicy::Error),
+        Err(MergeError::OverlappingData(0x0000..0x0002))
+    );
+    assert_eq!(merged.data_chunks, srecord_file.data_chunks);
+
+    other.data_chunks.remove(0);
+    assert_eq!(
+        merged.merge(clone(&other), OverlapPolicy::Error),
+        Err(MergeError::ConflictingHeaders)
+    );
+    other.header_data = srecord_file.header_data.clone();
+    assert_eq!(
+        merged.merge(clone(&other), OverlapPolicy::Error),
+        Err(MergeError::ConflictingStartAddresses(0x0000, 0x8000))
+    );
+    other.start_address = srecord_file.start_address;
+    merged.merge(clone(&other), OverlapPolicy::Error).unwrap();
+    assert_eq!(merged[0x8000..0x8002], [0xCC, 0xDD]);
+    assert_eq!(merged[0x0000], srecord_file[0x0000]);
+
+    other.header_data = Some(b"OTHER".to_vec());
+    other.start_address = Some(0x8000);
+    other.data_chunks[0].address = 0x0000;
+    let mut merged = clone(&srecord_file);
+    merged
+        .merge(clone(&other), OverlapPolicy::Overwrite)
+        .unwrap();
+    assert_eq!(merged[0x0000..0x0002], [0xCC, 0xDD]);
+    assert_eq!(merged[0x0002], srecord_file[0x0002]);
+    assert_eq!(merged.header_data, Some(b"OTHER".to_vec()));
+    assert_eq!(merged.start_address, Some(0x8000));
+
+    let mut merged = clone(&srecord_file);
+    merged
+        .merge(clone(&other), OverlapPolicy::KeepExisting)
+        .unwrap();
+    assert_eq!(merged.data_chunks, srecord_file.data_chunks);
+    assert_eq!(merged.header_data, srecord_file.header_data);
+    assert_eq!(merged.start_address, srecord_file.start_address);
+}
+
+#[test]
+fn test_srecord_file_set_data() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1000, &[0x00, 0x01]);
+    srecord_file.set_data(0x2000, &[0x20, 0x21]);
+    srecord_file.set_data(0x0FF0, &[0xF0]);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x0FF0);
+
+    // Adjacent data extends chunks
+    srecord_file.set_data(0x1002, &[0x02, 0x03]);
+    srecord_file.set_data(0x1FFE, &[0x1E, 0x1F]);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+    assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0x03]);
+    assert_eq!(srecord_file[0x1FFE..0x2002], [0x1E, 0x1F, 0x20, 0x21]);
+
+    // Data spanning several chunks merges them, overwriting existing data
+    srecord_file.set_data(0x0FF0, &[0xAA; 0x20]);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x0FF0);
+    assert_eq!(srecord_file.data_chunks[0].data, [0xAA; 0x20]);
+
+    srecord_file.set_data(0x3000, &[]);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_set_data_overflow() {
+    SRecordFile::new().set_data(u64::MAX, &[0x00]);
+}
+
+#[test]
+fn test_srecord_file_erase() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1000, &[0x00; 0x10]);
+    srecord_file.set_data(0x2000, &[0x20; 0x10]);
+    srecord_file.set_data(0x3000, &[0x30; 0x10]);
+
+    // Empty ranges do nothing
+    let (start, end) = (0x1008, 0x1004);
+    srecord_file.erase(start..end);
+    srecord_file.erase(0x1008..0x1008);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+
+    // Erasing in the middle of a chunk splits it
+    srecord_file.erase(0x1004..0x1008);
+    assert_eq!(srecord_file.data_chunks.len(), 4);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 4);
+    assert_eq!(srecord_file.data_chunks[1].address, 0x1008);
+    assert_eq!(srecord_file.data_chunks[1].data.len(), 8);
+
+    // Erasing across chunks cuts the ends and removes the chunks in between
+    srecord_file.erase(0x100C..0x3008);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+    assert_eq!(srecord_file.data_chunks[1].address, 0x1008);
+    assert_eq!(srecord_file.data_chunks[1].data.len(), 4);
+    assert_eq!(srecord_file.data_chunks[2].address, 0x3008);
+    assert_eq!(srecord_file.data_chunks[2].data, [0x30; 8]);
+
+    // Erasing outside of any data does nothing
+    srecord_file.erase(0x4000..0x5000);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+
+    srecord_file.erase(0..u64::MAX);
+    assert!(srecord_file.data_chunks.is_empty());
+}
+
+#[test]
+fn test_iter_records_align_data_records() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x0005, &[0xAA; 40]);
+    srecord_file.set_data(0x0040, &[0xBB; 20]);
+    let mut options = SerializationOptions::default();
+    options.data_record_size = 16;
+    options.data_record_type = DataRecordType::S1;
+    options.count_record_type = CountRecordType::Omit;
+    options.align_data_records = true;
+    let data_records: Vec<(u64, usize)> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .filter_map(|record| match record {
+            Record::S1Record(data_record) => Some((data_record.address, data_record.data.len())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        data_records,
+        [
+            (0x0005, 11),
+            (0x0010, 16),
+            (0x0020, 13),
+            (0x0040, 16),
+            (0x0050, 4)
+        ]
+    );
+
+    // Data outside of a preserved record layout is aligned, the layout records are not
+    srecord_file.set_record_layout(Some(RecordLayout::new(vec![0x0005..0x000A])));
+    options.preserve_layout = true;
+    let data_records: Vec<(u64, usize)> = srecord_file
+        .iter_records_with_options(&options)
+        .unwrap()
+        .filter_map(|record| match record {
+            Record::S1Record(data_record) => Some((data_record.address, data_record.data.len())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(data_records[..3], [(0x0005, 5), (0x000A, 6), (0x0010, 16)]);
+}
+
+#[test]
+fn test_srecord_file_fill() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1004, &[0x04, 0x05]);
+    srecord_file.set_data(0x100C, &[0x0C, 0x0D]);
+    srecord_file.set_data(0x2000, &[0x20]);
+
+    let (start, end) = (0x1010, 0x1000);
+    srecord_file.fill(start..end, 0xFF);
+    srecord_file.fill(0x1000..0x1000, 0xFF);
+    assert_eq!(srecord_file.data_chunks.len(), 3);
+
+    srecord_file.fill(0x1000..0x1010, 0xFF);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data, [0xFF; 0x10]);
+    assert_eq!(srecord_file[0x2000], 0x20);
+
+    // Filling adjacent to existing data merges the chunks
+    srecord_file.fill(0x1010..0x2000, 0x00);
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 0x1001);
+
+    // Data inside a data chunk is overwritten in place
+    let data_ptr = srecord_file.data_chunks[0].data.as_ptr();
+    srecord_file.fill(0x1004..0x1008, 0xAA);
+    assert_eq!(srecord_file.data_chunks[0].data.as_ptr(), data_ptr);
+    assert_eq!(
+        srecord_file[0x1003..0x1009],
+        [0xFF, 0xAA, 0xAA, 0xAA, 0xAA, 0xFF]
+    );
+    assert_eq!(srecord_file.data_chunks[0].data.len(), 0x1001);
+
+    // Data before and after the range is kept when the range spans several data chunks
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1004, &[0x04, 0x05, 0x06, 0x07]);
+    srecord_file.set_data(0x100C, &[0x0C, 0x0D, 0x0E, 0x0F]);
+    srecord_file.set_data(0x1010, &[0x10]);
+    srecord_file.fill(0x1002..0x100E, 0xFF);
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1002);
+    let mut expected = [0xFF; 0x0F];
+    expected[0x0C..].copy_from_slice(&[0x0E, 0x0F, 0x10]);
+    assert_eq!(srecord_file.data_chunks[0].data, expected);
+
+    srecord_file.fill(0x1006..0x1020, 0x00);
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file[0x1002..0x1006], [0xFF; 4]);
+    assert_eq!(srecord_file[0x1006..0x1020], [0x00; 0x1A]);
+}
+
+#[test]
+fn test_srecord_file_crop() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.set_data(0x1000, &[0xAA; 0x10]);
+
+    let cropped = srecord_file.cropped(&[0x1008..0x2000, 0x0004..0x0008, 0x0006..0x000C]);
+    assert_eq!(cropped.header_data, srecord_file.header_data);
+    assert_eq!(cropped.start_address, srecord_file.start_address);
+    assert_eq!(cropped.data_chunks.len(), 2);
+    assert_eq!(cropped.data_chunks[0].address, 0x0004);
+    assert_eq!(cropped[0x0004..0x000C], srecord_file[0x0004..0x000C]);
+    assert_eq!(cropped.data_chunks[1].address, 0x1008);
+    assert_eq!(cropped.data_chunks[1].data, [0xAA; 8]);
+    assert_eq!(srecord_file.data_chunks.len(), 2);
+
+    srecord_file.crop(&[0x0F00..0x1004]);
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x1000);
+    assert_eq!(srecord_file.data_chunks[0].data, [0xAA; 4]);
+    assert!(srecord_file.header_data.is_some());
+
+    srecord_file.crop(&[]);
+    assert!(srecord_file.data_chunks.is_empty());
+}
+
+#[test]
+fn test_srecord_file_crop_record_layout() {
+    let srecord_str = "S107100000010203E2\nS107100404050607CE\nS1052000AABB75\n";
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+
+    let cropped = srecord_file.cropped(&[0x1002..0x1003, 0x1005..0x1006, 0x2000..0x3000]);
+    assert_eq!(
+        cropped.record_layout().unwrap().data_records,
+        [0x1002..0x1003, 0x1005..0x1006, 0x2000..0x2002]
+    );
+    assert_eq!(
+        srecord_file.record_layout().unwrap().data_records,
+        [0x1000..0x1004, 0x1004..0x1008, 0x2000..0x2002]
+    );
+    assert_eq!(
+        cropped.serialize_original().unwrap(),
+        "S104100202E7\nS104100505E1\nS1052000AABB75\n"
+    );
+
+    srecord_file.crop(&[0x1002..0x1006]);
+    let record_layout = srecord_file.record_layout().unwrap();
+    assert_eq!(record_layout.data_records, [0x1002..0x1004, 0x1004..0x1006]);
+    assert_eq!(record_layout.source(), srecord_str.as_bytes());
+    assert_eq!(
+        srecord_file.serialize_original().unwrap(),
+        "S10510020203E3\nS10510040405DD\n"
+    );
+}
+
+#[test]
+fn test_srecord_file_erase_record_layout() {
+    let srecord_str = "S107100000010203E2\nS107100404050607CE\nS1052000AABB75\n";
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    let mut serialization_options = SerializationOptions::default();
+    serialization_options.data_record_type = DataRecordType::S1;
+    serialization_options.preserve_layout = true;
+    let serialize = |srecord_file: &SRecordFile| -> Vec<String> {
+        srecord_file
+            .iter_records_with_options(&serialization_options)
+            .unwrap()
+            .map(|record| record.serialize())
+            .collect()
+    };
+
+    srecord_file.erase(0x1001..0x1005);
+    assert_eq!(
+        srecord_file.record_layout().unwrap().data_records,
+        [0x1000..0x1001, 0x1005..0x1008, 0x2000..0x2002]
+    );
+    assert_eq!(
+        serialize(&srecord_file),
+        [
+            "S104100000EB",
+            "S1061005050607D2",
+            "S1052000AABB75",
+            "S5030003F9"
+        ]
+    );
+
+    // Data written into the erased range is not part of the layout anymore
+    srecord_file.set_data(0x1002, &[0xFF]);
+    assert_eq!(
+        serialize(&srecord_file),
+        [
+            "S104100000EB",
+            "S1061005050607D2",
+            "S1052000AABB75",
+            "S1041002FFEA",
+            "S5030004F8"
+        ]
+    );
+}
+
+#[test]
+fn test_srecord_file_offset() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let original = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+
+    srecord_file.offset(0x8000).unwrap();
+    assert_eq!(srecord_file.data_chunks[0].address, 0x8000);
+    assert_eq!(
+        srecord_file.data_chunks[0].data,
+        original.data_chunks[0].data
+    );
+    assert_eq!(srecord_file.start_address, Some(0x8000));
+
+    assert_eq!(
+        srecord_file.offset(-0x8001),
+        Err(OffsetError::AddressUnderflow)
+    );
+    srecord_file.offset(i64::MAX).unwrap();
+    assert_eq!(
+        srecord_file.offset(i64::MAX),
+        Err(OffsetError::AddressOverflow)
+    );
+    assert_eq!(
+        srecord_file.data_chunks[0].address,
+        0x8000 + i64::MAX as u64
+    );
+
+    // The start address is checked even without data
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.start_address = Some(0x10);
+    assert_eq!(
+        srecord_file.offset(-0x11),
+        Err(OffsetError::AddressUnderflow)
+    );
+    srecord_file.offset(-0x10).unwrap();
+    assert_eq!(srecord_file.start_address, Some(0));
+}
+
+#[test]
+fn test_srecord_file_extract_records_from_text() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let mut log = String::from("Starting download\n\n");
+    for (index, line) in srecord_str.lines().enumerate() {
+        log.push_str(&format!("[{index:04}] uart0: {line}  \r\n"));
+        log.push_str("uart0: Sent OK\n");
+    }
+
+    let (extracted, stats) =
+        SRecordFile::extract_records_from_text(&log, &ParseOptions::default()).unwrap();
+    assert_eq!(extracted.header_data, srecord_file.header_data);
+    assert_eq!(extracted.data_chunks, srecord_file.data_chunks);
+    assert_eq!(extracted.start_address, srecord_file.start_address);
+    let num_lines = srecord_str.lines().count();
+    assert_eq!(stats.num_data_records, num_lines - 3);
+    assert_eq!(stats.warnings.len(), 2 + num_lines);
+    assert_eq!(stats.warnings[0].line_index, 0);
+    assert_eq!(stats.warnings[1].line_index, 1);
+    assert_eq!(
+        stats.warnings[1].error_type,
+        ErrorType::InvalidFirstCharacter
+    );
+    assert_eq!(stats.warnings[2].line_index, 3);
+    assert_eq!(stats.warnings[2].error_type, ErrorType::InvalidRecordType);
+
+    let mut options = ParseOptions::default();
+    options.strict = true;
+    assert_eq!(
+        SRecordFile::extract_records_from_text(&log, &options)
+            .err()
+            .unwrap()
+            .error_type,
+        ErrorType::InvalidRecordType
+    );
+    let (extracted, stats) =
+        SRecordFile::extract_records_from_text(&srecord_str, &options).unwrap();
+    assert_eq!(extracted.data_chunks, srecord_file.data_chunks);
+    assert!(stats.warnings.is_empty());
+
+    // Candidates with data past the highest address of their record type are skipped
+    let (extracted, stats) = SRecordFile::extract_records_from_text(
+        "noise S105FFFFAABB97\nnoise S307FFFFFFFFAABB97\nS1051000AABB85\n",
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        extracted.data_chunks,
+        [DataChunk {
+            address: 0x1000,
+            data: vec![0xAA, 0xBB]
+        }]
+    );
+    assert_eq!(stats.warnings.len(), 2);
+    for (line_index, warning) in stats.warnings.iter().enumerate() {
+        assert_eq!(warning.line_index, line_index);
+        assert_eq!(warning.error_type, ErrorType::AddressOverflowForRecordType);
+    }
+}
+
+#[test]
+fn test_srecord_file_split_at() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.set_data(0x1000, &[0xAA; 4]);
+    srecord_file.start_address = Some(0x1000);
+
+    let (below, above) = srecord_file.split_at(0x0010);
+    assert_eq!(below.data_chunks.len(), 1);
+    assert_eq!(below.data_chunks[0].address, 0x0000);
+    assert_eq!(below.data_chunks[0].data, srecord_file[0x0000..0x0010]);
+    assert_eq!(above.data_chunks.len(), 2);
+    assert_eq!(above.data_chunks[0].address, 0x0010);
+    assert_eq!(above.data_chunks[1].data, [0xAA; 4]);
+    assert_eq!(below.header_data, srecord_file.header_data);
+    assert_eq!(above.header_data, srecord_file.header_data);
+    assert_eq!(below.start_address, None);
+    assert_eq!(above.start_address, Some(0x1000));
+
+    // Splitting between chunks does not cut any chunk
+    let (below, above) = srecord_file.split_at(0x0800);
+    assert_eq!(below.data_chunks.len(), 1);
+    assert_eq!(above.data_chunks.len(), 1);
+
+    let (below, above) = srecord_file.split_at(0);
+    assert!(below.data_chunks.is_empty());
+    assert_eq!(above.data_chunks, srecord_file.data_chunks);
+    assert_eq!(above.start_address, Some(0x1000));
+}
+
+#[test]
+fn test_srecord_file_split_by_size() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.set_data(0x1000, &[0xAA; 0x10]);
+    let num_bytes: usize = srecord_file
+        .data_chunks
+        .iter()
+        .map(|data_chunk| data_chunk.data.len())
+        .sum();
+
+    let files = srecord_file.split_by_size(0x20, false);
+    assert_eq!(files.len(), num_bytes.div_ceil(0x20));
+    for (index, file) in files.iter().enumerate() {
+        let file_bytes: usize = file
+            .data_chunks
+            .iter()
+            .map(|data_chunk| data_chunk.data.len())
+            .sum();
+        match index == files.len() - 1 {
+            true => {
+                assert_eq!(file_bytes, (num_bytes - 1) % 0x20 + 1);
+                assert_eq!(file.start_address, srecord_file.start_address);
+            }
+            false => {
+                assert_eq!(file_bytes, 0x20);
+                assert_eq!(file.start_address, None);
+            }
+        }
+        assert_eq!(file.header_data, srecord_file.header_data);
+        for data_chunk in file.data_chunks.iter() {
+            assert_eq!(
+                data_chunk.data,
+                srecord_file[data_chunk.address..data_chunk.end_address()]
+            );
+        }
+    }
+
+    let files = srecord_file.split_by_size(u64::MAX, true);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].data_chunks, srecord_file.data_chunks);
+
+    let files = SRecordFile::new().split_by_size(0x20, false);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].data_chunks.is_empty());
+}
+
+#[test]
+fn test_srecord_file_split_by_chunk() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s19").unwrap();
+    let mut srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    srecord_file.set_data(0x1000, &[0xAA; 0x10]);
+    srecord_file.set_data(0x2000, &[0xBB; 0x10]);
+
+    let files = srecord_file.split_by_chunk(true);
+    assert_eq!(files.len(), 3);
+    for (file, data_chunk) in files.iter().zip(srecord_file.data_chunks.iter()) {
+        assert_eq!(file.data_chunks.len(), 1);
+        assert_eq!(file.data_chunks[0], *data_chunk);
+        assert_eq!(file.header_data, srecord_file.header_data);
+        assert_eq!(file.start_address, srecord_file.start_address);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_split_by_size_zero() {
+    SRecordFile::new().split_by_size(0, false);
+}
+
+#[test]
+fn test_srecord_file_gaps() {
+    let mut srecord_file = SRecordFile::new();
+    assert!(srecord_file.gaps().next().is_none());
+    assert_eq!(
+        srecord_file.gaps_within(0x0000..0x0100).collect::<Vec<_>>(),
+        [0x0000..0x0100]
+    );
+
+    srecord_file.set_data(0x0000, &[0x00; 0x40]);
+    assert!(srecord_file.gaps().next().is_none());
+    srecord_file.set_data(0x0080, &[0x00; 0x10]);
+    srecord_file.set_data(0x0100, &[0x00; 0x10]);
+    assert_eq!(
+        srecord_file.gaps().collect::<Vec<_>>(),
+        [0x0040..0x0080, 0x0090..0x0100]
+    );
+
+    // Vector table region without holes
+    assert!(srecord_file.gaps_within(0x0000..0x0040).next().is_none());
+    assert_eq!(
+        srecord_file.gaps_within(0x0020..0x0088).collect::<Vec<_>>(),
+        [0x0040..0x0080]
+    );
+    assert_eq!(
+        srecord_file.gaps_within(0x0088..0x0200).collect::<Vec<_>>(),
+        [0x0090..0x0100, 0x0110..0x0200]
+    );
+    assert!(srecord_file.gaps_within(0x0100..0x0100).next().is_none());
+}
+
+#[test]
+fn test_data_reader() {
+    let mut srecord_file = SRecordFile::new();
+    let mut reader = srecord_file.reader();
+    assert!(reader.get(0x0000).is_none());
+    assert!(reader.get_range(0x0000..0x0001).is_none());
+
+    srecord_file.set_data(0x1000, &[0x00, 0x01, 0x02, 0x03]);
+    srecord_file.set_data(0x2000, &[0x10, 0x11]);
+    srecord_file.set_data(0x3000, &[0x20, 0x21, 0x22]);
+    let mut reader = srecord_file.reader();
+
+    // Sequential reads, including gaps between data chunks
+    for address in 0x0FFF..0x3004 {
+        assert_eq!(reader.get(address), srecord_file.get(address).copied());
+    }
+    // Random reads, jumping back and forth between data chunks
+    for address in [
+        0x3002, 0x1000, 0x2001, 0x3000, 0x2002, 0x1003, 0x0000, 0x3001,
+    ] {
+        assert_eq!(reader.get(address), srecord_file.get(address).copied());
+    }
+
+    assert_eq!(
+        reader.get_range(0x1001..0x1004),
+        Some([0x01, 0x02, 0x03].as_slice())
+    );
+    assert_eq!(
+        reader.get_range(0x3000..0x3003),
+        Some([0x20, 0x21, 0x22].as_slice())
+    );
+    assert_eq!(reader.get_range(0x2001..0x2001), Some([].as_slice()));
+    assert!(reader.get_range(0x1002..0x1005).is_none());
+    assert!(reader.get_range(0x0FFF..0x1001).is_none());
+    assert!(reader.get_range(0x2002..0x2003).is_none());
+}
+
+#[test]
+fn test_srecord_file_address_range() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.min_address(), None);
+    assert_eq!(srecord_file.max_address(), None);
+    assert_eq!(srecord_file.address_range(), None);
+
+    srecord_file.set_data(0x1000, &[0x00]);
+    assert_eq!(srecord_file.min_address(), Some(0x1000));
+    assert_eq!(srecord_file.max_address(), Some(0x1000));
+    assert_eq!(srecord_file.address_range(), Some(0x1000..0x1001));
+
+    srecord_file.set_data(0x0800, &[0x00; 0x10]);
+    srecord_file.set_data(0x2000, &[0x00; 0x10]);
+    assert_eq!(srecord_file.min_address(), Some(0x0800));
+    assert_eq!(srecord_file.max_address(), Some(0x200F));
+    assert_eq!(srecord_file.address_range(), Some(0x0800..0x2010));
+
+    srecord_file.set_data(0xFFFF_FFFF_FFFF_FFF0, &[0x00; 0x0F]);
+    assert_eq!(srecord_file.max_address(), Some(0xFFFF_FFFF_FFFF_FFFE));
+    assert_eq!(
+        srecord_file.address_range(),
+        Some(0x0800..0xFFFF_FFFF_FFFF_FFFF)
+    );
+}
+
+#[test]
+fn test_srecord_file_start_address_info() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.start_address_info(), None);
+
+    srecord_file.start_address = Some(0x1000);
+    assert_eq!(
+        srecord_file.start_address_info(),
+        Some(StartAddressInfo {
+            address: Address::new(0x1000),
+            record_type: Some(RecordType::S9),
+            points_to_data: false,
+        })
+    );
+
+    srecord_file.set_data(0x1000, &[0x00; 0x10]);
+    assert!(srecord_file.start_address_info().unwrap().is_valid());
+
+    for (start_address, record_type) in [
+        (0xFFFF, Some(RecordType::S9)),
+        (0x1_0000, Some(RecordType::S8)),
+        (0xFF_FFFF, Some(RecordType::S8)),
+        (0x100_0000, Some(RecordType::S7)),
+        (0xFFFF_FFFF, Some(RecordType::S7)),
+        (0x1_0000_0000, None),
+    ] {
+        srecord_file.start_address = Some(start_address);
+        let start_address_info = srecord_file.start_address_info().unwrap();
+        assert_eq!(start_address_info.record_type, record_type);
+        assert!(!start_address_info.points_to_data);
+        assert!(!start_address_info.is_valid());
+    }
+
+    srecord_file.set_data(0x1_0000_0000, &[0x00]);
+    let start_address_info = srecord_file.start_address_info().unwrap();
+    assert!(start_address_info.points_to_data);
+    assert!(!start_address_info.is_valid());
+}
+
+#[test]
+fn test_srecord_file_size() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.header_data = Some(b"HDR".to_vec());
+    srecord_file.start_address = Some(0x1000);
+    assert!(srecord_file.is_empty());
+    assert_eq!(srecord_file.chunk_count(), 0);
+    assert_eq!(srecord_file.total_data_len(), 0);
+
+    srecord_file.set_data(0x1000, &[0x00; 0x10]);
+    srecord_file.set_data(0x2000, &[0x00; 0x20]);
+    assert!(!srecord_file.is_empty());
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file.total_data_len(), 0x30);
+
+    // Adjacent data is merged into one chunk
+    srecord_file.set_data(0x1010, &[0x00; 0x08]);
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file.total_data_len(), 0x38);
+
+    srecord_file.erase(0x0000..0x3000);
+    assert!(srecord_file.is_empty());
+    assert_eq!(srecord_file.total_data_len(), 0);
+}
+
+#[test]
+fn test_srecord_file_contains() {
+    let mut srecord_file = SRecordFile::new();
+    assert!(!srecord_file.contains_address(0x0000));
+    assert!(!srecord_file.contains_range(0x0000..0x0001));
+    assert!(srecord_file.contains_range(0x0000..0x0000));
+
+    srecord_file.set_data(0x1000, &[0x00; 0x10]);
+    srecord_file.set_data(0x1020, &[0x00; 0x10]);
+    assert!(!srecord_file.contains_address(0x0FFF));
+    assert!(srecord_file.contains_address(0x1000));
+    assert!(srecord_file.contains_address(0x100F));
+    assert!(!srecord_file.contains_address(0x1010));
+    assert!(srecord_file.contains_address(0x1020));
+    assert!(!srecord_file.contains_address(0x1030));
+
+    assert!(srecord_file.contains_range(0x1000..0x1010));
+    assert!(srecord_file.contains_range(0x1004..0x1008));
+    assert!(!srecord_file.contains_range(0x0FFF..0x1010));
+    assert!(!srecord_file.contains_range(0x1000..0x1011));
+    // Ranges spanning a gap are not contained
+    assert!(!srecord_file.contains_range(0x1000..0x1030));
+    let (start, end) = (0x1008, 0x1004);
+    assert!(srecord_file.contains_range(start..end));
+}
+
+#[test]
+fn test_srecord_file_iter_bytes() {
+    let mut srecord_file = SRecordFile::new();
+    assert!(srecord_file.iter_bytes().next().is_none());
+    assert!(srecord_file.iter_bytes_mut().next().is_none());
+
+    srecord_file.set_data(0x2000, &[0x20, 0x21]);
+    srecord_file.set_data(0x1000, &[0x10, 0x11, 0x12]);
+    srecord_file.set_data(0xFFFF_FFFF_FFFF_FFFD, &[0xF0, 0xF1]);
+    assert_eq!(
+        srecord_file.iter_bytes().collect::<Vec<_>>(),
+        [
+            (0x1000, 0x10),
+            (0x1001, 0x11),
+            (0x1002, 0x12),
+            (0x2000, 0x20),
+            (0x2001, 0x21),
+            (0xFFFF_FFFF_FFFF_FFFD, 0xF0),
+            (0xFFFF_FFFF_FFFF_FFFE, 0xF1),
+        ]
+    );
+    assert_eq!(
+        srecord_file
+            .iter_bytes()
+            .map(|(_, byte)| byte as u32)
+            .sum::<u32>(),
+        0x10 + 0x11 + 0x12 + 0x20 + 0x21 + 0xF0 + 0xF1
+    );
+
+    for (address, byte) in srecord_file.iter_bytes_mut() {
+        *byte = (address >> 8) as u8;
+    }
+    assert_eq!(srecord_file[0x1000..0x1003], [0x10, 0x10, 0x10]);
+    assert_eq!(srecord_file[0x2000..0x2002], [0x20, 0x20]);
+    assert_eq!(
+        srecord_file[0xFFFF_FFFF_FFFF_FFFD..0xFFFF_FFFF_FFFF_FFFF],
+        [0xFF, 0xFF]
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_tektronix_conversion() {
+    let srecord_str = fs::read_to_string("tests/srec_files/wikipedia.s37").unwrap();
+    let srecord_file = SRecordFile::from_str(&srecord_str).unwrap();
+    let tektronix_string = srecord_file.to_tektronix_string();
+    assert!(tektronix_string.lines().all(|line| line.starts_with('%')));
+    let converted = SRecordFile::from_tektronix_str(&tektronix_string).unwrap();
+    assert_eq!(converted.data_chunks, srecord_file.data_chunks);
+    assert_eq!(converted.start_address, srecord_file.start_address);
+
+    // Symbol records are skipped, and records may be out of order
+    let tektronix_str = "%173124main10_start81000\r\n%0C63041001CC\r\n%0E64341000AABB\r\n\r\n";
+    assert_eq!(
+        SRecordFile::from_tektronix_str(tektronix_str)
+            .unwrap_err()
+            .error_type,
+        ErrorType::OverlappingData
+    );
+    let tektronix_str = "%173124main10_start81000\n%0E64341000AABB\n%0A81741000\n";
+    let srecord_file = SRecordFile::from_tektronix_str(tektronix_str).unwrap();
+    assert_eq!(srecord_file.data_chunks.len(), 1);
+    assert_eq!(srecord_file[0x1000..0x1002], [0xAA, 0xBB]);
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+
+    // Addresses above 32 bits
+    let srecord_file = SRecordFile::from_tektronix_str("%186FF0FFFFFFFFFFFFFFFE01").unwrap();
+    assert_eq!(srecord_file[0xFFFF_FFFF_FFFF_FFFE], 0x01);
+    assert_eq!(
+        srecord_file.to_tektronix_string(),
+        "%186FF0FFFFFFFFFFFFFFFE01\n%0E81E800000000\n"
+    );
+
+    for (tektronix_str, error_type) in [
+        ("S0E64341000AABB", ErrorType::InvalidFirstCharacter),
+        ("%0", ErrorType::EolWhileParsingByteCount),
+        ("%XX64341000AABB", ErrorType::InvalidByteCount),
+        ("%0F64341000AABB", ErrorType::EolWhileParsingData),
+        ("%0D64341000AABB", ErrorType::LineNotTerminatedAfterChecksum),
+        ("%0E74341000AABB", ErrorType::InvalidRecordType),
+        ("%0E6XX41000AABB", ErrorType::InvalidChecksum),
+        (
+            "%0E64441000AABB",
+            ErrorType::CalculatedChecksumNotMatchingParsedChecksum,
+        ),
+        (
+            "%0A81741000\n%0E64341000AABB",
+            ErrorType::RecordAfterTerminator,
+        ),
+        ("%0A81741000\n%0A81741000", ErrorType::RecordAfterTerminator),
+    ] {
+        assert_eq!(
+            SRecordFile::from_tektronix_str(tektronix_str)
+                .unwrap_err()
+                .error_type,
+            error_type,
+            "{tektronix_str}"
+        );
+    }
+}
+
+#[test]
+fn test_indexed_srecord_file() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.header_data = Some(b"HDR".to_vec());
+    srecord_file.set_data(0x1000, &(0..=255).collect::<Vec<u8>>());
+    srecord_file.set_data(0x2000, &[0xAA; 0x30]);
+    srecord_file.set_data(0x1_0000_0000 - 0x10, &[0xBB; 0x10]);
+    srecord_file.start_address = Some(0x1000);
+    let srecord_str = srecord_file.serialize_original().unwrap();
+
+    let directory = std::env::temp_dir();
+    let path = directory.join(format!("srex_indexed_{}.s37", std::process::id()));
+    let index_path = directory.join(format!("srex_indexed_{}.idx", std::process::id()));
+    fs::write(&path, &srecord_str).unwrap();
+    let _ = fs::remove_file(&index_path);
+
+    let indexed_file = IndexedSRecordFile::open(&path).unwrap();
+    assert_eq!(indexed_file.start_address(), Some(0x1000));
+    for range in [
+        0x1000..0x1100,
+        0x1005..0x1006,
+        0x100F..0x1011,
+        0x2010..0x2030,
+        0xFFFF_FFF0..0x1_0000_0000,
+        0x0FFF..0x1001,
+        0x10FF..0x1101,
+        0x2000..0x3000,
+    ] {
+        assert_eq!(
+            indexed_file.get(range.clone()).unwrap().as_deref(),
+            srecord_file.get(range.clone()),
+            "{range:X?}"
+        );
+    }
+    assert_eq!(indexed_file.get(0x3000..0x3000).unwrap(), Some(Vec::new()));
+    // Ranges far larger than the file are not allocated up front
+    assert_eq!(indexed_file.get(0x1000_0000..u64::MAX).unwrap(), None);
+    assert_eq!(indexed_file.get(0x1000..u64::MAX).unwrap(), None);
+
+    // The index is saved on the first open, and used on later opens
+    let indexed_file = IndexedSRecordFile::open_with_index(&path, &index_path).unwrap();
+    assert!(index_path.exists());
+    assert_eq!(
+        indexed_file.get(0x1080..0x1084).unwrap(),
+        Some(vec![0x80, 0x81, 0x82, 0x83])
+    );
+    let indexed_file = IndexedSRecordFile::open_with_index(&path, &index_path).unwrap();
+    assert_eq!(indexed_file.start_address(), Some(0x1000));
+    assert_eq!(
+        indexed_file.get(0x2000..0x2002).unwrap(),
+        Some(vec![0xAA, 0xAA])
+    );
+
+    // An index of a file of a different length is rebuilt
+    let srecord_str = srecord_str.replace("S70500001000EA\n", "");
+    fs::write(&path, &srecord_str).unwrap();
+    let indexed_file = IndexedSRecordFile::open_with_index(&path, &index_path).unwrap();
+    assert_eq!(indexed_file.start_address(), None);
+    assert_eq!(
+        indexed_file.get(0x2000..0x2002).unwrap(),
+        Some(vec![0xAA, 0xAA])
+    );
+
+    fs::write(&path, "S107100000010203E2\nS1051002AABB83\n").unwrap();
+    assert!(matches!(
+        IndexedSRecordFile::open(&path),
+        Err(ReadFileError::Parse(SRecordParseError {
+            error_type: ErrorType::OverlappingData
+        }))
+    ));
+    fs::write(&path, "S107100000010203E2\nS105FFFFAABB97\n").unwrap();
+    assert!(matches!(
+        IndexedSRecordFile::open(&path),
+        Err(ReadFileError::Parse(SRecordParseError {
+            error_type: ErrorType::AddressOverflowForRecordType
+        }))
+    ));
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&index_path).unwrap();
+    assert!(matches!(
+        IndexedSRecordFile::open(&path),
+        Err(ReadFileError::Io(_))
+    ));
+}
+
+#[test]
+fn test_srecord_file_chunks() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.chunks().len(), 0);
+    assert_eq!(srecord_file.chunks_mut().len(), 0);
+
+    srecord_file.set_data(0x2000, &[0x20, 0x21]);
+    srecord_file.set_data(0x1000, &[0x10, 0x11, 0x12]);
+    let chunks: Vec<_> = srecord_file
+        .chunks()
+        .map(|data_chunk| (data_chunk.address, data_chunk.data.len()))
+        .collect();
+    assert_eq!(chunks, [(0x1000, 3), (0x2000, 2)]);
+
+    for data_chunk in srecord_file.chunks_mut() {
+        data_chunk.data.iter_mut().for_each(|byte| *byte = !*byte);
+    }
+    assert_eq!(srecord_file[0x1000..0x1003], [0xEF, 0xEE, 0xED]);
+    assert_eq!(srecord_file[0x2000..0x2002], [0xDF, 0xDE]);
+
+    assert_eq!(
+        srecord_file.into_chunks(),
+        [
+            DataChunk {
+                address: 0x1000,
+                data: vec![0xEF, 0xEE, 0xED],
+            },
+            DataChunk {
+                address: 0x2000,
+                data: vec![0xDF, 0xDE],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_srecord_file_from_iter() {
+    let srecord_file: SRecordFile = std::iter::empty::<(u64, u8)>().collect();
+    assert!(srecord_file.is_empty());
+
+    let bytes = (0x1000..0x1100)
+        .chain(0x0F00..0x0F10)
+        .chain(0x1100..0x1110)
+        .map(|address| (address, address as u8));
+    let srecord_file: SRecordFile = bytes.collect();
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file.address_range(), Some(0x0F00..0x1110));
+    assert!(srecord_file
+        .iter_bytes()
+        .all(|(address, byte)| byte == address as u8));
+
+    // Later bytes at the same address are kept
+    let bytes = [(0x10, 0xAA), (0x11, 0xBB), (0x10, 0xCC), (0x0F, 0xDD)];
+    let srecord_file: SRecordFile = bytes.into_iter().collect();
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file[0x0F..0x12], [0xDD, 0xCC, 0xBB]);
+
+    let segments = vec![
+        (0x2000, vec![0x00; 0x10]),
+        (0x1000, vec![0x11; 0x10]),
+        (0x2008, vec![0x22; 0x10]),
+        (0x1010, Vec::new()),
+        (0x1010, vec![0x33; 0x04]),
+    ];
+    let srecord_file: SRecordFile = segments.into_iter().collect();
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file[0x100E..0x1012], [0x11, 0x11, 0x33, 0x33]);
+    assert_eq!(srecord_file[0x2006..0x200A], [0x00, 0x00, 0x22, 0x22]);
+    assert_eq!(srecord_file.address_range(), Some(0x1000..0x2018));
+
+    // Later segments are kept, also when they start at a lower address
+    let segments = vec![
+        (0x14, vec![0xAA; 4]),
+        (0x10, vec![0xBB; 6]),
+        (0x12, vec![0xCC]),
+    ];
+    let srecord_file: SRecordFile = segments.into_iter().collect();
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(
+        srecord_file[0x10..0x18],
+        [0xBB, 0xBB, 0xCC, 0xBB, 0xBB, 0xBB, 0xAA, 0xAA]
+    );
+}
+
+#[test]
+fn test_srecord_file_from_binary() {
+    let srecord_file = SRecordFile::from_binary(0x1000, &[]);
+    assert!(srecord_file.is_empty());
+    assert_eq!(srecord_file.header_data, None);
+    assert_eq!(srecord_file.start_address, None);
+
+    let data: Vec<u8> = (0..=0xFF).collect();
+    let srecord_file = SRecordFile::from_binary(0x08000000, &data)
+        .with_header("app.bin")
+        .with_start_address(0x08000004);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file.address_range(), Some(0x08000000..0x08000100));
+    assert_eq!(srecord_file[0x08000000..0x08000100], data);
+    assert_eq!(srecord_file.header_data, Some(b"app.bin".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0x08000004));
+
+    let srecord_file = SRecordFile::from_str(&srecord_file.serialize_original().unwrap()).unwrap();
+    assert_eq!(srecord_file[0x08000000..0x08000100], data);
+    assert_eq!(srecord_file.header_data, Some(b"app.bin".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0x08000004));
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_from_binary_overflow() {
+    SRecordFile::from_binary(u64::MAX, &[0x00]);
+}
+
+#[test]
+fn test_srecord_file_clone_eq_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+
+    let hash = |srecord_file: &SRecordFile| {
+        let mut hasher = DefaultHasher::new();
+        srecord_file.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let srecord_str = "S00600004844521B\nS107100000010203E2\nS9031000EC";
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    let mut cloned = srecord_file.clone();
+    assert_eq!(cloned, srecord_file);
+    assert_eq!(hash(&cloned), hash(&srecord_file));
+    assert_eq!(SRecordFile::from_str(srecord_str).unwrap(), srecord_file);
+
+    cloned[0x1000] = 0xFF;
+    assert_ne!(cloned, srecord_file);
+    assert_eq!(srecord_file[0x1000], 0x00);
+    cloned[0x1000] = 0x00;
+    cloned.start_address = None;
+    assert_ne!(cloned, srecord_file);
+
+    let files: HashSet<SRecordFile> = [srecord_file.clone(), cloned, srecord_file].into();
+    assert_eq!(files.len(), 2);
+
+    // The record layout is not compared, e.g. the line endings of the parsed text
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (with_layout, _) =
+        SRecordFile::from_str_with_options(&srecord_str.replace('\n', "\r\n"), &options).unwrap();
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    assert!(with_layout.record_layout().is_some());
+    assert_eq!(with_layout, srecord_file);
+    assert_eq!(hash(&with_layout), hash(&srecord_file));
+
+    let data_chunk = DataChunk {
+        address: 0x1000,
+        data: vec![0x00, 0x01],
+    };
+    assert_eq!(data_chunk.clone(), data_chunk);
+    let record = Record::S1Record(DataRecord {
+        address: 0x1000,
+        data: &data_chunk.data,
+    });
+    let owned_record = OwnedRecord::from(record.clone());
+    assert_eq!(record.clone(), record);
+    let records: HashSet<OwnedRecord> = [owned_record.clone(), owned_record].into();
+    assert_eq!(records.len(), 1);
+}
+
+#[test]
+fn test_srecord_file_clear() {
+    let srecord_str = "S00600004844521B\nS107100000010203E2\nS9031000EC";
+    let mut options = ParseOptions::default();
+    options.record_layout = true;
+    let (mut srecord_file, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    assert!(srecord_file.record_layout().is_some());
+    srecord_file.clear();
+    assert!(srecord_file.is_empty());
+    assert!(srecord_file.record_layout().is_none());
+    assert_eq!(srecord_file.header_data, Some(b"HDR".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+
+    srecord_file.set_header("NEW");
+    srecord_file.set_start_address(0x2000);
+    srecord_file.set_data(0x2000, &[0xAA]);
+    assert_eq!(srecord_file.header_data, Some(b"NEW".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0x2000));
+    assert!(srecord_file.start_address_info().unwrap().points_to_data);
+
+    srecord_file.clear_all();
+    assert!(srecord_file.is_empty());
+    assert_eq!(srecord_file.header_data, None);
+    assert_eq!(srecord_file.start_address, None);
+    assert!(srecord_file.record_layout().is_none());
+}
+
+#[test]
+fn test_srecord_file_from_binary_sparse() {
+    // 16 KiB of erased flash with two programmed regions and a short run of filler in one of them
+    let mut data = vec![0xFF; 0x4000];
+    data[0x0000..0x0100].fill(0x11);
+    data[0x0040..0x0048].fill(0xFF);
+    data[0x2000..0x2010].fill(0x22);
+    let srecord_file = SRecordFile::from_binary_sparse(0x08000000, &data, 0xFF, 16);
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file.data_chunks[0].address, 0x08000000);
+    assert_eq!(srecord_file.data_chunks[0].data, data[0x0000..0x0100]);
+    assert_eq!(srecord_file.data_chunks[1].address, 0x08002000);
+    assert_eq!(srecord_file.data_chunks[1].data, [0x22; 0x10]);
+    assert_eq!(
+        srecord_file.to_binary_range(0x08000000..0x08004000, 0xFF),
+        data
+    );
+
+    // Runs of exactly max_filler_run bytes are kept, including at the start and end
+    let data = [0xFF, 0xFF, 0x01, 0xFF, 0xFF, 0xFF, 0x02, 0xFF, 0xFF];
+    let srecord_file = SRecordFile::from_binary_sparse(0x10, &data, 0xFF, 2);
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file[0x10..0x13], [0xFF, 0xFF, 0x01]);
+    assert_eq!(srecord_file[0x16..0x19], [0x02, 0xFF, 0xFF]);
+
+    let srecord_file = SRecordFile::from_binary_sparse(0x10, &data, 0xFF, usize::MAX);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file[0x10..0x19], data);
+    assert!(SRecordFile::from_binary_sparse(0x10, &[0x00; 4], 0x00, 0).is_empty());
+    assert!(SRecordFile::from_binary_sparse(0x10, &[], 0x00, 0).is_empty());
+}
+
+#[test]
+fn test_srecord_file_to_binary() {
+    assert!(SRecordFile::new().to_binary(0xFF).is_empty());
+    assert_eq!(
+        SRecordFile::new().to_binary_range(0x10..0x14, 0xFF),
+        [0xFF; 4]
+    );
+
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1000, &[0x11; 4]);
+    srecord_file.set_data(0x1008, &[0x22; 2]);
+    srecord_file.set_data(0x2000, &[0x33]);
+    let image = srecord_file.to_binary(0xFF);
+    assert_eq!(image.len(), 0x1001);
+    assert_eq!(
+        image[0x0000..0x000C],
+        [0x11, 0x11, 0x11, 0x11, 0xFF, 0xFF, 0xFF, 0xFF, 0x22, 0x22, 0xFF, 0xFF]
+    );
+    assert!(image[0x000A..0x1000].iter().all(|&byte| byte == 0xFF));
+    assert_eq!(image[0x1000], 0x33);
+
+    // Data chunks are cut at the edges of the range
+    assert_eq!(
+        srecord_file.to_binary_range(0x1002..0x100A, 0x00),
+        [0x11, 0x11, 0x00, 0x00, 0x00, 0x00, 0x22, 0x22]
+    );
+    assert_eq!(
+        srecord_file.to_binary_range(0x0FFE..0x1001, 0x00),
+        [0x00, 0x00, 0x11]
+    );
+    assert!(srecord_file
+        .to_binary_range(0x1004..0x1004, 0x00)
+        .is_empty());
+
+    // The image is the same as the data after filling all gaps
+    let gaps: Vec<_> = srecord_file.gaps().collect();
+    for gap in gaps {
+        srecord_file.fill(gap, 0xFF);
+    }
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file[0x1000..0x2001], image);
+}
+
+#[test]
+fn test_srecord_file_read_int() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.set_data(0x1000, &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+    srecord_file.set_data(0x100A, &[0xAA, 0xBB]);
+    assert_eq!(srecord_file.read_u16_le(0x1000), Some(0x0201));
+    assert_eq!(srecord_file.read_u16_be(0x1000), Some(0x0102));
+    assert_eq!(srecord_file.read_u32_le(0x1004), Some(0x08070605));
+    assert_eq!(srecord_file.read_u32_be(0x1004), Some(0x05060708));
+    assert_eq!(srecord_file.read_u64_le(0x1000), Some(0x0807060504030201));
+    assert_eq!(srecord_file.read_u64_be(0x1000), Some(0x0102030405060708));
+    assert_eq!(srecord_file.read_u16_be(0x100A), Some(0xAABB));
+    for size in 1..=8 {
+        assert_eq!(
+            srecord_file.read_int(0x1000, size, Endianness::Little),
+            Some(0x0807060504030201 & (u64::MAX >> (64 - 8 * size)))
+        );
+        assert_eq!(
+            srecord_file.read_int(0x1000, size, Endianness::Big),
+            Some(0x0102030405060708 >> (64 - 8 * size))
+        );
+    }
+
+    // Reads crossing the end of the data or a gap return None
+    assert_eq!(srecord_file.read_u64_le(0x1001), None);
+    assert_eq!(srecord_file.read_u32_be(0x1008), None);
+    assert_eq!(srecord_file.read_u16_le(0x100B), None);
+    assert_eq!(srecord_file.read_int(0x1007, 4, Endianness::Little), None);
+    assert_eq!(srecord_file.read_u16_le(u64::MAX), None);
+
+    let data_chunk = &srecord_file.data_chunks[0];
+    assert_eq!(data_chunk.read_u16_be(0x1006), Some(0x0708));
+    assert_eq!(data_chunk.read_u32_le(0x1002), Some(0x06050403));
+    assert_eq!(data_chunk.read_u64_be(0x1000), Some(0x0102030405060708));
+    assert_eq!(
+        data_chunk.read_int(0x1005, 3, Endianness::Big),
+        Some(0x060708)
+    );
+    assert_eq!(data_chunk.read_u16_le(0x0FFF), None);
+    assert_eq!(data_chunk.read_u64_le(0x1001), None);
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_read_int_invalid_size() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    srecord_file.read_int(0x1000, 9, Endianness::Little);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_srecord_file_read_padded() {
+    let srecord_file =
+        SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D\nS1051010EEFFED").unwrap();
+    assert_eq!(
+        srecord_file.read_padded(0x0FFE..0x1012, 0x00),
+        [
+            0x00, 0x00, 0xAA, 0xBB, 0x00, 0x00, 0xCC, 0xDD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0xEE, 0xFF
+        ]
+    );
+    assert_eq!(
+        srecord_file.read_padded(0x1001..0x1005, 0xFF),
+        [0xBB, 0xFF, 0xFF, 0xCC]
+    );
+    assert_eq!(srecord_file.read_padded(0x1004..0x1006, 0xFF), [0xCC, 0xDD]);
+    assert_eq!(srecord_file.read_padded(0x2000..0x2002, 0xFF), [0xFF, 0xFF]);
+    assert!(srecord_file.read_padded(0x1000..0x1000, 0xFF).is_empty());
+}
+
+#[test]
+fn test_srecord_cursor() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+    let mut cursor = srecord_file.cursor(0x00);
+    assert_eq!(cursor.position(), 0);
+    let mut data = Vec::new();
+    assert_eq!(cursor.read_to_end(&mut data).unwrap(), 0x1006);
+    assert_eq!(data, srecord_file.to_binary_range(0x0000..0x1006, 0x00));
+    assert_eq!(cursor.read(&mut [0; 4]).unwrap(), 0);
+
+    // Reads are cut at the end of the data
+    assert_eq!(cursor.seek(SeekFrom::End(-3)).unwrap(), 0x1003);
+    let mut buf = [0x55; 4];
+    assert_eq!(cursor.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [0x00, 0xCC, 0xDD, 0x55]);
+    assert_eq!(cursor.position(), 0x1006);
+
+    assert_eq!(cursor.seek(SeekFrom::Current(-5)).unwrap(), 0x1001);
+    let mut buf = [0; 4];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0xBB, 0x00, 0x00, 0xCC]);
+    cursor.set_position(0x0FFF);
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0xAA, 0xBB, 0x00]);
+
+    // Seeking past the end is allowed, but seeking before address 0 is an error
+    assert_eq!(cursor.seek(SeekFrom::Start(0x2000)).unwrap(), 0x2000);
+    assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+    assert!(cursor.seek(SeekFrom::End(-0x1007)).is_err());
+    assert!(cursor.seek(SeekFrom::Current(-0x2001)).is_err());
+    assert_eq!(cursor.position(), 0x2000);
+
+    let srecord_file = SRecordFile::new();
+    let mut cursor = srecord_file.cursor(0xFF);
+    assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+    assert_eq!(cursor.seek(SeekFrom::End(0)).unwrap(), 0);
+}
+
+#[test]
+fn test_srecord_cursor_mut() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let mut srecord_file = SRecordFile::from_str("S1051000AABB85").unwrap();
+    let mut cursor = srecord_file.cursor_mut();
+    assert_eq!(cursor.position(), 0);
+    assert_eq!(cursor.seek(SeekFrom::End(0)).unwrap(), 0x1002);
+    cursor.write_all(&[0x01, 0x02]).unwrap();
+    assert_eq!(cursor.seek(SeekFrom::Current(-3)).unwrap(), 0x1001);
+    cursor.write_all(&[0xCC]).unwrap();
+    cursor.set_position(0x2000);
+    write!(cursor, "{:04X}", 0xABCD).unwrap();
+    assert_eq!(cursor.position(), 0x2004);
+    cursor.flush().unwrap();
+    assert!(cursor.seek(SeekFrom::Current(-0x2005)).is_err());
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file[0x1000..0x1004], [0xAA, 0xCC, 0x01, 0x02]);
+    assert_eq!(srecord_file[0x2000..0x2004], *b"ABCD");
+
+    // Writes are cut before address u64::MAX
+    let mut srecord_file = SRecordFile::new();
+    let mut cursor = srecord_file.cursor_mut();
+    cursor.set_position(u64::MAX - 2);
+    assert_eq!(cursor.write(&[0x11, 0x22, 0x33]).unwrap(), 2);
+    assert!(cursor.write_all(&[0x44]).is_err());
+    assert_eq!(srecord_file[u64::MAX - 2..u64::MAX], [0x11, 0x22]);
+
+    // Data written through the cursor is read back through the read cursor
+    let mut srecord_file = SRecordFile::new();
+    let mut cursor = srecord_file.cursor_mut();
+    cursor.seek(SeekFrom::Start(0x10)).unwrap();
+    std::io::copy(&mut &[0x5A; 0x100][..], &mut cursor).unwrap();
+    let mut cursor = srecord_file.cursor(0x00);
+    cursor.set_position(0x10);
+    let mut data = Vec::new();
+    std::io::Read::read_to_end(&mut cursor, &mut data).unwrap();
+    assert_eq!(data, [0x5A; 0x100]);
+}
+
+#[test]
+fn test_srecord_file_allocating_mut() {
+    let mut srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004CCDD3D").unwrap();
+
+    // Existing data is returned unchanged
+    assert_eq!(*srecord_file.at_mut(0x1001), 0xBB);
+    assert_eq!(srecord_file.range_mut(0x1004..0x1006), [0xCC, 0xDD]);
+    assert_eq!(srecord_file.chunk_count(), 2);
+
+    // Missing data is allocated, merging data chunks
+    *srecord_file.at_mut(0x1002) = 0x11;
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(
+        srecord_file.range_mut(0x1001..0x1005),
+        [0xBB, 0x11, 0x00, 0xCC]
+    );
+    assert_eq!(srecord_file.chunk_count(), 1);
+    srecord_file
+        .range_mut_with_fill(0x0FFC..0x1008, 0xFF)
+        .iter_mut()
+        .for_each(|byte| *byte = byte.wrapping_add(1));
+    assert_eq!(
+        srecord_file[0x0FFC..0x1008],
+        [0x00, 0x00, 0x00, 0x00, 0xAB, 0xBC, 0x12, 0x01, 0xCD, 0xDE, 0x00, 0x00]
+    );
+    assert!(srecord_file.range_mut(0x2000..0x2000).is_empty());
+    assert_eq!(srecord_file.chunk_count(), 1);
+    *srecord_file.at_mut(u64::MAX - 1) = 0x55;
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file[u64::MAX - 1], 0x55);
+}
+
+#[test]
+fn test_srecord_file_index_range_types() {
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    assert_eq!(srecord_file[0x1000..=0x1003], [0x00, 0x01, 0x02, 0x03]);
+    assert_eq!(srecord_file[0x1001..], [0x01, 0x02, 0x03]);
+    assert_eq!(srecord_file[..0x1002], [0x00, 0x01]);
+    assert_eq!(srecord_file[0x2001..], [0xBB]);
+    assert_eq!(srecord_file[..0x2002], [0xAA, 0xBB]);
+    assert!(srecord_file.get(0x1000..=0x1004).is_none());
+    assert!(srecord_file.get(0x0FFF..=0x1000).is_none());
+    assert!(srecord_file.get(0x1004..).is_none());
+    assert!(srecord_file.get(..0x1005).is_none());
+    assert!(srecord_file.get(..0).is_none());
+    assert!(srecord_file.get(..).is_none());
+    assert!(srecord_file.get(0..=u64::MAX).is_none());
+
+    srecord_file[0x1000..=0x1001].fill(0xFF);
+    srecord_file[0x1003..].fill(0xEE);
+    srecord_file[..0x2001].fill(0xDD);
+    assert_eq!(srecord_file[0x1000..0x1004], [0xFF, 0xFF, 0x02, 0xEE]);
+    assert_eq!(srecord_file[0x2000..0x2002], [0xDD, 0xBB]);
+    srecord_file.erase(0x2000..0x2002);
+    srecord_file[..].fill(0x11);
+    assert_eq!(srecord_file[..], [0x11; 4]);
+    assert_eq!(SRecordFile::new()[..], []);
+
+    let data_chunk = &mut srecord_file.data_chunks[0];
+    assert_eq!(data_chunk.get(0x1001..=0x1002).unwrap(), [0x11, 0x11]);
+    assert_eq!(data_chunk.get(0x1003..).unwrap(), [0x11]);
+    assert_eq!(data_chunk.get(..0x1001).unwrap(), [0x11]);
+    assert_eq!(data_chunk.get(..).unwrap(), [0x11; 4]);
+    assert!(data_chunk.get(0x1004..).unwrap().is_empty());
+    assert!(data_chunk.get(..0x1000).unwrap().is_empty());
+    assert!(data_chunk.get(0x1000..=0x1004).is_none());
+    assert!(data_chunk.get(0x1005..).is_none());
+    assert!(data_chunk.get(..0x0FFF).is_none());
+    data_chunk.get_mut(0x1002..).unwrap().fill(0x22);
+    assert_eq!(data_chunk.data, [0x11, 0x11, 0x22, 0x22]);
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_index_range_full_error() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2\nS1052000AABB75").unwrap();
+    let _ = &srecord_file[..];
+}
+
+#[test]
+fn test_srecord_file_write_int() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.write_u32_le(0x1000, 0x04030201);
+    srecord_file.write_u32_be(0x1004, 0x05060708);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(
+        srecord_file[0x1000..0x1008],
+        [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+    );
+
+    // Writes overwrite existing data, and bridge gaps between data chunks
+    srecord_file.write_u64_le(0x100A, 0x1122334455667788);
+    assert_eq!(srecord_file.chunk_count(), 2);
+    srecord_file.write_u16_be(0x1008, 0xAABB);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file.read_u64_le(0x100A), Some(0x1122334455667788));
+    srecord_file.write_u16_le(0x1000, 0xCCDD);
+    srecord_file.write_u64_be(0x2000, 0x0102030405060708);
+    assert_eq!(srecord_file[0x1000..0x1002], [0xDD, 0xCC]);
+    assert_eq!(srecord_file.read_u64_be(0x2000), Some(0x0102030405060708));
+
+    for size in 1..=8 {
+        let value = 0x0102030405060708 >> (64 - 8 * size);
+        let mut srecord_file = SRecordFile::new();
+        srecord_file.write_int(0x10, size, value, Endianness::Little);
+        srecord_file.write_int(0x20, size, value, Endianness::Big);
+        assert_eq!(srecord_file.total_data_len(), 2 * size as u64);
+        assert_eq!(
+            srecord_file.read_int(0x10, size, Endianness::Little),
+            Some(value)
+        );
+        assert_eq!(
+            srecord_file.read_int(0x20, size, Endianness::Big),
+            Some(value)
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_write_int_value_too_large() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.write_int(0x1000, 3, 0x01000000, Endianness::Little);
+}
+
+#[test]
+fn test_srecord_file_extend() {
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    srecord_file.extend(std::iter::empty::<(u64, u8)>());
+    assert_eq!(srecord_file.chunk_count(), 1);
+
+    // Bytes before, after and between existing data are merged into the existing chunks
+    srecord_file.extend([
+        (0x0FFF, 0xFF),
+        (0x1004, 0x04),
+        (0x1006, 0x06),
+        (0x1005, 0x05),
+    ]);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(
+        srecord_file[0x0FFF..0x1007],
+        [0xFF, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06]
+    );
+    srecord_file.extend([(0x1001, 0xAA), (0x2000, 0xBB)]);
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(srecord_file[0x1001], 0xAA);
+    assert_eq!(srecord_file[0x2000], 0xBB);
+
+    let data = [0x11; 0x10];
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.extend([
+        (0x1000, &data[..]),
+        (0x1010, &[]),
+        (0x1010, &data[..4]),
+        (0x0FF8, &[0x22; 0x0C]),
+    ]);
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file.address_range(), Some(0x0FF8..0x1014));
+    assert_eq!(srecord_file[0x1002..0x1006], [0x22, 0x22, 0x11, 0x11]);
+
+    // Scattered bytes overwrite existing data, and later bytes at the same address are kept
+    srecord_file.extend((0..0x40).rev().map(|offset| (0x0FF0 + 2 * offset, 0x33)));
+    srecord_file.extend([(0x0FF8, 0x44), (0x0FF8, 0x55)]);
+    assert_eq!(srecord_file.chunk_count(), 0x32);
+    assert_eq!(srecord_file[0x0FF8..0x0FFC], [0x55, 0x22, 0x33, 0x22]);
+    assert_eq!(srecord_file[0x1012..0x1015], [0x33, 0x11, 0x33]);
+}
+
+#[test]
+fn test_srecord_file_header_info() {
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.header_str(), None);
+    assert_eq!(srecord_file.header_info(), None);
+
+    srecord_file.header_data = Some(vec![b'A', 0xFF, b'B']);
+    assert_eq!(srecord_file.header_str().unwrap(), "A\u{FFFD}B");
+
+    // Short headers only fill the leading fields
+    srecord_file.set_header("firmware\0\0");
+    let header_info = srecord_file.header_info().unwrap();
+    assert_eq!(header_info.module_name, "firmware");
+    assert_eq!(header_info.version, "");
+    assert_eq!(header_info.revision, "");
+    assert_eq!(header_info.description, "");
+
+    let header_info = HeaderInfo {
+        module_name: String::from("a module name longer than 20 bytes"),
+        version: String::from("123"),
+        revision: String::from("4"),
+        description: "x".repeat(40),
+    };
+    srecord_file.set_header_info(&header_info);
+    let header_data = srecord_file.header_data.as_ref().unwrap();
+    assert_eq!(header_data.len(), 60);
+    assert_eq!(&header_data[..24], b"a module name longer124 ");
+    let parsed_header_info = srecord_file.header_info().unwrap();
+    assert_eq!(parsed_header_info.module_name, "a module name longer");
+    assert_eq!(parsed_header_info.version, "12");
+    assert_eq!(parsed_header_info.revision, "4");
+    assert_eq!(parsed_header_info.description, "x".repeat(36));
+    assert_eq!(
+        HeaderInfo::from_bytes(&parsed_header_info.to_bytes()),
+        parsed_header_info
+    );
+}
+
+#[test]
+fn test_srecord_file_validate_start_address() {
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    assert_eq!(srecord_file.validate_start_address(), Ok(()));
+    assert!(srecord_file
+        .validate(&ValidationOptions::default())
+        .is_empty());
+
+    srecord_file.start_address = Some(0x1003);
+    assert_eq!(srecord_file.validate_start_address(), Ok(()));
+    srecord_file.start_address = Some(0x1004);
+    assert_eq!(
+        srecord_file.validate_start_address(),
+        Err(StartAddressError::NotInData(0x1004))
+    );
+
+    srecord_file.start_address = Some(0x1_0000_0000);
+    assert_eq!(
+        srecord_file.validate_start_address(),
+        Err(StartAddressError::TooLarge(0x1_0000_0000))
+    );
+    let issues = srecord_file.validate(&ValidationOptions::default());
+    assert_eq!(
+        issues,
+        [ValidationIssue::StartAddress(StartAddressError::TooLarge(
+            0x1_0000_0000
+        ))]
+    );
+    assert_eq!(issues[0].severity(), Severity::Error);
+}
+
+#[test]
+fn test_srecord_file_validate() {
+    let options = ValidationOptions::default();
+    let mut srecord_file = SRecordFile::new();
+    assert_eq!(srecord_file.validate(&options), [ValidationIssue::NoData]);
+    assert_eq!(
+        srecord_file.validate(&ValidationOptions {
+            require_header: true,
+            require_start_address: true,
+            ..ValidationOptions::default()
+        }),
+        [
+            ValidationIssue::NoData,
+            ValidationIssue::MissingHeader,
+            ValidationIssue::MissingStartAddress,
+        ]
+    );
+
+    // Broken invariants of programmatically built files
+    srecord_file.data_chunks = vec![
+        DataChunk {
+            address: 0x2000,
+            data: vec![0x01; 4],
+        },
+        DataChunk {
+            address: 0x2002,
+            data: vec![0x02; 4],
+        },
+        DataChunk {
+            address: 0x1000,
+            data: vec![],
+        },
+        DataChunk {
+            address: u64::MAX,
+            data: vec![0x03; 2],
+        },
+    ];
+    let issues = srecord_file.validate(&options);
+    assert_eq!(
+        issues,
+        [
+            ValidationIssue::OverlappingDataChunks(0x2002..0x2004),
+            ValidationIssue::EmptyDataChunk(2),
+            ValidationIssue::UnsortedDataChunks(2),
+            ValidationIssue::DataChunkAddressOverflow(3),
+        ]
+    );
+    assert_eq!(issues[1].severity(), Severity::Warning);
+    assert!(issues
+        .iter()
+        .filter(|issue| **issue != ValidationIssue::EmptyDataChunk(2))
+        .all(|issue| issue.severity() == Severity::Error));
+
+    // Limits of the selected output format
+    let mut srecord_file = SRecordFile::from_str("S20801000001020304EC").unwrap();
+    srecord_file.header_data = Some(b"HDR".to_vec());
+    srecord_file.start_address = Some(0x010000);
+    let mut options = ValidationOptions {
+        require_header: true,
+        require_start_address: true,
+        ..ValidationOptions::default()
+    };
+    assert!(srecord_file.validate(&options).is_empty());
+    options.serialization_options.data_record_type = DataRecordType::S1;
+    assert_eq!(
+        srecord_file.validate(&options),
+        [ValidationIssue::Serialization(
+            SerializationError::AddressTooLargeForFormat(0x010003)
+        )]
+    );
+
+    let srecord_file = SRecordFile::from_binary(0, &vec![0; 16 << 16]);
+    let mut serialization_options = SerializationOptions::default();
+    serialization_options.count_record_type = CountRecordType::S5;
+    let options = ValidationOptions {
+        serialization_options,
+        ..ValidationOptions::default()
+    };
+    assert_eq!(
+        srecord_file.validate(&options),
+        [ValidationIssue::Serialization(
+            SerializationError::RecordCountTooLarge(1 << 16)
+        )]
+    );
+
+    // An invalid start address is only reported once
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    srecord_file.start_address = Some(0x1_0000_0000);
+    assert_eq!(
+        srecord_file.validate(&ValidationOptions::default()),
+        [ValidationIssue::StartAddress(StartAddressError::TooLarge(
+            0x1_0000_0000
+        ))]
+    );
+}
+
+#[test]
+fn test_srecord_file_normalize() {
+    let mut srecord_file = SRecordFile::new();
+    srecord_file.normalize().unwrap();
+    assert!(srecord_file.is_empty());
+
+    srecord_file.data_chunks = vec![
+        DataChunk {
+            address: 0x2000,
+            data: vec![0x03],
+        },
+        DataChunk {
+            address: 0x1002,
+            data: vec![0x02],
+        },
+        DataChunk {
+            address: 0x1001,
+            data: vec![],
+        },
+        DataChunk {
+            address: 0x1000,
+            data: vec![0x00, 0x01],
+        },
+    ];
+    srecord_file.normalize().unwrap();
+    assert_eq!(
+        srecord_file.data_chunks,
+        [
+            DataChunk {
+                address: 0x1000,
+                data: vec![0x00, 0x01, 0x02],
+            },
+            DataChunk {
+                address: 0x2000,
+                data: vec![0x03],
+            },
+        ]
+    );
+    assert!(srecord_file
+        .validate(&ValidationOptions::default())
+        .is_empty());
+
+    // Errors leave the data chunks unchanged
+    let mut overlapping = srecord_file.clone();
+    overlapping.data_chunks.insert(
+        0,
+        DataChunk {
+            address: 0x1FFF,
+            data: vec![0xAA; 4],
+        },
+    );
+    let data_chunks = overlapping.data_chunks.clone();
+    assert_eq!(
+        overlapping.normalize(),
+        Err(NormalizeError::OverlappingData(0x2000..0x2001))
+    );
+    assert_eq!(overlapping.data_chunks, data_chunks);
+
+    srecord_file.data_chunks.push(DataChunk {
+        address: u64::MAX,
+        data: vec![0xAA; 2],
+    });
+    assert_eq!(
+        srecord_file.normalize(),
+        Err(NormalizeError::AddressOverflow(u64::MAX))
+    );
+    assert_eq!(srecord_file.chunk_count(), 3);
+}
+
+#[test]
+fn test_srecord_file_bridge_gaps() {
+    let srecord_str = "S1051000AABB85\nS1051004AABB81\nS1051008AABB7D\nS1051100CCDD40";
+    let mut srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    srecord_file.bridge_gaps(0, 0x00).unwrap();
+    assert_eq!(srecord_file.chunk_count(), 4);
+    // Gaps exactly `max_gap` bytes long are not bridged
+    srecord_file.bridge_gaps(2, 0x00).unwrap();
+    assert_eq!(srecord_file.chunk_count(), 4);
+    srecord_file.bridge_gaps(3, 0x00).unwrap();
+    assert_eq!(srecord_file.chunk_count(), 2);
+    assert_eq!(
+        srecord_file[0x1000..0x100A],
+        [0xAA, 0xBB, 0x00, 0x00, 0xAA, 0xBB, 0x00, 0x00, 0xAA, 0xBB]
+    );
+    srecord_file.bridge_gaps(0xF6, 0xFF).unwrap();
+    assert_eq!(srecord_file.data_chunks[0].end_address(), 0x100A);
+    srecord_file.bridge_gaps(0xF7, 0xFF).unwrap();
+    assert_eq!(srecord_file.chunk_count(), 1);
+    assert_eq!(srecord_file[0x100A..0x1100], [0xFF; 0xF6]);
+    assert_eq!(srecord_file[0x1100..0x1102], [0xCC, 0xDD]);
+
+    // Same result as filling the gaps while parsing
+    let mut options = ParseOptions::default();
+    options.gap_fill = Some(GapFill {
+        max_gap: 2,
+        value: 0xFF,
+    });
+    let (parsed, _) = SRecordFile::from_str_with_options(srecord_str, &options).unwrap();
+    let mut srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    srecord_file.bridge_gaps(3, 0xFF).unwrap();
+    assert_eq!(srecord_file, parsed);
+
+    // Data chunks out of order are sorted first
+    srecord_file.data_chunks.reverse();
+    srecord_file.bridge_gaps(0x100, 0xFF).unwrap();
+    assert_eq!(srecord_file.chunk_count(), 1);
+}
+
+#[test]
+fn test_srecord_file_bridge_gaps_overlapping() {
+    let mut srecord_file = SRecordFile::from_str("S1051000AABB85\nS1051004AABB81").unwrap();
+    srecord_file.data_chunks.push(DataChunk {
+        address: 0x1001,
+        data: vec![0x00],
+    });
+    let expected = srecord_file.clone();
+    assert_eq!(
+        srecord_file.bridge_gaps(0x10, 0xFF),
+        Err(NormalizeError::OverlappingData(0x1001..0x1002))
+    );
+    assert_eq!(srecord_file, expected);
+}
+
+#[test]
+fn test_srecord_file_bitwise_ranges() {
+    let srecord_str = "S107100000010203E2\nS1052000AABB75\nS1053000CCDD21";
+    let original = SRecordFile::from_str(srecord_str).unwrap();
+    let key = [0x12, 0x34, 0x56];
+
+    let mut srecord_file = original.clone();
+    srecord_file.xor_range(0x1002..0x2002, &key);
+    assert_eq!(
+        srecord_file[0x1000..0x1004],
+        [0x00, 0x01, 0x02 ^ 0x12, 0x03 ^ 0x34]
+    );
+    // 0x2000 is 0xFFE bytes after 0x1002, and 0xFFE % 3 == 2
+    assert_eq!(srecord_file[0x2000..0x2002], [0xAA ^ 0x56, 0xBB ^ 0x12]);
+    assert_eq!(srecord_file[0x3000..0x3002], [0xCC, 0xDD]);
+    srecord_file.xor_range(0x1002..0x2002, &key);
+    assert_eq!(srecord_file, original);
+
+    let mut srecord_file = original.clone();
+    srecord_file.and_range(0..u64::MAX, &[0xF0, 0x0F]);
+    assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x00, 0x03]);
+    assert_eq!(srecord_file[0x2000..0x2002], [0xA0, 0x0B]);
+    srecord_file.or_range(0x3001..0x3002, &[0x22]);
+    assert_eq!(srecord_file[0x3000..0x3002], [0xC0, 0x2F]);
+
+    let mut srecord_file = original.clone();
+    srecord_file.not_range(0x1003..0x3001);
+    assert_eq!(srecord_file[0x1000..0x1004], [0x00, 0x01, 0x02, 0xFC]);
+    assert_eq!(srecord_file[0x2000..0x2002], [0x55, 0x44]);
+    assert_eq!(srecord_file[0x3000..0x3002], [0x33, 0xDD]);
+    srecord_file.not_range(0x1003..0x3001);
+    assert_eq!(srecord_file, original);
+
+    // Nothing is allocated in gaps
+    srecord_file.xor_range(0x4000..0x5000, &key);
+    srecord_file.not_range(0x4000..0x5000);
+    assert_eq!(srecord_file, original);
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_xor_range_empty_key() {
+    let mut srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    srecord_file.xor_range(0x1000..0x1004, &[]);
+}
+
+#[test]
+fn test_srecord_file_byte_swap() {
+    let srecord_str = "S113100000112233445566778899AABBCCDDEEFFE4\nS1051100AABB84";
+    let original = SRecordFile::from_str(srecord_str).unwrap();
+    let mut srecord_file = original.clone();
+    srecord_file.byte_swap(0x1000..0x1010, 8).unwrap();
+    assert_eq!(
+        srecord_file[0x1000..0x1010],
+        [
+            0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00, 0xFF, 0xEE, 0xDD, 0xCC, 0xBB, 0xAA,
+            0x99, 0x88
+        ]
+    );
+    srecord_file.byte_swap(0x1000..0x1010, 8).unwrap();
+    assert_eq!(srecord_file, original);
+    srecord_file.byte_swap(0x1002..0x1004, 2).unwrap();
+    assert_eq!(
+        srecord_file[0x1000..0x1006],
+        [0x00, 0x11, 0x33, 0x22, 0x44, 0x55]
+    );
+    srecord_file.byte_swap(0x1100..0x1100, 2).unwrap();
+
+    let mut srecord_file = original.clone();
+    for word_size in [0, 1, 3, 16] {
+        assert_eq!(
+            srecord_file.byte_swap(0x1000..0x1010, word_size),
+            Err(ByteSwapError::InvalidWordSize)
+        );
+    }
+    assert_eq!(
+        srecord_file.byte_swap(0x1002..0x1006, 4),
+        Err(ByteSwapError::UnalignedRange)
+    );
+    assert_eq!(
+        srecord_file.byte_swap(0x1000..0x1006, 4),
+        Err(ByteSwapError::UnalignedRange)
+    );
+    assert_eq!(
+        srecord_file.byte_swap(0x1008..0x1108, 8),
+        Err(ByteSwapError::MissingData(0x1010..0x1100))
+    );
+    assert_eq!(
+        srecord_file.byte_swap(0x1100..0x1104, 2),
+        Err(ByteSwapError::MissingData(0x1102..0x1104))
+    );
+    assert_eq!(srecord_file, original);
+}
+
+#[test]
+fn test_srecord_file_find() {
+    let srecord_str = "S1071000AABBAABB1E\nS1051004CCDD3D\nS1051010AABB75";
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    assert_eq!(srecord_file.find(&[0xAA, 0xBB]), Some(0x1000));
+    assert_eq!(srecord_file.rfind(&[0xAA, 0xBB]), Some(0x1010));
+    let matches: Vec<u64> = srecord_file.find_all(&[0xAA, 0xBB]).collect();
+    assert_eq!(matches, [0x1000, 0x1002, 0x1010]);
+    let matches: Vec<u64> = srecord_file.find_all(&[0xAA, 0xBB]).rev().collect();
+    assert_eq!(matches, [0x1010, 0x1002, 0x1000]);
+
+    // Contiguous data is searched across record boundaries, but gaps are never matched
+    assert_eq!(srecord_file.find(&[0xBB, 0xCC]), Some(0x1003));
+    assert_eq!(srecord_file.find(&[0xDD, 0xAA]), None);
+    assert_eq!(srecord_file.find(&[0xAA; 32]), None);
+    assert_eq!(srecord_file.find(&[]), None);
+    assert_eq!(srecord_file.rfind(&[]), None);
+    assert_eq!(SRecordFile::new().find(&[0x00]), None);
+
+    assert_eq!(
+        srecord_file.find_masked(&[0xAA, 0x00, 0xAA], &[0xFF, 0x00, 0xFF]),
+        Some(0x1000)
+    );
+    assert_eq!(
+        srecord_file.rfind_masked(&[0x0B, 0x0C], &[0x0F, 0x0F]),
+        Some(0x1003)
+    );
+    let matches: Vec<u64> = srecord_file
+        .find_all_masked(&[0x00, 0x00], &[0x00, 0x00])
+        .collect();
+    assert_eq!(matches, [0x1000, 0x1001, 0x1002, 0x1003, 0x1004, 0x1010]);
+}
+
+#[test]
+#[should_panic]
+fn test_srecord_file_find_masked_length_mismatch() {
+    let srecord_file = SRecordFile::from_str("S107100000010203E2").unwrap();
+    srecord_file.find_masked(&[0x00, 0x01], &[0xFF]);
+}
+
+#[test]
+fn test_srecord_file_diff() {
+    let old = SRecordFile::from_str("S10B10000001020304050607C8\nS1052000AABB75").unwrap();
+    assert!(old.diff(&old).is_empty());
+    assert!(old.diff(&old.clone().with_header("NEW")).is_empty());
+
+    let mut new = old.clone();
+    new.erase(0x1000..0x1002);
+    new.set_data(0x1003, &[0xF3, 0xF4]);
+    new.set_data(0x1006, &[0xF6]);
+    new.set_data(0x2002, &[0xCC, 0xDD]);
+    new.set_data(0x3000, &[0xEE]);
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.only_in_self,
+        [DataChunk {
+            address: 0x1000,
+            data: vec![0x00, 0x01],
+        }]
+    );
+    assert_eq!(
+        diff.only_in_other,
+        [
+            DataChunk {
+                address: 0x2002,
+                data: vec![0xCC, 0xDD],
+            },
+            DataChunk {
+                address: 0x3000,
+                data: vec![0xEE],
+            },
+        ]
+    );
+    assert_eq!(
+        diff.changed,
+        [
+            ChangedData {
+                address: 0x1003,
+                old_data: vec![0x03, 0x04],
+                new_data: vec![0xF3, 0xF4],
+            },
+            ChangedData {
+                address: 0x1006,
+                old_data: vec![0x06],
+                new_data: vec![0xF6],
+            },
+        ]
+    );
+    assert_eq!(diff.changed[0].range(), 0x1003..0x1005);
+
+    // The reverse diff swaps the roles of the files
+    let reverse_diff = new.diff(&old);
+    assert_eq!(reverse_diff.only_in_self, diff.only_in_other);
+    assert_eq!(reverse_diff.only_in_other, diff.only_in_self);
+    assert_eq!(reverse_diff.changed[1].old_data, [0xF6]);
+    assert_eq!(reverse_diff.changed[1].new_data, [0x06]);
+
+    let diff = SRecordFile::new().diff(&old);
+    assert_eq!(diff.only_in_other, old.data_chunks);
+    assert!(diff.only_in_self.is_empty() && diff.changed.is_empty());
+}
+
+#[test]
+fn test_srecord_file_apply_diff() {
+    let old = SRecordFile::from_str("S10B10000001020304050607C8\nS1052000AABB75").unwrap();
+    let mut new = old.clone().with_header("NEW");
+    new.erase(0x1000..0x1002);
+    new.set_data(0x1003, &[0xF3, 0xF4]);
+    new.set_data(0x2002, &[0xCC, 0xDD]);
+    new.set_data(0x3000, &[0xEE]);
+
+    let diff = old.diff(&new);
+    let mut patched = old.clone();
+    patched.apply(&diff).unwrap();
+    assert_eq!(patched.data_chunks, new.data_chunks);
+    assert_eq!(patched.header_data, None);
+    // The reverse diff restores the original data
+    patched.apply(&new.diff(&old)).unwrap();
+    assert_eq!(patched, old);
+    patched.apply(&SRecordDiff::default()).unwrap();
+    assert_eq!(patched, old);
+
+    // Files that do not match the old data of the diff are left unchanged
+    let mut modified = old.clone();
+    modified[0x1004] = 0x00;
+    let expected = modified.clone();
+    assert_eq!(
+        modified.apply(&diff),
+        Err(ApplyDiffError::DataMismatch(0x1003..0x1005))
+    );
+    let mut modified = old.clone();
+    modified.erase(0x1001..0x1002);
+    assert_eq!(
+        modified.apply(&diff),
+        Err(ApplyDiffError::DataMismatch(0x1000..0x1002))
+    );
+    let mut modified = expected.clone();
+    modified[0x1004] = 0x04;
+    modified.set_data(0x3000, &[0x00]);
+    assert_eq!(
+        modified.apply(&diff),
+        Err(ApplyDiffError::UnexpectedData(0x3000..0x3001))
+    );
+    assert_eq!(modified.get(0x3000), Some(&0x00));
+}
+
+#[test]
+fn test_srecord_file_overlay() {
+    let base_str = "S00600004844521B\nS10B10000001020304050607C8\nS1052000AABB75\nS9031000EC";
+    let mut srecord_file = SRecordFile::from_str(base_str).unwrap();
+    let mut patch = SRecordFile::new().with_header("PATCH");
+    patch.set_data(0x0FFE, &[0xF0, 0xF1, 0xF2]);
+    patch.set_data(0x1006, &[0xF6, 0xF7, 0xF8]);
+    patch.set_data(0x2000, &[0xAA, 0xFF]);
+    patch.set_data(0x3000, &[0xEE]);
+    srecord_file.overlay(&patch);
+    assert_eq!(
+        srecord_file[0x0FFE..0x1009],
+        [0xF0, 0xF1, 0xF2, 0x01, 0x02, 0x03, 0x04, 0x05, 0xF6, 0xF7, 0xF8]
+    );
+    assert_eq!(srecord_file[0x2000..0x2002], [0xAA, 0xFF]);
+    assert_eq!(srecord_file[0x3000], 0xEE);
+    assert_eq!(srecord_file.chunk_count(), 3);
+    assert_eq!(srecord_file.header_data, Some(b"HDR".to_vec()));
+    assert_eq!(srecord_file.start_address, Some(0x1000));
+
+    // Same data as merging with OverlapPolicy::Overwrite
+    let mut merged = SRecordFile::from_str(base_str).unwrap();
+    merged.merge(patch, OverlapPolicy::Overwrite).unwrap();
+    assert_eq!(merged.data_chunks, srecord_file.data_chunks);
+}
+
+#[test]
+fn test_checksum() {
+    let srecord_str = "S107100000010203E2\nS1052000AABB75";
+    let srecord_file = SRecordFile::from_str(srecord_str).unwrap();
+    // Gaps before, between and after the data are filled
+    let range = 0x0FF0..0x2010;
+    let binary = srecord_file.to_binary_range(range.clone(), 0xFF);
+    let binary_file = SRecordFile::from_binary(0, &binary);
+    let binary_range = 0..binary.len() as u64;
+    assert_eq!(
+        checksum::crc32(&srecord_file, range.clone(), 0xFF),
+        checksum::crc32(&binary_file, binary_range.clone(), 0x00)
+    );
+    assert_eq!(
+        checksum::crc16_ccitt(&srecord_file, range.clone(), 0xFF),
+        checksum::crc16_ccitt(&binary_file, binary_range.clone(), 0x00)
+    );
+    assert_eq!(
+        checksum::crc8(&srecord_file, range.clone(), 0xFF),
+        checksum::crc8(&binary_file, binary_range.clone(), 0x00)
+    );
+    let sum: u64 = binary.iter().map(|&byte| byte as u64).sum();
+    assert_eq!(
+        checksum::sum32(&srecord_file, range.clone(), 0xFF),
+        sum as u32
+    );
+    assert_eq!(
+        checksum::sum16(&srecord_file, range.clone(), 0xFF),
+        sum as u16
+    );
+    assert_eq!(checksum::sum8(&srecord_file, range, 0xFF), sum as u8);
+
+    // Only the data inside the range is used
+    assert_eq!(checksum::sum32(&srecord_file, 0x1001..0x1003, 0xFF), 0x03);
+    assert_eq!(checksum::sum32(&srecord_file, 0x1004..0x1006, 0x10), 0x20);
+
+    // Empty ranges have the initial values
+    assert_eq!(checksum::crc32(&srecord_file, 0x1000..0x1000, 0xFF), 0);
+    assert_eq!(
+        checksum::crc16_ccitt(&srecord_file, 0x1002..0x1002, 0xFF),
+        0xFFFF
+    );
+    assert_eq!(checksum::crc8(&SRecordFile::new(), 0..0, 0xFF), 0);
+    assert_eq!(checksum::sum8(&SRecordFile::new(), 0..0, 0xFF), 0);
+}
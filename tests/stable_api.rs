@@ -0,0 +1,89 @@
+//! Pins the signatures of the stable API. Every item in this file must keep compiling unchanged
+//! until the next major version; APIs that are still evolving are behind the `unstable` feature
+//! and are not listed here.
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use srex::srecord::utils::{calculate_checksum, validate_record_bytes};
+use srex::srecord::*;
+
+type ParseResult = Result<(SRecordFile, ParseStats), SRecordParseError>;
+
+#[test]
+fn test_stable_srecord_file_api() {
+    let _: fn() -> SRecordFile = SRecordFile::new;
+    let _: fn(&str) -> Result<SRecordFile, SRecordParseError> = SRecordFile::from_str;
+    let _: fn(&str, &ParseOptions) -> ParseResult = SRecordFile::from_str_with_options;
+    let _: fn(&[u8]) -> Result<SRecordFile, SRecordParseError> = SRecordFile::from_bytes;
+    let _: fn(&[u8], &ParseOptions) -> ParseResult = SRecordFile::from_bytes_with_options;
+    let _: fn(&[u8], &ParseOptions) -> ParseResult = SRecordFile::from_bytes_lossy;
+
+    let _: fn(&SRecordFile, u64) -> Option<&u8> = SRecordFile::get::<u64>;
+    let _: fn(&SRecordFile, Range<u64>) -> Option<&[u8]> = SRecordFile::get::<Range<u64>>;
+    let _: fn(&mut SRecordFile, u64) -> Option<&mut u8> = SRecordFile::get_mut::<u64>;
+    let _: fn(&mut SRecordFile, Range<u64>) -> Option<&mut [u8]> =
+        SRecordFile::get_mut::<Range<u64>>;
+    let _: fn(&mut SRecordFile, u64, &[u8]) = SRecordFile::set_data::<u64>;
+    let _: fn(&mut SRecordFile, Range<u64>, u8) = SRecordFile::fill::<u64>;
+    let _: fn(&mut SRecordFile, Range<u64>) = SRecordFile::erase::<u64>;
+    let _: fn(&SRecordFile) -> Result<String, SerializationError> = SRecordFile::serialize_original;
+    let _: fn(&SRecordFile) -> RecordType = SRecordFile::minimal_record_type;
+    let _: fn(&mut SRecordFile, SRecordFile, OverlapPolicy) -> Result<(), MergeError> =
+        SRecordFile::merge;
+    let _: fn(&mut SRecordFile, i64) -> Result<(), OffsetError> = SRecordFile::offset;
+    let _: fn(&mut SRecordFile, &[Range<u64>]) = SRecordFile::crop;
+    let _: fn(&SRecordFile, &[Range<u64>]) -> SRecordFile = SRecordFile::cropped;
+    let _: fn(&SRecordFile, u64) -> (SRecordFile, SRecordFile) = SRecordFile::split_at;
+
+    // Functions returning iterators are pinned through their item types
+    let srecord_file = SRecordFile::new();
+    let _: Result<Vec<Record>, SerializationError> =
+        srecord_file.iter_records(16).map(Iterator::collect);
+    let _: Result<Vec<Record>, SerializationError> = srecord_file
+        .iter_records_with_options(&SerializationOptions::default())
+        .map(Iterator::collect);
+    let _: Vec<Range<u64>> = srecord_file.gaps().collect();
+    let _: Option<&RecordLayout> = srecord_file.record_layout();
+
+    let SRecordFile {
+        header_data,
+        data_chunks,
+        start_address,
+        ..
+    } = srecord_file;
+    let _: Option<Vec<u8>> = header_data;
+    let _: Vec<DataChunk> = data_chunks;
+    let _: Option<u64> = start_address;
+}
+
+#[test]
+fn test_stable_record_api() {
+    let _: for<'a> fn(&str, &'a mut [u8]) -> Result<Record<'a>, SRecordParseError> =
+        Record::from_str;
+    let _: for<'a> fn(&[u8], &'a mut [u8]) -> Result<Record<'a>, SRecordParseError> =
+        Record::from_bytes;
+    let _: fn(&Record<'static>) -> String = Record::serialize;
+    let _: fn(&RecordType) -> usize = RecordType::num_address_bytes;
+    let _: fn(&u8, &u64, &[u8]) -> u8 = calculate_checksum;
+    let _: fn(&[u8]) -> Result<RecordInfo, ErrorType> = validate_record_bytes;
+
+    let DataChunk { address, data } = DataChunk {
+        address: 0,
+        data: Vec::new(),
+    };
+    let _: u64 = address;
+    let _: Vec<u8> = data;
+    let _: fn(&DataChunk) -> u64 = DataChunk::end_address;
+
+    let DataRecord { address, data } = DataRecord {
+        address: 0,
+        data: &[],
+    };
+    let _: u64 = address;
+    let _: &[u8] = data;
+    let SRecordParseError { error_type } = SRecordParseError {
+        error_type: ErrorType::InvalidData,
+    };
+    let _: ErrorType = error_type;
+}